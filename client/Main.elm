@@ -8,14 +8,15 @@ import Mode.Audience
 import Mode.Landing
 import Mode.Presenter
 import Error exposing (Error)
+import Presentation
 import Question exposing (Question)
 import Ports exposing (scrollTop)
 import Resource exposing (Resource)
 
 
-main : Program Never Model Msg
+main : Program Flags Model Msg
 main =
-    Html.program
+    Html.programWithFlags
         { init = init
         , update = update
         , view = view
@@ -27,9 +28,17 @@ type Msg
     = AudienceModeMsg Mode.Audience.Msg
     | LandingModeMsg Mode.Landing.Msg
     | PresenterModeMsg Mode.Presenter.Msg
+    | JoinCodeFound (Result Http.Error Presentation.JoinResponse)
     | HideError
 
 
+{-| Values handed to the application by the page that embeds it.
+-}
+type alias Flags =
+    { location : String
+    }
+
+
 type ViewMode
     = Landing Mode.Landing.Model
     | Audience Mode.Audience.Model
@@ -42,8 +51,8 @@ type alias Model =
     }
 
 
-init : ( Model, Cmd Msg )
-init =
+init : Flags -> ( Model, Cmd Msg )
+init { location } =
     let
         ( landingModel, command ) =
             Mode.Landing.init
@@ -52,8 +61,16 @@ init =
             { error = Nothing
             , mode = Landing landingModel
             }
+
+        joinCommand =
+            case Presentation.joinCodeFromLocation location of
+                Just code ->
+                    Http.send JoinCodeFound (Presentation.join code)
+
+                Nothing ->
+                    Cmd.none
     in
-        ( model, Cmd.map LandingModeMsg command )
+        ( model, Cmd.batch [ Cmd.map LandingModeMsg command, joinCommand ] )
 
 
 update : Msg -> Model -> ( Model, Cmd Msg )
@@ -79,6 +96,21 @@ update msg model =
             in
                 ( { model | mode = Audience audModel }, Cmd.map AudienceModeMsg audCmd )
 
+        ( JoinCodeFound (Ok { presentation }), _ ) ->
+            case presentation of
+                Just presentationId ->
+                    let
+                        ( audModel, audCmd ) =
+                            Mode.Audience.init presentationId
+                    in
+                        ( { model | mode = Audience audModel }, Cmd.map AudienceModeMsg audCmd )
+
+                Nothing ->
+                    ( { model | error = Just "No open presentation has that join code." }, Cmd.none )
+
+        ( JoinCodeFound (Err _), _ ) ->
+            ( { model | error = Just "No open presentation has that join code." }, Cmd.none )
+
         ( LandingModeMsg (Mode.Landing.Login credentials), _ ) ->
             -- TODO - Login to the server
             let
@@ -136,6 +168,7 @@ view model =
         case model.mode of
             Landing landingModel ->
                 [ viewNav model
+                , viewError model
                 , Html.map LandingModeMsg <| Mode.Landing.view landingModel
                 ]
 
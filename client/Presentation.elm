@@ -1,11 +1,17 @@
 module Presentation
     exposing
         ( Presentation
+        , JoinResponse
+        , join
+        , joinCodeFromLocation
         )
 
 {-| A model of presentations and functions for managing them.
 -}
 
+import Http exposing (Request)
+import Json.Decode exposing (Decoder, field, string, maybe)
+import Config
 import Error exposing (Error)
 import Question exposing (Question)
 import Resource exposing (Resource)
@@ -19,3 +25,45 @@ type alias Presentation =
     , description : String
     , questions : Resource (List Question) Error
     }
+
+
+{-| Response type produced by a request to find the presentation a join code belongs to.
+-}
+type alias JoinResponse =
+    { error : Maybe String
+    , presentation : Maybe String
+    }
+
+
+{-| Produce an HTTP request that will find the ID of the open presentation with a join code.
+-}
+join : String -> Request JoinResponse
+join code =
+    let
+        url =
+            "http://" ++ Config.apiServerAddress ++ "/api/join/" ++ Http.encodeUri code
+    in
+        Http.get url joinResponse
+
+
+{-| Find the join code in a location hash of the form `#join/CODE`, which is where the QR codes
+shown on presenters' slides lead.
+-}
+joinCodeFromLocation : String -> Maybe String
+joinCodeFromLocation hash =
+    case String.split "/" hash of
+        [ "#join", code ] ->
+            if String.isEmpty code then
+                Nothing
+            else
+                Just code
+
+        _ ->
+            Nothing
+
+
+joinResponse : Decoder JoinResponse
+joinResponse =
+    Json.Decode.map2 JoinResponse
+        (field "error" (maybe string))
+        (field "presentation" (maybe (field "id" string)))
@@ -0,0 +1,149 @@
+use std::sync::Arc;
+
+use chrono::prelude::*;
+use iron::prelude::*;
+use iron::Handler;
+use iron::headers::Location;
+use iron::modifiers::Header;
+use iron::status;
+use url::form_urlencoded;
+use urlencoded::UrlEncodedBody;
+
+use auth::{self, Authorize};
+use auth::lti::{self, LtiPlatform};
+use capabilities::{Capability, Delete, Save, Search};
+use models::{Id, Audience, ExternalIdentity, LtiResourceLink, Membership, OidcLoginAttempt, Presentation, Presenter,
+             Role, Session};
+
+
+/// Handles resource link launches posted by a learning management system.
+///
+/// Instructors are signed in as the presenter linked to their platform account, which is one made
+/// for the account on its first launch until the instructor links it to their own. The first
+/// instructor to launch from a resource link creates the presentation it opens, and any other
+/// instructor launching from it becomes one of its presenters. Learners are issued an audience
+/// identity for the presentation.
+pub struct LaunchHandler<DB> {
+    database: DB,
+    platforms: Arc<Vec<LtiPlatform>>,
+    app_url: String,
+}
+
+#[derive(Debug, Serialize)]
+struct LaunchResponse {
+    pub error: Option<String>,
+}
+
+impl<DB> LaunchHandler<DB> {
+    pub fn new(db: DB, platforms: Arc<Vec<LtiPlatform>>, app_url: String) -> Self {
+        LaunchHandler {
+            database: db,
+            platforms: platforms,
+            app_url: app_url,
+        }
+    }
+}
+
+impl<DB> Handler for LaunchHandler<DB>
+    where DB: 'static + Sync + Send
+        + Capability<Search<OidcLoginAttempt>, Data = OidcLoginAttempt, Error = String>
+        + Capability<Delete<OidcLoginAttempt>, Data = (), Error = String>
+        + Capability<Search<ExternalIdentity>, Data = ExternalIdentity, Error = String>
+        + Capability<Save<ExternalIdentity>, Data = ExternalIdentity, Error = String>
+        + Capability<Search<Presenter>, Data = Presenter, Error = String>
+        + Capability<Save<Presenter>, Data = Presenter, Error = String>
+        + Capability<Search<LtiResourceLink>, Data = LtiResourceLink, Error = String>
+        + Capability<Save<LtiResourceLink>, Data = LtiResourceLink, Error = String>
+        + Capability<Search<Presentation>, Data = Presentation, Error = String>
+        + Capability<Save<Presentation>, Data = Presentation, Error = String>
+        + Capability<Save<Membership>, Data = Membership, Error = String>
+        + Capability<Save<Session>, Data = Session, Error = String>
+        + Capability<Save<Audience>, Data = Audience, Error = String>
+        + Authorize
+{
+    fn handle(&self, request: &mut Request) -> IronResult<Response> {
+        let params = request.get::<UrlEncodedBody>().unwrap_or_default();
+        let param = |name: &str| params.get(name).and_then(|values| values.first()).cloned();
+        let (id_token, state) = match (param("id_token"), param("state")) {
+            (Some(id_token), Some(state)) => (id_token, Id(state)),
+            _ => return json_response!(status::BadRequest, LaunchResponse {
+                error: Some("Missing or invalid request data.".to_string()),
+            }),
+        };
+        let db_result = try_do!({
+            let attempt = self.database.perform(Search(OidcLoginAttempt::search_parameter(state)))?;
+            let nonce = attempt.nonce.clone();
+            let expired = attempt.is_expired(Utc::now());
+            self.database.perform(Delete(attempt))?;
+            if expired {
+                return Err("Launch expired.".to_string());
+            }
+            let claims = lti::validate_launch(&self.platforms, &id_token, &nonce, Utc::now())?;
+            let to_find = LtiResourceLink::search_parameter(
+                claims.iss.clone(),
+                claims.deployment_id.clone(),
+                claims.resource_link.id.clone());
+            let existing_link = self.database.perform(Search(to_find));
+
+            if !claims.is_instructor() {
+                let link = existing_link.map_err(|_| "Your instructor has not set up this activity yet.".to_string())?;
+                let audience = self.database.perform(Save(Audience::new(claims.audience_id())))?;
+                let mut fragment = form_urlencoded::Serializer::new(String::new());
+                fragment
+                    .append_pair("presentation", &link.presentation.0)
+                    .append_pair("audience", &audience.id.0)
+                    .append_pair("submissionToken", &audience.submission_token);
+                return Ok(fragment.finish());
+            }
+
+            let identity = ExternalIdentity::search_parameter(claims.iss.clone(), claims.sub.clone());
+            let presenter_id = match self.database.perform(Search(identity)) {
+                Ok(identity) => identity.presenter,
+                Err(_) => {
+                    let presenter_id = claims.presenter_id();
+                    let presenter = match self.database.perform(Search(Presenter::search_parameter(presenter_id.clone()))) {
+                        Ok(presenter) => presenter,
+                        Err(_) => self.database.perform(Save(Presenter::from_identity_provider(presenter_id.0)))?,
+                    };
+                    let identity = ExternalIdentity::new(claims.iss.clone(), claims.sub.clone(), presenter.email_address);
+                    self.database.perform(Save(identity))?.presenter
+                },
+            };
+            let presentation_id = match existing_link {
+                Ok(link) => {
+                    let presentation = self.database.perform(Search(Presentation::search_parameter(link.presentation)))?;
+                    if auth::role_of(&self.database, &presenter_id, &presentation).is_none() {
+                        let membership = Membership::new(presentation.id.clone(), presenter_id.clone(), Role::Presenter);
+                        self.database.perform(Save(membership))?;
+                    }
+                    presentation.id
+                },
+                Err(_) => {
+                    let title = claims.resource_link.title.clone().unwrap_or("Untitled activity".to_string());
+                    let presentation = self.database.perform(Save(Presentation::new(presenter_id.clone(), title)))?;
+                    let link = LtiResourceLink::new(
+                        claims.iss.clone(),
+                        claims.deployment_id.clone(),
+                        claims.resource_link.id.clone(),
+                        presentation.id);
+                    self.database.perform(Save(link))?.presentation
+                },
+            };
+            let session = self.database.perform(Save(Session::new(Presenter::search_parameter(presenter_id))))?;
+            let mut fragment = form_urlencoded::Serializer::new(String::new());
+            fragment
+                .append_pair("presentation", &presentation_id.0)
+                .append_pair("sessionToken", &session.token.0);
+            Ok(fragment.finish())
+        });
+        match db_result {
+            Ok(fragment) => {
+                let url = format!("{}#{}", self.app_url, fragment);
+                Ok(Response::with((status::Found, Header(Location(url)))))
+            },
+            Err(err) => json_response!(status::BadRequest, LaunchResponse {
+                error: Some(err),
+            }),
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use std::error::Error;
+
+use iron::prelude::*;
+use iron::Handler;
+use iron::status;
+
+use auth::{self, Authenticate};
+use auth::lti;
+use capabilities::{Capability, Search, Update};
+use capabilities::sqlite::MergePlatformPresenter;
+use models::{Id, Scope, Session};
+
+
+/// Handles requests from an instructor to link their account on a learning management system to
+/// the presenter they sign in to AsQ as, so that launches sign them in as that presenter.
+///
+/// Launches never link accounts by email address, since platforms may claim any address. Instead
+/// the instructor proves they hold both accounts, by presenting a session of each.
+pub struct LinkHandler<DB> {
+    database: DB,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct LinkRequest {
+    #[serde(rename = "sessionToken")]
+    pub session_token: Id,
+    /// The session token issued by the launch, which signs in as the platform account's presenter.
+    #[serde(rename = "launchSessionToken")]
+    pub launch_session_token: Id,
+}
+
+#[derive(Debug, Serialize)]
+struct LinkResponse {
+    pub error: Option<String>,
+}
+
+impl<DB> LinkHandler<DB> {
+    pub fn new(db: DB) -> Self {
+        LinkHandler {
+            database: db,
+        }
+    }
+}
+
+impl<DB> Handler for LinkHandler<DB>
+    where DB: 'static + Sync + Send
+        + Capability<Update<MergePlatformPresenter>, Data = (), Error = String>
+        + Authenticate
+{
+    fn handle(&self, request: &mut Request) -> IronResult<Response> {
+        let request_data = decode_body_or_write_error!(request, LinkRequest, |_: Option<&Error>| LinkResponse {
+            error: Some("Missing or invalid request data.".to_string()),
+        });
+        let db_result = try_do!({
+            let presenter = auth::authenticate(&self.database, request_data.session_token, Scope::PresentationsWrite)?;
+            let launch = self.database.perform(Search(Session::search_parameter(request_data.launch_session_token)))?;
+            if !lti::is_platform_presenter(&launch.owner) || lti::is_platform_presenter(&presenter) {
+                return Err("Only a launch can be linked to your account.".to_string());
+            }
+            self.database.perform(Update(MergePlatformPresenter {
+                from: launch.owner,
+                to: presenter,
+            }))
+        });
+        match db_result {
+            Ok(()) => json_response!(status::Ok, LinkResponse {
+                error: None,
+            }),
+            Err(err) => json_response!(status::BadRequest, LinkResponse {
+                error: Some(err),
+            }),
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use std::sync::Arc;
+
+use iron::prelude::*;
+use iron::Handler;
+use iron::headers::Location;
+use iron::modifiers::Header;
+use iron::status;
+use urlencoded::{UrlEncodedBody, UrlEncodedQuery};
+
+use auth::lti::{self, LoginInitiation, LtiPlatform};
+use auth::oidc;
+use capabilities::{Capability, Save};
+use models::{Id, OidcLoginAttempt};
+
+
+/// Handles the third-party initiated login that a learning management system begins a launch with,
+/// by redirecting back to the platform to have it post the launch to `LaunchHandler`.
+pub struct LoginHandler<DB> {
+    database: DB,
+    platforms: Arc<Vec<LtiPlatform>>,
+    launch_url: String,
+}
+
+#[derive(Debug, Serialize)]
+struct LoginResponse {
+    pub error: Option<String>,
+}
+
+impl<DB> LoginHandler<DB> {
+    pub fn new(db: DB, platforms: Arc<Vec<LtiPlatform>>, launch_url: String) -> Self {
+        LoginHandler {
+            database: db,
+            platforms: platforms,
+            launch_url: launch_url,
+        }
+    }
+}
+
+impl<DB> Handler for LoginHandler<DB>
+    where DB: 'static + Sync + Send
+        + Capability<Save<OidcLoginAttempt>, Data = OidcLoginAttempt, Error = String>
+{
+    fn handle(&self, request: &mut Request) -> IronResult<Response> {
+        // Platforms may initiate a login with either a GET or a form POST.
+        let params = request.get::<UrlEncodedBody>()
+            .or_else(|_| request.get::<UrlEncodedQuery>())
+            .unwrap_or_default();
+        let param = |name: &str| params.get(name).and_then(|values| values.first()).cloned();
+        let initiation = match (param("iss"), param("login_hint"), param("target_link_uri")) {
+            (Some(issuer), Some(login_hint), Some(target_link_uri)) => LoginInitiation {
+                issuer: issuer,
+                login_hint: login_hint,
+                target_link_uri: target_link_uri,
+                client_id: param("client_id"),
+                lti_message_hint: param("lti_message_hint"),
+            },
+            _ => return json_response!(status::BadRequest, LoginResponse {
+                error: Some("Missing or invalid request data.".to_string()),
+            }),
+        };
+        let redirect = try_do!({
+            let platform = lti::find_platform(
+                &self.platforms,
+                &initiation.issuer,
+                initiation.client_id.as_ref().map(String::as_str))
+                .ok_or("Unknown platform.".to_string())?;
+            let attempt = OidcLoginAttempt::new(Id(oidc::random_value()), oidc::random_value(), String::new());
+            let attempt = self.database.perform(Save(attempt))?;
+            lti::authentication_request_url(platform, &initiation, &self.launch_url, &attempt.state.0, &attempt.nonce)
+        });
+        match redirect {
+            Ok(url) => Ok(Response::with((status::Found, Header(Location(url))))),
+            Err(err) => json_response!(status::BadRequest, LoginResponse {
+                error: Some(err),
+            }),
+        }
+    }
+}
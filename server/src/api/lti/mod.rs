@@ -0,0 +1,3 @@
+pub mod launch;
+pub mod link;
+pub mod login;
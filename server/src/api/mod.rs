@@ -57,6 +57,10 @@ macro_rules! try_do {
     }
 }
 
+pub mod lti;
+pub mod oidc;
+pub mod organizations;
 pub mod presenters;
 pub mod presentations;
 pub mod questions;
+pub mod rate_limit;
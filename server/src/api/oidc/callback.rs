@@ -0,0 +1,130 @@
+use std::sync::Arc;
+
+use chrono::prelude::*;
+use iron::prelude::*;
+use iron::Handler;
+use iron::headers::{ContentType, Location, SetCookie};
+use iron::modifiers::Header;
+use iron::status;
+use ring::constant_time::verify_slices_are_equal;
+use serde_json;
+
+use auth::oidc::OidcProvider;
+use capabilities::{Capability, Delete, Save, Search};
+use models::{Id, AccountToken, ExternalIdentity, OidcLoginAttempt, Presenter, Session, TokenPurpose};
+use super::{state_cookie, state_from_cookies};
+
+
+/// Handles presenters being redirected back from the OpenID Connect provider after signing in.
+///
+/// The first time a provider account signs in, a new presenter is created for it, named after its
+/// verified email address. If that address already belongs to a presenter, the new presenter is
+/// named after the provider account instead, and must be linked with `LinkHandler` by someone who
+/// can sign in as both. Provider accounts never sign in as an existing presenter otherwise.
+///
+/// Presenters with two-factor authentication enabled are sent a challenge token instead of a
+/// session, as when they sign in with a password.
+pub struct CallbackHandler<DB> {
+    database: DB,
+    provider: Arc<OidcProvider>,
+}
+
+#[derive(Debug, Serialize)]
+struct CallbackResponse {
+    pub error: Option<String>,
+}
+
+impl<DB> CallbackHandler<DB> {
+    pub fn new(db: DB, provider: Arc<OidcProvider>) -> Self {
+        CallbackHandler {
+            database: db,
+            provider: provider,
+        }
+    }
+}
+
+impl<DB> Handler for CallbackHandler<DB>
+    where DB: 'static + Sync + Send
+        + Capability<Search<OidcLoginAttempt>, Data = OidcLoginAttempt, Error = String>
+        + Capability<Delete<OidcLoginAttempt>, Data = (), Error = String>
+        + Capability<Search<ExternalIdentity>, Data = ExternalIdentity, Error = String>
+        + Capability<Save<ExternalIdentity>, Data = ExternalIdentity, Error = String>
+        + Capability<Search<Presenter>, Data = Presenter, Error = String>
+        + Capability<Save<Presenter>, Data = Presenter, Error = String>
+        + Capability<Save<Session>, Data = Session, Error = String>
+        + Capability<Save<AccountToken>, Data = AccountToken, Error = String>
+{
+    fn handle(&self, request: &mut Request) -> IronResult<Response> {
+        let input_err = "Missing or invalid request data.".to_string();
+        let (state, code) = decode_query_or_write_error!(
+            request,
+            extract = |query| {
+                let state = query.get("state").and_then(|strings| strings.first());
+                let code = query.get("code").and_then(|strings| strings.first());
+                match (state, code) {
+                    (Some(state), Some(code)) => Some((Id(state.clone()), code.clone())),
+                    _ => None,
+                }
+            },
+            missing = CallbackResponse {
+                error: Some(input_err),
+            });
+        let bound_state = state_from_cookies(request);
+        let db_result = try_do!({
+            let bound = bound_state
+                .map(|bound| verify_slices_are_equal(bound.as_bytes(), state.0.as_bytes()).is_ok())
+                .unwrap_or(false);
+            if !bound {
+                return Err("Signing in was not started from this browser.".to_string());
+            }
+            let attempt = self.database.perform(Search(OidcLoginAttempt::search_parameter(state)))?;
+            let nonce = attempt.nonce.clone();
+            let code_verifier = attempt.code_verifier.clone();
+            let expired = attempt.is_expired(Utc::now());
+            self.database.perform(Delete(attempt))?;
+            if expired {
+                return Err("Login attempt expired.".to_string());
+            }
+            let id_token = self.provider.exchange_code(&code, &code_verifier)?;
+            let claims = self.provider.validate_id_token(&id_token, &nonce, Utc::now())?;
+            let identity = ExternalIdentity::search_parameter(claims.iss.clone(), claims.sub.clone());
+            let presenter = match self.database.perform(Search(identity)) {
+                Ok(identity) => self.database.perform(Search(Presenter::search_parameter(identity.presenter)))?,
+                Err(_) => {
+                    let email = match (claims.email.clone(), claims.email_verified) {
+                        (Some(email), Some(true)) => Id(email),
+                        _ => return Err("The identity provider did not supply a verified email address.".to_string()),
+                    };
+                    let presenter_id = match self.database.perform(Search(Presenter::search_parameter(email.clone()))) {
+                        Ok(_)  => claims.provider_presenter_id(),
+                        Err(_) => email,
+                    };
+                    let presenter = self.database.perform(Save(Presenter::from_identity_provider(presenter_id.0)))?;
+                    let identity = ExternalIdentity::new(claims.iss, claims.sub, presenter.email_address.clone());
+                    self.database.perform(Save(identity))?;
+                    presenter
+                },
+            };
+            if presenter.totp_enabled {
+                let challenge = AccountToken::new(presenter.email_address, TokenPurpose::LoginChallenge);
+                self.database.perform(Save(challenge)).map(|challenge| ("challengeToken", challenge.token))
+            } else {
+                self.database.perform(Save(Session::new(presenter))).map(|session| ("sessionToken", session.token))
+            }
+        });
+        let secure = self.provider.config.redirect_uri.starts_with("https:");
+        let clear_state = Header(SetCookie(vec![state_cookie("", 0, secure)]));
+        match db_result {
+            Ok((kind, token)) => {
+                let url = format!("{}#{}={}", self.provider.config.post_login_redirect, kind, token.0);
+                Ok(Response::with((status::Found, Header(Location(url)), clear_state)))
+            },
+            Err(err) => {
+                let body = serde_json::to_string(&CallbackResponse {
+                    error: Some(err),
+                }).unwrap();
+                Ok(Response::with((ContentType::json().0, status::BadRequest, body, clear_state)))
+            },
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use std::error::Error;
+
+use iron::prelude::*;
+use iron::Handler;
+use iron::status;
+
+use auth::{self, Authenticate};
+use auth::{lti, oidc};
+use capabilities::{Capability, Search, Update};
+use capabilities::sqlite::MergePlatformPresenter;
+use models::{Id, Scope, Session};
+
+
+/// Handles requests from a presenter to link an identity provider account, whose email address
+/// they already had an account under, to that account, so that signing in with the provider signs
+/// them in to it.
+///
+/// The presenter proves they hold both accounts by presenting a session of each, so an identity
+/// provider can never take over an account just by claiming its email address.
+pub struct LinkHandler<DB> {
+    database: DB,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct LinkRequest {
+    #[serde(rename = "sessionToken")]
+    pub session_token: Id,
+    /// The session token issued when signing in with the identity provider.
+    #[serde(rename = "providerSessionToken")]
+    pub provider_session_token: Id,
+}
+
+#[derive(Debug, Serialize)]
+struct LinkResponse {
+    pub error: Option<String>,
+}
+
+impl<DB> LinkHandler<DB> {
+    pub fn new(db: DB) -> Self {
+        LinkHandler {
+            database: db,
+        }
+    }
+}
+
+impl<DB> Handler for LinkHandler<DB>
+    where DB: 'static + Sync + Send
+        + Capability<Update<MergePlatformPresenter>, Data = (), Error = String>
+        + Authenticate
+{
+    fn handle(&self, request: &mut Request) -> IronResult<Response> {
+        let request_data = decode_body_or_write_error!(request, LinkRequest, |_: Option<&Error>| LinkResponse {
+            error: Some("Missing or invalid request data.".to_string()),
+        });
+        let db_result = try_do!({
+            let presenter = auth::authenticate(&self.database, request_data.session_token, Scope::PresentationsWrite)?;
+            let provider = self.database.perform(Search(Session::search_parameter(request_data.provider_session_token)))?;
+            let is_own_account = !oidc::is_provider_presenter(&presenter) && !lti::is_platform_presenter(&presenter);
+            if !oidc::is_provider_presenter(&provider.owner) || !is_own_account {
+                return Err("Only an identity provider account can be linked to your account.".to_string());
+            }
+            self.database.perform(Update(MergePlatformPresenter {
+                from: provider.owner,
+                to: presenter,
+            }))
+        });
+        match db_result {
+            Ok(()) => json_response!(status::Ok, LinkResponse {
+                error: None,
+            }),
+            Err(err) => json_response!(status::BadRequest, LinkResponse {
+                error: Some(err),
+            }),
+        }
+    }
+}
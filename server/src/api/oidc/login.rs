@@ -0,0 +1,59 @@
+use std::sync::Arc;
+
+use iron::prelude::*;
+use iron::Handler;
+use iron::headers::{Location, SetCookie};
+use iron::modifiers::Header;
+use iron::status;
+
+use auth::oidc::{self, OidcProvider};
+use capabilities::{Capability, Save};
+use models::{Id, LOGIN_ATTEMPT_LIFETIME_MINUTES, OidcLoginAttempt};
+use super::state_cookie;
+
+
+/// Handles requests to sign in through the configured OpenID Connect provider, by redirecting the
+/// presenter to it. The login attempt is bound to the presenter's browser with a cookie, which
+/// `CallbackHandler` checks.
+pub struct LoginHandler<DB> {
+    database: DB,
+    provider: Arc<OidcProvider>,
+}
+
+#[derive(Debug, Serialize)]
+struct LoginResponse {
+    pub error: Option<String>,
+}
+
+impl<DB> LoginHandler<DB> {
+    pub fn new(db: DB, provider: Arc<OidcProvider>) -> Self {
+        LoginHandler {
+            database: db,
+            provider: provider,
+        }
+    }
+}
+
+impl<DB> Handler for LoginHandler<DB>
+    where DB: 'static + Sync + Send
+        + Capability<Save<OidcLoginAttempt>, Data = OidcLoginAttempt, Error = String>
+{
+    fn handle(&self, _request: &mut Request) -> IronResult<Response> {
+        let attempt = OidcLoginAttempt::new(Id(oidc::random_value()), oidc::random_value(), oidc::random_value());
+        let redirect = try_do!({
+            let attempt = self.database.perform(Save(attempt))?;
+            self.provider.authorization_url(&attempt.state.0, &attempt.nonce, &attempt.code_verifier)
+                .map(|url| (url, attempt.state))
+        });
+        match redirect {
+            Ok((url, state)) => {
+                let secure = self.provider.config.redirect_uri.starts_with("https:");
+                let cookie = state_cookie(&state.0, LOGIN_ATTEMPT_LIFETIME_MINUTES * 60, secure);
+                Ok(Response::with((status::Found, Header(Location(url)), Header(SetCookie(vec![cookie])))))
+            },
+            _ => json_response!(status::InternalServerError, LoginResponse {
+                error: Some("Could not start signing in. Try again later.".to_string()),
+            }),
+        }
+    }
+}
@@ -0,0 +1,38 @@
+use iron::headers::Cookie;
+use iron::prelude::*;
+
+
+pub mod callback;
+pub mod link;
+pub mod login;
+
+
+/// The cookie binding a login attempt to the browser that started it, so that a callback carrying
+/// someone else's `state` can't sign a presenter in as another.
+const STATE_COOKIE: &'static str = "asq_oidc_state";
+
+/// Where the state cookie is sent, which covers the callback.
+const STATE_COOKIE_PATH: &'static str = "/api/oidc";
+
+
+/// Produce the `Set-Cookie` value that binds the login attempt identified by `state` to a browser
+/// for `max_age_seconds` seconds. A max age of zero removes the cookie.
+fn state_cookie(state: &str, max_age_seconds: i64, secure: bool) -> String {
+    format!("{}={}; Max-Age={}; Path={}; HttpOnly; SameSite=Lax{}",
+            STATE_COOKIE, state, max_age_seconds, STATE_COOKIE_PATH, if secure { "; Secure" } else { "" })
+}
+
+/// Find the state of the login attempt a request's browser started, if it sent one.
+fn state_from_cookies(request: &Request) -> Option<String> {
+    let cookies = request.headers.get::<Cookie>()?;
+    cookies.iter()
+        .flat_map(|header| header.split(';'))
+        .filter_map(|cookie| {
+            let mut parts = cookie.trim().splitn(2, '=');
+            match (parts.next(), parts.next()) {
+                (Some(name), Some(value)) if name == STATE_COOKIE => Some(value.to_string()),
+                _ => None,
+            }
+        })
+        .next()
+}
@@ -0,0 +1,69 @@
+use std::error::Error;
+
+use iron::prelude::*;
+use iron::Handler;
+use iron::status;
+
+use auth::{self, Authenticate};
+use capabilities::{Capability, Save};
+use models::{Id, Organization, OrganizationMember, OrganizationRole, Scope};
+
+
+/// Handles requests from a presenter to create an organization, of which they become the first
+/// admin.
+pub struct CreateOrganizationHandler<DB> {
+    database: DB,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct CreateOrganizationRequest {
+    #[serde(rename = "sessionToken")]
+    pub session_token: Id,
+    pub name: String,
+}
+
+#[derive(Debug, Serialize)]
+struct OrganizationResponse {
+    pub error: Option<String>,
+    pub organization: Option<Organization>,
+}
+
+impl<DB> CreateOrganizationHandler<DB> {
+    pub fn new(db: DB) -> Self {
+        CreateOrganizationHandler {
+            database: db,
+        }
+    }
+}
+
+impl<DB> Handler for CreateOrganizationHandler<DB>
+    where DB: 'static + Sync + Send
+        + Capability<Save<Organization>, Data = Organization, Error = String>
+        + Capability<Save<OrganizationMember>, Data = OrganizationMember, Error = String>
+        + Authenticate
+{
+    fn handle(&self, request: &mut Request) -> IronResult<Response> {
+        let request_data = decode_body_or_write_error!(request, CreateOrganizationRequest, |_: Option<&Error>| OrganizationResponse {
+            error: Some("Missing or invalid request data.".to_string()),
+            organization: None,
+        });
+        let db_result = try_do!({
+            let presenter = auth::authenticate(&self.database, request_data.session_token, Scope::PresentationsWrite)?;
+            let name = Organization::validate_name(&request_data.name)?;
+            let organization = self.database.perform(Save(Organization::new(name)))?;
+            let admin = OrganizationMember::new(organization.id.clone(), presenter, OrganizationRole::Admin);
+            self.database.perform(Save(admin))?;
+            Ok(organization)
+        });
+        match db_result {
+            Ok(organization) => json_response!(status::Ok, OrganizationResponse {
+                error: None,
+                organization: Some(organization),
+            }),
+            Err(err) => json_response!(status::BadRequest, OrganizationResponse {
+                error: Some(err),
+                organization: None,
+            }),
+        }
+    }
+}
@@ -0,0 +1,79 @@
+use std::error::Error;
+
+use iron::prelude::*;
+use iron::Handler;
+use iron::status;
+
+use auth::{self, Authenticate, Authorize};
+use capabilities::{Capability, Search, Update};
+use models::{Id, Organization, OrganizationDefaults, OrganizationRole, Scope};
+
+
+/// Handles requests from an organization's admins to rename it, or to change the settings that
+/// presentations created in it start out with. Presentations already in the organization keep
+/// their settings.
+pub struct SetDefaultsHandler<DB> {
+    database: DB,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct SetDefaultsRequest {
+    #[serde(rename = "sessionToken")]
+    pub session_token: Id,
+    #[serde(rename = "organization")]
+    pub organization_id: Id,
+    /// The organization's new name, if it is to be renamed.
+    pub name: Option<String>,
+    pub defaults: OrganizationDefaults,
+}
+
+#[derive(Debug, Serialize)]
+struct SetDefaultsResponse {
+    pub error: Option<String>,
+    pub organization: Option<Organization>,
+}
+
+impl<DB> SetDefaultsHandler<DB> {
+    pub fn new(db: DB) -> Self {
+        SetDefaultsHandler {
+            database: db,
+        }
+    }
+}
+
+impl<DB> Handler for SetDefaultsHandler<DB>
+    where DB: 'static + Sync + Send
+        + Capability<Search<Organization>, Data = Organization, Error = String>
+        + Capability<Update<Organization>, Data = Organization, Error = String>
+        + Authenticate
+        + Authorize
+{
+    fn handle(&self, request: &mut Request) -> IronResult<Response> {
+        let request_data = decode_body_or_write_error!(request, SetDefaultsRequest, |_: Option<&Error>| SetDefaultsResponse {
+            error: Some("Missing or invalid request data.".to_string()),
+            organization: None,
+        });
+        let db_result = try_do!({
+            auth::authorize_in_organization(
+                &self.database, request_data.session_token, Scope::PresentationsWrite,
+                &request_data.organization_id, OrganizationRole::Admin)?;
+            let organization = Organization::search_parameter(request_data.organization_id);
+            let mut organization = self.database.perform(Search(organization))?;
+            if let Some(ref name) = request_data.name {
+                organization.name = Organization::validate_name(name)?;
+            }
+            organization.defaults = request_data.defaults.clone().validate()?;
+            self.database.perform(Update(organization))
+        });
+        match db_result {
+            Ok(organization) => json_response!(status::Ok, SetDefaultsResponse {
+                error: None,
+                organization: Some(organization),
+            }),
+            Err(err) => json_response!(status::BadRequest, SetDefaultsResponse {
+                error: Some(err),
+                organization: None,
+            }),
+        }
+    }
+}
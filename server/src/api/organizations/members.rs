@@ -0,0 +1,215 @@
+use std::error::Error;
+
+use iron::prelude::*;
+use iron::Handler;
+use iron::status;
+use router::Router;
+
+use auth::{self, Authenticate, Authorize};
+use capabilities::{Capability, Delete, FindAll, Save};
+use capabilities::sqlite::MembersOfOrganization;
+use models::{Id, OrganizationMember, OrganizationRole, Scope};
+
+
+/// Handles requests from an organization's admins to add a presenter to it, or to change the role
+/// of a presenter already in it.
+pub struct AddMemberHandler<DB> {
+    database: DB,
+}
+
+/// Handles requests from an organization's admins to remove a presenter from it. The last admin
+/// of an organization cannot be removed.
+pub struct RemoveMemberHandler<DB> {
+    database: DB,
+}
+
+/// Handles requests from the presenters in an organization to see who else is in it.
+pub struct ListMembersHandler<DB> {
+    database: DB,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct AddMemberRequest {
+    #[serde(rename = "sessionToken")]
+    pub session_token: Id,
+    #[serde(rename = "organization")]
+    pub organization_id: Id,
+    pub member: Id,
+    pub role: OrganizationRole,
+}
+
+#[derive(Debug, Serialize)]
+struct AddMemberResponse {
+    pub error: Option<String>,
+    pub member: Option<OrganizationMember>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct RemoveMemberRequest {
+    #[serde(rename = "sessionToken")]
+    pub session_token: Id,
+    #[serde(rename = "organization")]
+    pub organization_id: Id,
+    pub member: Id,
+}
+
+#[derive(Debug, Serialize)]
+struct RemoveMemberResponse {
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct ListMembersResponse {
+    pub error: Option<String>,
+    pub members: Vec<OrganizationMember>,
+}
+
+impl<DB> AddMemberHandler<DB> {
+    pub fn new(db: DB) -> Self {
+        AddMemberHandler {
+            database: db,
+        }
+    }
+}
+
+impl<DB> RemoveMemberHandler<DB> {
+    pub fn new(db: DB) -> Self {
+        RemoveMemberHandler {
+            database: db,
+        }
+    }
+}
+
+impl<DB> ListMembersHandler<DB> {
+    pub fn new(db: DB) -> Self {
+        ListMembersHandler {
+            database: db,
+        }
+    }
+}
+
+/// Ensure that `organization` would still have an admin if `member` were removed from it or made
+/// an ordinary member.
+fn keeps_an_admin<DB>(db: &DB, organization: &Id, member: &Id) -> Result<(), String>
+    where DB: Capability<FindAll<MembersOfOrganization>, Data = Vec<OrganizationMember>, Error = String>
+{
+    let members = db.perform(FindAll(MembersOfOrganization { organization_id: organization.clone() }))?;
+    let other_admins = members.iter()
+        .filter(|other| other.role == OrganizationRole::Admin && other.member != *member)
+        .count();
+    if other_admins > 0 {
+        Ok(())
+    } else {
+        Err("Organizations must keep at least one admin.".to_string())
+    }
+}
+
+impl<DB> Handler for AddMemberHandler<DB>
+    where DB: 'static + Sync + Send
+        + Capability<Save<OrganizationMember>, Data = OrganizationMember, Error = String>
+        + Capability<FindAll<MembersOfOrganization>, Data = Vec<OrganizationMember>, Error = String>
+        + Authenticate
+        + Authorize
+{
+    fn handle(&self, request: &mut Request) -> IronResult<Response> {
+        let request_data = decode_body_or_write_error!(request, AddMemberRequest, |_: Option<&Error>| AddMemberResponse {
+            error: Some("Missing or invalid request data.".to_string()),
+            member: None,
+        });
+        let db_result = try_do!({
+            let organization = request_data.organization_id;
+            auth::authorize_in_organization(
+                &self.database, request_data.session_token, Scope::PresentationsWrite, &organization,
+                OrganizationRole::Admin)?;
+            let member = Id(request_data.member.0.trim().to_string());
+            if !member.0.contains('@') {
+                return Err("Invalid email address.".to_string());
+            }
+            if request_data.role != OrganizationRole::Admin {
+                keeps_an_admin(&self.database, &organization, &member)?;
+            }
+            self.database.perform(Save(OrganizationMember::new(organization, member, request_data.role)))
+        });
+        match db_result {
+            Ok(member) => json_response!(status::Ok, AddMemberResponse {
+                error: None,
+                member: Some(member),
+            }),
+            Err(err) => json_response!(status::BadRequest, AddMemberResponse {
+                error: Some(err),
+                member: None,
+            }),
+        }
+    }
+}
+
+impl<DB> Handler for RemoveMemberHandler<DB>
+    where DB: 'static + Sync + Send
+        + Capability<Delete<OrganizationMember>, Data = (), Error = String>
+        + Capability<FindAll<MembersOfOrganization>, Data = Vec<OrganizationMember>, Error = String>
+        + Authenticate
+        + Authorize
+{
+    fn handle(&self, request: &mut Request) -> IronResult<Response> {
+        let request_data = decode_body_or_write_error!(request, RemoveMemberRequest, |_: Option<&Error>| RemoveMemberResponse {
+            error: Some("Missing or invalid request data.".to_string()),
+        });
+        let db_result = try_do!({
+            let organization = request_data.organization_id;
+            auth::authorize_in_organization(
+                &self.database, request_data.session_token, Scope::PresentationsWrite, &organization,
+                OrganizationRole::Admin)?;
+            keeps_an_admin(&self.database, &organization, &request_data.member)?;
+            self.database.perform(Delete(OrganizationMember::search_parameter(organization, request_data.member)))
+        });
+        match db_result {
+            Ok(()) => json_response!(status::Ok, RemoveMemberResponse {
+                error: None,
+            }),
+            Err(err) => json_response!(status::BadRequest, RemoveMemberResponse {
+                error: Some(err),
+            }),
+        }
+    }
+}
+
+impl<DB> Handler for ListMembersHandler<DB>
+    where DB: 'static + Sync + Send
+        + Capability<FindAll<MembersOfOrganization>, Data = Vec<OrganizationMember>, Error = String>
+        + Authenticate
+        + Authorize
+{
+    fn handle(&self, request: &mut Request) -> IronResult<Response> {
+        let organization_id = request.extensions
+            .get::<Router>()
+            .and_then(|router| router.find("id"))
+            .map(|id| Id(id.to_string()));
+        let input_err = "Missing or invalid request data.".to_string();
+        let session_token = decode_query_or_write_error!(
+            request,
+            extract = |query| query.get("sessionToken")
+                .and_then(|strings| strings.first())
+                .map(|token| Id(token.clone())),
+            missing = ListMembersResponse {
+                error: Some(input_err),
+                members: vec![],
+            }
+        );
+        let db_result = try_do!({
+            let organization = organization_id.ok_or("Missing organization.".to_string())?;
+            auth::authorize_in_organization(
+                &self.database, session_token, Scope::PresentationsRead, &organization, OrganizationRole::Member)?;
+            self.database.perform(FindAll(MembersOfOrganization { organization_id: organization }))
+        });
+        match db_result {
+            Ok(members) => json_response!(status::Ok, ListMembersResponse {
+                error: None,
+                members: members,
+            }),
+            Err(err) => json_response!(status::BadRequest, ListMembersResponse {
+                error: Some(err),
+                members: vec![],
+            }),
+        }
+    }
+}
@@ -0,0 +1,4 @@
+pub mod create;
+pub mod defaults;
+pub mod members;
+pub mod presentations;
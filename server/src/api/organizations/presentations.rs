@@ -0,0 +1,147 @@
+use std::error::Error;
+
+use iron::prelude::*;
+use iron::Handler;
+use iron::status;
+use router::Router;
+
+use auth::{self, Authenticate, Authorize};
+use capabilities::{Capability, FindAll, Search, Update};
+use capabilities::sqlite::PresentationsForOrganization;
+use models::{Id, Action, Organization, OrganizationRole, Presentation, Scope};
+
+
+/// Handles requests from an organization's admins to list every presentation in it, newest first.
+pub struct ListPresentationsHandler<DB> {
+    database: DB,
+}
+
+/// Handles requests from the owner of a presentation to move it into one of their organizations,
+/// or out of its organization when none is given. A presentation moved into an organization takes
+/// on its defaults wherever its own settings haven't been changed, and keeps its settings when
+/// moved out.
+pub struct MovePresentationHandler<DB> {
+    database: DB,
+}
+
+#[derive(Debug, Serialize)]
+struct ListPresentationsResponse {
+    pub error: Option<String>,
+    pub presentations: Vec<Presentation>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct MovePresentationRequest {
+    #[serde(rename = "sessionToken")]
+    pub session_token: Id,
+    #[serde(rename = "presentation")]
+    pub presentation_id: Id,
+    #[serde(rename = "organization", default)]
+    pub organization_id: Option<Id>,
+}
+
+#[derive(Debug, Serialize)]
+struct MovePresentationResponse {
+    pub error: Option<String>,
+    pub presentation: Option<Presentation>,
+}
+
+impl<DB> ListPresentationsHandler<DB> {
+    pub fn new(db: DB) -> Self {
+        ListPresentationsHandler {
+            database: db,
+        }
+    }
+}
+
+impl<DB> MovePresentationHandler<DB> {
+    pub fn new(db: DB) -> Self {
+        MovePresentationHandler {
+            database: db,
+        }
+    }
+}
+
+impl<DB> Handler for ListPresentationsHandler<DB>
+    where DB: 'static + Sync + Send
+        + Capability<FindAll<PresentationsForOrganization>, Data = Vec<Presentation>, Error = String>
+        + Authenticate
+        + Authorize
+{
+    fn handle(&self, request: &mut Request) -> IronResult<Response> {
+        let organization_id = request.extensions
+            .get::<Router>()
+            .and_then(|router| router.find("id"))
+            .map(|id| Id(id.to_string()));
+        let input_err = "Missing or invalid request data.".to_string();
+        let session_token = decode_query_or_write_error!(
+            request,
+            extract = |query| query.get("sessionToken")
+                .and_then(|strings| strings.first())
+                .map(|token| Id(token.clone())),
+            missing = ListPresentationsResponse {
+                error: Some(input_err),
+                presentations: vec![],
+            }
+        );
+        let db_result = try_do!({
+            let organization = organization_id.ok_or("Missing organization.".to_string())?;
+            auth::authorize_in_organization(
+                &self.database, session_token, Scope::PresentationsRead, &organization, OrganizationRole::Admin)?;
+            self.database.perform(FindAll(PresentationsForOrganization { organization_id: organization }))
+        });
+        match db_result {
+            Ok(presentations) => json_response!(status::Ok, ListPresentationsResponse {
+                error: None,
+                presentations: presentations,
+            }),
+            Err(err) => json_response!(status::BadRequest, ListPresentationsResponse {
+                error: Some(err),
+                presentations: vec![],
+            }),
+        }
+    }
+}
+
+impl<DB> Handler for MovePresentationHandler<DB>
+    where DB: 'static + Sync + Send
+        + Capability<Search<Presentation>, Data = Presentation, Error = String>
+        + Capability<Update<Presentation>, Data = Presentation, Error = String>
+        + Capability<Search<Organization>, Data = Organization, Error = String>
+        + Authenticate
+        + Authorize
+{
+    fn handle(&self, request: &mut Request) -> IronResult<Response> {
+        let request_data = decode_body_or_write_error!(request, MovePresentationRequest, |_: Option<&Error>| MovePresentationResponse {
+            error: Some("Missing or invalid request data.".to_string()),
+            presentation: None,
+        });
+        let db_result = try_do!({
+            let presentation = Presentation::search_parameter(request_data.presentation_id);
+            let mut presentation = self.database.perform(Search(presentation))?;
+            let presenter = auth::authorize(
+                &self.database, request_data.session_token, Scope::PresentationsWrite, &presentation, Action::ManageMembers)?;
+            match request_data.organization_id {
+                Some(organization) => {
+                    if auth::organization_role_of(&self.database, &presenter, &organization).is_none() {
+                        return Err("You are not allowed to do that!".to_string());
+                    }
+                    let organization = self.database.perform(Search(Organization::search_parameter(organization)))?;
+                    presentation.join_organization(organization.id, organization.defaults);
+                },
+                None => presentation.organization = None,
+            }
+            self.database.perform(Update(presentation))
+        });
+        match db_result {
+            Ok(presentation) => json_response!(status::Ok, MovePresentationResponse {
+                error: None,
+                presentation: Some(presentation),
+            }),
+            Err(err) => json_response!(status::BadRequest, MovePresentationResponse {
+                error: Some(err),
+                presentation: None,
+            }),
+        }
+    }
+}
@@ -0,0 +1,189 @@
+use std::error::Error;
+use std::net::IpAddr;
+
+use chrono::prelude::*;
+use iron::prelude::*;
+use iron::Handler;
+use iron::status;
+
+use api::rate_limit;
+use auth::{self, Authenticate, Authorize};
+use capabilities::{Capability, Save, Search, Update};
+use models::{AccessGrant, Action, Budget, Id, Presentation, Scope};
+use ratelimit::BucketStore;
+
+
+/// How many passcodes each client IP address may try, in a burst and then per minute.
+const GUESSES_PER_ADDRESS: (u32, u32) = (10, 5);
+
+/// How many passcodes may be tried for each presentation, in a burst and then per minute, from
+/// every address together. This is large enough for a whole audience to enter the passcode at
+/// once, but stops guesses spread over many addresses from getting through.
+const GUESSES_PER_PRESENTATION: (u32, u32) = (200, 60);
+
+
+/// Handles requests from audience members to enter the passcode of a private presentation.
+///
+/// Passcode attempts are limited per client IP address and per presentation, so that passcodes
+/// can't be guessed by trying them all.
+pub struct RequestAccessHandler<DB, S> {
+    database: DB,
+    buckets: S,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct AccessRequest {
+    #[serde(rename = "presentation")]
+    pub presentation_id: Id,
+    pub passcode: String,
+}
+
+#[derive(Debug, Serialize)]
+struct AccessResponse {
+    pub error: Option<String>,
+    #[serde(rename = "accessToken")]
+    pub access_token: Option<Id>,
+}
+
+impl<DB, S> RequestAccessHandler<DB, S> {
+    pub fn new(db: DB, buckets: S) -> Self {
+        RequestAccessHandler {
+            database: db,
+            buckets: buckets,
+        }
+    }
+}
+
+impl<DB, S> RequestAccessHandler<DB, S>
+    where S: BucketStore
+{
+    /// Spend a guess from the client's and the presentation's budgets, producing the number of
+    /// seconds to wait if either is used up.
+    ///
+    /// A bucket that can't be read lets the guess through, since the passcode still has to match
+    /// and refusing would shut the whole audience out of a private presentation. The address's
+    /// budget is spent from first and lazily, so that one client making too many guesses doesn't
+    /// use up the presentation's budget for everyone else.
+    fn guess_wait(&self, presentation_id: &Id, address: IpAddr) -> Option<u64> {
+        let now = Utc::now();
+        let (address_capacity, address_rate) = GUESSES_PER_ADDRESS;
+        let (presentation_capacity, presentation_rate) = GUESSES_PER_PRESENTATION;
+        let limits = vec![
+            (format!("passcode:ip:{}", address), Budget::new(address_capacity, address_rate)),
+            (format!("passcode:{}", presentation_id.0), Budget::new(presentation_capacity, presentation_rate)),
+        ];
+        limits.into_iter()
+            .map(|(key, budget)| self.buckets.take(&key, budget, now))
+            .filter_map(|decision| match decision {
+                Ok(Err(wait)) => Some(wait),
+                _ => None,
+            })
+            .next()
+    }
+}
+
+impl<DB, S> Handler for RequestAccessHandler<DB, S>
+    where DB: 'static + Sync + Send
+        + Capability<Search<Presentation>, Data = Presentation, Error = String>
+        + Capability<Save<AccessGrant>, Data = AccessGrant, Error = String>,
+          S: 'static + Sync + Send + BucketStore
+{
+    fn handle(&self, request: &mut Request) -> IronResult<Response> {
+        let request_data = decode_body_or_write_error!(request, AccessRequest, |_: Option<&Error>| AccessResponse {
+            error: Some("Missing or invalid request data.".to_string()),
+            access_token: None,
+        });
+        if let Some(wait) = self.guess_wait(&request_data.presentation_id, request.remote_addr.ip()) {
+            return Ok(rate_limit::too_many_requests(&AccessResponse {
+                error: Some("Too many passcodes have been tried. Try again shortly.".to_string()),
+                access_token: None,
+            }, wait));
+        }
+        let db_result = try_do!({
+            let presentation = Presentation::search_parameter(request_data.presentation_id);
+            let presentation = self.database.perform(Search(presentation))
+                .map_err(|_| "Incorrect passcode.".to_string())?;
+            if !presentation.passcode_matches(&request_data.passcode) {
+                return Err("Incorrect passcode.".to_string());
+            }
+            self.database.perform(Save(AccessGrant::new(presentation.id)))
+        });
+        match db_result {
+            Ok(grant) => json_response!(status::Ok, AccessResponse {
+                error: None,
+                access_token: Some(grant.token),
+            }),
+            Err(err) => json_response!(status::Forbidden, AccessResponse {
+                error: Some(err),
+                access_token: None,
+            }),
+        }
+    }
+}
+
+
+/// Handles requests from a presenter to make one of their presentations private by setting a
+/// passcode, or public again by removing it.
+pub struct SetPasscodeHandler<DB> {
+    database: DB,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct SetPasscodeRequest {
+    #[serde(rename = "sessionToken")]
+    pub session_token: Id,
+    #[serde(rename = "presentation")]
+    pub presentation_id: Id,
+    pub passcode: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct SetPasscodeResponse {
+    pub error: Option<String>,
+    pub presentation: Option<Presentation>,
+}
+
+impl<DB> SetPasscodeHandler<DB> {
+    pub fn new(db: DB) -> Self {
+        SetPasscodeHandler {
+            database: db,
+        }
+    }
+}
+
+impl<DB> Handler for SetPasscodeHandler<DB>
+    where DB: 'static + Sync + Send
+        + Capability<Search<Presentation>, Data = Presentation, Error = String>
+        + Capability<Update<Presentation>, Data = Presentation, Error = String>
+        + Authenticate
+        + Authorize
+{
+    fn handle(&self, request: &mut Request) -> IronResult<Response> {
+        let request_data = decode_body_or_write_error!(request, SetPasscodeRequest, |_: Option<&Error>| SetPasscodeResponse {
+            error: Some("Missing or invalid request data.".to_string()),
+            presentation: None,
+        });
+        let db_result = try_do!({
+            let presentation = Presentation::search_parameter(request_data.presentation_id);
+            let mut presentation = self.database.perform(Search(presentation))?;
+            auth::authorize(
+                &self.database, request_data.session_token, Scope::PresentationsWrite, &presentation, Action::ChangeSettings)?;
+            let passcode = request_data.passcode.as_ref().map(|passcode| passcode.as_str());
+            if passcode.map(|passcode| passcode.is_empty()).unwrap_or(false) {
+                return Err("Passcodes cannot be empty.".to_string());
+            }
+            presentation.set_passcode(passcode);
+            self.database.perform(Update(presentation))
+        });
+        match db_result {
+            Ok(presentation) => json_response!(status::Ok, SetPasscodeResponse {
+                error: None,
+                presentation: Some(presentation),
+            }),
+            Err(err) => json_response!(status::BadRequest, SetPasscodeResponse {
+                error: Some(err),
+                presentation: None,
+            }),
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use std::error::Error;
+
+use chrono::prelude::*;
+use iron::prelude::*;
+use iron::Handler;
+use iron::status;
+
+use api::rate_limit;
+use auth::{self, CheckAccess};
+use capabilities::{Capability, Save, Search};
+use models::{Id, Audience, Budget, Presentation};
+use ratelimit::BucketStore;
+
+
+/// How many audience members may join from each client IP address, in a burst and then per
+/// minute. This is enough for a class behind one address to join at once, but keeps anyone from
+/// joining as a new audience member every time they would like to be counted again.
+const JOINS_PER_ADDRESS: (u32, u32) = (50, 10);
+
+
+/// Handles requests from audience members joining a presentation to be issued an anonymous
+/// identity, which they prove with its submission token when taking actions that count once per
+/// audience member, such as flagging a question.
+///
+/// Audience members launched from a learning management system are issued an identity by the
+/// launch instead.
+pub struct JoinAudienceHandler<DB, S> {
+    database: DB,
+    buckets: S,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct JoinAudienceRequest {
+    #[serde(rename = "presentation")]
+    pub presentation_id: Id,
+    #[serde(rename = "accessToken")]
+    pub access_token: Option<Id>,
+}
+
+#[derive(Debug, Serialize)]
+struct JoinAudienceResponse {
+    pub error: Option<String>,
+    pub audience: Option<Id>,
+    #[serde(rename = "submissionToken")]
+    pub submission_token: Option<String>,
+}
+
+impl<DB, S> JoinAudienceHandler<DB, S> {
+    pub fn new(db: DB, buckets: S) -> Self {
+        JoinAudienceHandler {
+            database: db,
+            buckets: buckets,
+        }
+    }
+}
+
+impl<DB, S> Handler for JoinAudienceHandler<DB, S>
+    where DB: 'static + Sync + Send
+        + Capability<Search<Presentation>, Data = Presentation, Error = String>
+        + Capability<Save<Audience>, Data = Audience, Error = String>
+        + CheckAccess,
+          S: 'static + Sync + Send + BucketStore
+{
+    fn handle(&self, request: &mut Request) -> IronResult<Response> {
+        let request_data = decode_body_or_write_error!(request, JoinAudienceRequest, |_: Option<&Error>| JoinAudienceResponse {
+            error: Some("Missing or invalid request data.".to_string()),
+            audience: None,
+            submission_token: None,
+        });
+        let (capacity, per_minute) = JOINS_PER_ADDRESS;
+        let key = format!("join:ip:{}", request.remote_addr.ip());
+        if let Ok(Err(wait)) = self.buckets.take(&key, Budget::new(capacity, per_minute), Utc::now()) {
+            return Ok(rate_limit::too_many_requests(&JoinAudienceResponse {
+                error: Some("Too many audience members have joined from here. Try again shortly.".to_string()),
+                audience: None,
+                submission_token: None,
+            }, wait));
+        }
+        let presentation = Presentation::search_parameter(request_data.presentation_id);
+        let presentation = match self.database.perform(Search(presentation)) {
+            Ok(presentation) => presentation,
+            Err(_) => return json_response!(status::BadRequest, JoinAudienceResponse {
+                error: Some("Invalid presentation.".to_string()),
+                audience: None,
+                submission_token: None,
+            }),
+        };
+        if let Err(err) = auth::check_access(&self.database, &presentation, request_data.access_token) {
+            return json_response!(status::Forbidden, JoinAudienceResponse {
+                error: Some(err),
+                audience: None,
+                submission_token: None,
+            });
+        }
+        match self.database.perform(Save(Audience::new(Id::generate()))) {
+            Ok(audience) => json_response!(status::Ok, JoinAudienceResponse {
+                error: None,
+                audience: Some(audience.id),
+                submission_token: Some(audience.submission_token),
+            }),
+            Err(err) => json_response!(status::InternalServerError, JoinAudienceResponse {
+                error: Some(err),
+                audience: None,
+                submission_token: None,
+            }),
+        }
+    }
+}
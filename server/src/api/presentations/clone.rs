@@ -0,0 +1,106 @@
+use std::error::Error;
+
+use iron::prelude::*;
+use iron::Handler;
+use iron::status;
+
+use auth::{self, Authenticate, Authorize};
+use capabilities::{Capability, FindAll, Save, Search};
+use capabilities::sqlite::{Import, QuestionsForPresentation};
+use models::{Id, Action, Presentation, Question, Scope};
+
+
+/// Handles requests from the owner of a presentation to start a new one with the same details and
+/// settings, such as when a course is taught again. The questions the audience can see may be
+/// copied over too, without their nods or answers. The copy belongs to the organization of the
+/// original only if the presenter making it is in that organization.
+pub struct ClonePresentationHandler<DB> {
+    database: DB,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct CloneRequest {
+    #[serde(rename = "sessionToken")]
+    pub session_token: Id,
+    #[serde(rename = "presentation")]
+    pub presentation_id: Id,
+    /// The title of the copy, if it should differ from the original's.
+    pub title: Option<String>,
+    #[serde(rename = "includeQuestions", default)]
+    pub include_questions: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct CloneResponse {
+    pub error: Option<String>,
+    pub presentation: Option<Presentation>,
+    pub questions: usize,
+}
+
+impl<DB> ClonePresentationHandler<DB> {
+    pub fn new(db: DB) -> Self {
+        ClonePresentationHandler {
+            database: db,
+        }
+    }
+}
+
+impl<DB> Handler for ClonePresentationHandler<DB>
+    where DB: 'static + Sync + Send
+        + Capability<Search<Presentation>, Data = Presentation, Error = String>
+        + Capability<FindAll<QuestionsForPresentation>, Data = Vec<Question>, Error = String>
+        + Capability<Save<Import>, Data = Import, Error = String>
+        + Authenticate
+        + Authorize
+{
+    fn handle(&self, request: &mut Request) -> IronResult<Response> {
+        let request_data = decode_body_or_write_error!(request, CloneRequest, |_: Option<&Error>| CloneResponse {
+            error: Some("Missing or invalid request data.".to_string()),
+            presentation: None,
+            questions: 0,
+        });
+        let db_result = try_do!({
+            let original = Presentation::search_parameter(request_data.presentation_id);
+            let original = self.database.perform(Search(original))?;
+            let presenter = auth::authorize(
+                &self.database, request_data.session_token, Scope::PresentationsWrite, &original, Action::ChangeSettings)?;
+            let mut copy = original.copy_for(presenter.clone());
+            if let Some(title) = request_data.title {
+                let mut details = copy.details();
+                details.title = title;
+                copy.set_details(details.validate()?);
+            }
+            if let Some(organization) = original.organization {
+                if auth::organization_role_of(&self.database, &presenter, &organization).is_some() {
+                    copy.organization = Some(organization);
+                }
+            }
+            let questions = if request_data.include_questions {
+                self.database.perform(FindAll(QuestionsForPresentation { presentation_id: original.id }))?
+                    .into_iter()
+                    .filter(|question| question.is_visible())
+                    .map(|question| (Question::new(copy.id.clone(), question.text), vec![]))
+                    .collect()
+            } else {
+                vec![]
+            };
+            self.database.perform(Save(Import {
+                presentation: copy,
+                new_presentation: true,
+                questions: questions,
+            }))
+        });
+        match db_result {
+            Ok(import) => json_response!(status::Ok, CloneResponse {
+                error: None,
+                questions: import.questions.len(),
+                presentation: Some(import.presentation),
+            }),
+            Err(err) => json_response!(status::BadRequest, CloneResponse {
+                error: Some(err),
+                presentation: None,
+                questions: 0,
+            }),
+        }
+    }
+}
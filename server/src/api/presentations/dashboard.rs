@@ -0,0 +1,94 @@
+use chrono::prelude::*;
+use iron::prelude::*;
+use iron::Handler;
+use iron::status;
+
+use auth::{self, Authenticate};
+use capabilities::{Aggregate, Capability};
+use capabilities::sqlite::{DashboardOrder, PresenterDashboard};
+use models::{Id, PresentationActivity, Scope};
+
+
+/// Handles requests from a presenter for a summary of the activity in each presentation they
+/// created or are a member of, along with their role in it. Presentations can be limited to those created within a range of dates, given
+/// as RFC 3339 `from` and `to` parameters, to those that are `open` or `closed` through the
+/// `status` parameter, and to those with a particular `tag`. They are listed most recently active
+/// first, unless `sort` is `created`.
+pub struct DashboardHandler<DB> {
+    database: DB,
+}
+
+#[derive(Debug, Serialize)]
+struct DashboardResponse {
+    pub error: Option<String>,
+    pub presentations: Vec<PresentationActivity>,
+}
+
+impl<DB> DashboardHandler<DB> {
+    pub fn new(db: DB) -> Self {
+        DashboardHandler {
+            database: db,
+        }
+    }
+}
+
+impl<DB> Handler for DashboardHandler<DB>
+    where DB: 'static + Sync + Send
+        + Capability<Aggregate<PresenterDashboard>, Data = Vec<PresentationActivity>, Error = String>
+        + Authenticate
+{
+    fn handle(&self, request: &mut Request) -> IronResult<Response> {
+        let input_err = "Missing or invalid request data.".to_string();
+        let (session_token, from, to, open, tag, order) = decode_query_or_write_error!(
+            request,
+            extract = |query| {
+                let param = |name: &str| query.get(name).and_then(|strings| strings.first());
+                let date = |name: &str| match param(name) {
+                    Some(date) => DateTime::parse_from_rfc3339(date).ok().map(|date| Some(date.with_timezone(&Utc))),
+                    None       => Some(None),
+                };
+                let open = match param("status").map(|status| status.as_str()) {
+                    Some("open")         => Some(Some(true)),
+                    Some("closed")       => Some(Some(false)),
+                    Some("all") | None   => Some(None),
+                    Some(_)              => None,
+                };
+                let order = match param("sort").map(|sort| sort.as_str()) {
+                    Some("activity") | None => Some(DashboardOrder::LastActivity),
+                    Some("created")         => Some(DashboardOrder::Created),
+                    Some(_)                 => None,
+                };
+                match (param("sessionToken"), date("from"), date("to"), open, order) {
+                    (Some(token), Some(from), Some(to), Some(open), Some(order)) =>
+                        Some((Id(token.clone()), from, to, open, param("tag").cloned(), order)),
+                    _ => None,
+                }
+            },
+            missing = DashboardResponse {
+                error: Some(input_err),
+                presentations: vec![],
+            }
+        );
+        let db_result = try_do!({
+            let presenter = auth::authenticate(&self.database, session_token, Scope::PresentationsRead)?;
+            self.database.perform(Aggregate(PresenterDashboard {
+                presenter_id: presenter,
+                created_after: from,
+                created_before: to,
+                open: open,
+                tag: tag,
+                order: order,
+            }))
+        });
+        match db_result {
+            Ok(presentations) => json_response!(status::Ok, DashboardResponse {
+                error: None,
+                presentations: presentations,
+            }),
+            Err(err) => json_response!(status::BadRequest, DashboardResponse {
+                error: Some(err),
+                presentations: vec![],
+            }),
+        }
+    }
+}
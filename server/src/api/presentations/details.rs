@@ -0,0 +1,137 @@
+use std::error::Error;
+
+use iron::prelude::*;
+use iron::Handler;
+use iron::status;
+
+use auth::{self, Authenticate, Authorize};
+use capabilities::{Capability, Save, Search, Update};
+use models::{Id, Action, Organization, Presentation, PresentationDetails, Scope};
+
+
+/// Handles requests from a presenter to create a new presentation, open to questions. A
+/// presentation created in one of the presenter's organizations starts out with the organization's
+/// default settings.
+pub struct CreatePresentationHandler<DB> {
+    database: DB,
+}
+
+/// Handles requests from a presenter to change the details of one of their presentations. Every
+/// detail is replaced, so optional details that are left out are cleared.
+pub struct UpdateDetailsHandler<DB> {
+    database: DB,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct CreatePresentationRequest {
+    #[serde(rename = "sessionToken")]
+    pub session_token: Id,
+    #[serde(rename = "organization", default)]
+    pub organization_id: Option<Id>,
+    #[serde(flatten)]
+    pub details: PresentationDetails,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct UpdateDetailsRequest {
+    #[serde(rename = "sessionToken")]
+    pub session_token: Id,
+    #[serde(rename = "presentation")]
+    pub presentation_id: Id,
+    #[serde(flatten)]
+    pub details: PresentationDetails,
+}
+
+#[derive(Debug, Serialize)]
+struct PresentationResponse {
+    pub error: Option<String>,
+    pub presentation: Option<Presentation>,
+}
+
+impl<DB> CreatePresentationHandler<DB> {
+    pub fn new(db: DB) -> Self {
+        CreatePresentationHandler {
+            database: db,
+        }
+    }
+}
+
+impl<DB> UpdateDetailsHandler<DB> {
+    pub fn new(db: DB) -> Self {
+        UpdateDetailsHandler {
+            database: db,
+        }
+    }
+}
+
+impl<DB> Handler for CreatePresentationHandler<DB>
+    where DB: 'static + Sync + Send
+        + Capability<Save<Presentation>, Data = Presentation, Error = String>
+        + Capability<Search<Organization>, Data = Organization, Error = String>
+        + Authenticate
+        + Authorize
+{
+    fn handle(&self, request: &mut Request) -> IronResult<Response> {
+        let request_data = decode_body_or_write_error!(request, CreatePresentationRequest, |_: Option<&Error>| PresentationResponse {
+            error: Some("Missing or invalid request data.".to_string()),
+            presentation: None,
+        });
+        let db_result = try_do!({
+            let presenter = auth::authenticate(&self.database, request_data.session_token, Scope::PresentationsWrite)?;
+            let details = request_data.details.validate()?;
+            let mut presentation = Presentation::new(presenter, String::new());
+            presentation.set_details(details);
+            if let Some(organization) = request_data.organization_id {
+                if auth::organization_role_of(&self.database, &presentation.creator, &organization).is_none() {
+                    return Err("You are not allowed to do that!".to_string());
+                }
+                let organization = self.database.perform(Search(Organization::search_parameter(organization)))?;
+                presentation.join_organization(organization.id, organization.defaults);
+            }
+            self.database.perform(Save(presentation))
+        });
+        match db_result {
+            Ok(presentation) => json_response!(status::Ok, PresentationResponse {
+                error: None,
+                presentation: Some(presentation),
+            }),
+            Err(err) => json_response!(status::BadRequest, PresentationResponse {
+                error: Some(err),
+                presentation: None,
+            }),
+        }
+    }
+}
+
+impl<DB> Handler for UpdateDetailsHandler<DB>
+    where DB: 'static + Sync + Send
+        + Capability<Search<Presentation>, Data = Presentation, Error = String>
+        + Capability<Update<Presentation>, Data = Presentation, Error = String>
+        + Authenticate
+        + Authorize
+{
+    fn handle(&self, request: &mut Request) -> IronResult<Response> {
+        let request_data = decode_body_or_write_error!(request, UpdateDetailsRequest, |_: Option<&Error>| PresentationResponse {
+            error: Some("Missing or invalid request data.".to_string()),
+            presentation: None,
+        });
+        let db_result = try_do!({
+            let presentation = Presentation::search_parameter(request_data.presentation_id);
+            let mut presentation = self.database.perform(Search(presentation))?;
+            auth::authorize(
+                &self.database, request_data.session_token, Scope::PresentationsWrite, &presentation, Action::ChangeSettings)?;
+            presentation.set_details(request_data.details.validate()?);
+            self.database.perform(Update(presentation))
+        });
+        match db_result {
+            Ok(presentation) => json_response!(status::Ok, PresentationResponse {
+                error: None,
+                presentation: Some(presentation),
+            }),
+            Err(err) => json_response!(status::BadRequest, PresentationResponse {
+                error: Some(err),
+                presentation: None,
+            }),
+        }
+    }
+}
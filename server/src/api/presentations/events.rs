@@ -0,0 +1,70 @@
+use std::sync::Arc;
+
+use iron::prelude::*;
+use iron::Handler;
+use iron::status;
+use router::Router;
+
+use events::{Event, EventLog};
+use models::Id;
+
+
+/// Handles requests from clients for the changes to a presentation that happened after the event
+/// numbered `after`, so that they can poll for updates. Clients that have seen no events yet can
+/// leave `after` out.
+pub struct EventsHandler {
+    events: Arc<EventLog>,
+}
+
+#[derive(Debug, Serialize)]
+struct EventsResponse {
+    pub error: Option<String>,
+    pub events: Vec<Event>,
+    /// The number of the latest event, which the client should poll after next.
+    pub latest: u64,
+}
+
+impl EventsHandler {
+    pub fn new(events: Arc<EventLog>) -> Self {
+        EventsHandler {
+            events: events,
+        }
+    }
+}
+
+impl Handler for EventsHandler {
+    fn handle(&self, request: &mut Request) -> IronResult<Response> {
+        let presentation_id = request.extensions
+            .get::<Router>()
+            .and_then(|router| router.find("id"))
+            .map(|id| Id(id.to_string()));
+        let input_err = "Missing or invalid request data.".to_string();
+        let after = decode_query_or_write_error!(
+            request,
+            extract = |query| match query.get("after").and_then(|strings| strings.first()) {
+                Some(after) => after.parse::<u64>().ok(),
+                None        => Some(0),
+            },
+            missing = EventsResponse {
+                error: Some(input_err),
+                events: vec![],
+                latest: 0,
+            }
+        );
+        let result = presentation_id
+            .ok_or("Missing presentation.".to_string())
+            .and_then(|presentation_id| self.events.since(&presentation_id, after));
+        match result {
+            Ok((events, latest)) => json_response!(status::Ok, EventsResponse {
+                error: None,
+                events: events,
+                latest: latest,
+            }),
+            Err(err) => json_response!(status::BadRequest, EventsResponse {
+                error: Some(err),
+                events: vec![],
+                latest: 0,
+            }),
+        }
+    }
+}
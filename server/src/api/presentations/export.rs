@@ -0,0 +1,118 @@
+use std::io::{self, Write};
+
+use iron::prelude::*;
+use iron::Handler;
+use iron::mime::{Mime, TopLevel, SubLevel};
+use iron::response::WriteBody;
+use iron::status;
+use router::Router;
+
+use auth::{self, Authenticate, Authorize};
+use capabilities::{Capability, FindAll, Search};
+use capabilities::sqlite::{AnswersForQuestion, QuestionsPage};
+use export::{self, Format};
+use models::{Id, Action, Answer, Presentation, Question, Scope};
+
+
+/// Handles requests from a presenter to download the questions asked during one of their
+/// presentations, and their answers, as a CSV, JSON or Markdown document.
+pub struct ExportHandler<DB> {
+    database: DB,
+}
+
+#[derive(Debug, Serialize)]
+struct ExportResponse {
+    pub error: Option<String>,
+}
+
+/// Writes an export into the response a page of questions at a time, so that the whole document
+/// is never held in memory.
+struct ExportBody<DB> {
+    database: DB,
+    presentation: Presentation,
+    format: Format,
+}
+
+impl<DB> ExportHandler<DB> {
+    pub fn new(db: DB) -> Self {
+        ExportHandler {
+            database: db,
+        }
+    }
+}
+
+impl<DB> WriteBody for ExportBody<DB>
+    where DB: Send
+        + Capability<FindAll<QuestionsPage>, Data = Vec<Question>, Error = String>
+        + Capability<FindAll<AnswersForQuestion>, Data = Vec<Answer>, Error = String>
+{
+    fn write_body(&mut self, out: &mut Write) -> io::Result<()> {
+        let format = self.format;
+        let mut first = true;
+        export::write_header(format, &self.presentation, out)?;
+        export::for_each_question(&self.database, &self.presentation.id, |entry| {
+            let written = export::write_question(format, first, &entry, out).map_err(|err| err.to_string());
+            first = false;
+            written
+        }).map_err(|err| io::Error::new(io::ErrorKind::Other, err))?;
+        export::write_footer(format, out)
+    }
+}
+
+impl<DB> Handler for ExportHandler<DB>
+    where DB: 'static + Sync + Send + Clone
+        + Capability<Search<Presentation>, Data = Presentation, Error = String>
+        + Capability<FindAll<QuestionsPage>, Data = Vec<Question>, Error = String>
+        + Capability<FindAll<AnswersForQuestion>, Data = Vec<Answer>, Error = String>
+        + Authenticate
+        + Authorize
+{
+    fn handle(&self, request: &mut Request) -> IronResult<Response> {
+        let presentation_id = request.extensions
+            .get::<Router>()
+            .and_then(|router| router.find("id"))
+            .map(|id| Id(id.to_string()));
+        let input_err = "Missing or invalid request data.".to_string();
+        let (session_token, format) = decode_query_or_write_error!(
+            request,
+            extract = |query| {
+                let param = |name: &str| query.get(name).and_then(|strings| strings.first());
+                let format = param("format").map(|format| Format::from_str(format)).unwrap_or(Some(Format::Json));
+                match (param("sessionToken"), format) {
+                    (Some(token), Some(format)) => Some((Id(token.clone()), format)),
+                    _                           => None,
+                }
+            },
+            missing = ExportResponse {
+                error: Some(input_err),
+            }
+        );
+        let db_result = try_do!({
+            let presentation_id = presentation_id.ok_or("Missing presentation.".to_string())?;
+            let presentation = self.database.perform(Search(Presentation::search_parameter(presentation_id)))?;
+            auth::authorize(
+                &self.database, session_token, Scope::QuestionsRead, &presentation, Action::ViewQuestions)?;
+            Ok(presentation)
+        });
+        let presentation = match db_result {
+            Ok(presentation) => presentation,
+            Err(err) => return json_response!(status::BadRequest, ExportResponse {
+                error: Some(err),
+            }),
+        };
+        let mime = match format {
+            Format::Csv      => Mime(TopLevel::Text, SubLevel::Ext("csv".to_string()), vec![]),
+            Format::Json     => Mime(TopLevel::Application, SubLevel::Json, vec![]),
+            Format::Markdown => Mime(TopLevel::Text, SubLevel::Ext("markdown".to_string()), vec![]),
+        };
+        let disposition = format!("attachment; filename=\"presentation-{}.{}\"", presentation.id.0, format.extension());
+        let body: Box<WriteBody> = Box::new(ExportBody {
+            database: self.database.clone(),
+            presentation: presentation,
+            format: format,
+        });
+        let mut response = Response::with((mime, status::Ok, body));
+        response.headers.set_raw("Content-Disposition", vec![disposition.into_bytes()]);
+        Ok(response)
+    }
+}
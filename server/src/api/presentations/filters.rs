@@ -0,0 +1,72 @@
+use std::error::Error;
+
+use iron::prelude::*;
+use iron::Handler;
+use iron::status;
+
+use auth::{self, Authenticate, Authorize};
+use capabilities::{Capability, Search, Update};
+use models::{Id, Action, FilterSetting, Presentation, Scope};
+
+
+/// Handles requests from a presenter to replace the filters that questions asked during one of
+/// their presentations are run through.
+pub struct SetFiltersHandler<DB> {
+    database: DB,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct SetFiltersRequest {
+    #[serde(rename = "sessionToken")]
+    pub session_token: Id,
+    #[serde(rename = "presentation")]
+    pub presentation_id: Id,
+    pub filters: Vec<FilterSetting>,
+}
+
+#[derive(Debug, Serialize)]
+struct SetFiltersResponse {
+    pub error: Option<String>,
+    pub filters: Option<Vec<FilterSetting>>,
+}
+
+impl<DB> SetFiltersHandler<DB> {
+    pub fn new(db: DB) -> Self {
+        SetFiltersHandler {
+            database: db,
+        }
+    }
+}
+
+impl<DB> Handler for SetFiltersHandler<DB>
+    where DB: 'static + Sync + Send
+        + Capability<Search<Presentation>, Data = Presentation, Error = String>
+        + Capability<Update<Presentation>, Data = Presentation, Error = String>
+        + Authenticate
+        + Authorize
+{
+    fn handle(&self, request: &mut Request) -> IronResult<Response> {
+        let request_data = decode_body_or_write_error!(request, SetFiltersRequest, |_: Option<&Error>| SetFiltersResponse {
+            error: Some("Missing or invalid request data.".to_string()),
+            filters: None,
+        });
+        let db_result = try_do!({
+            let presentation = Presentation::search_parameter(request_data.presentation_id);
+            let mut presentation = self.database.perform(Search(presentation))?;
+            auth::authorize(
+                &self.database, request_data.session_token, Scope::PresentationsWrite, &presentation, Action::ChangeSettings)?;
+            presentation.filters = request_data.filters.clone();
+            self.database.perform(Update(presentation))
+        });
+        match db_result {
+            Ok(presentation) => json_response!(status::Ok, SetFiltersResponse {
+                error: None,
+                filters: Some(presentation.filters),
+            }),
+            Err(err) => json_response!(status::BadRequest, SetFiltersResponse {
+                error: Some(err),
+                filters: None,
+            }),
+        }
+    }
+}
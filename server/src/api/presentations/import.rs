@@ -0,0 +1,133 @@
+use std::error::Error;
+
+use iron::prelude::*;
+use iron::Handler;
+use iron::status;
+
+use auth::{self, Authenticate, Authorize};
+use capabilities::{Capability, Save, Search};
+use capabilities::sqlite::Import;
+use export::Format;
+use import::{self, RowError};
+use models::{Id, Action, Presentation, Scope};
+
+
+/// Handles requests from a presenter to import the questions in a CSV or JSON document, along with
+/// their answers, either into one of their presentations or into a new one. Nothing is imported
+/// unless every question in the document is valid.
+pub struct ImportHandler<DB> {
+    database: DB,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct ImportRequest {
+    #[serde(rename = "sessionToken")]
+    pub session_token: Id,
+    pub format: String,
+    pub document: String,
+    /// The presentation to import into. A new presentation is created when this is left out.
+    #[serde(rename = "presentation")]
+    pub presentation_id: Option<Id>,
+    /// The title of the new presentation, if the document does not provide one.
+    pub title: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct ImportResponse {
+    pub error: Option<String>,
+    pub errors: Vec<RowError>,
+    pub presentation: Option<Presentation>,
+    pub imported: usize,
+}
+
+impl<DB> ImportHandler<DB> {
+    pub fn new(db: DB) -> Self {
+        ImportHandler {
+            database: db,
+        }
+    }
+}
+
+impl<DB> Handler for ImportHandler<DB>
+    where DB: 'static + Sync + Send
+        + Capability<Search<Presentation>, Data = Presentation, Error = String>
+        + Capability<Save<Import>, Data = Import, Error = String>
+        + Authenticate
+        + Authorize
+{
+    fn handle(&self, request: &mut Request) -> IronResult<Response> {
+        let request_data = decode_body_or_write_error!(request, ImportRequest, |_: Option<&Error>| ImportResponse {
+            error: Some("Missing or invalid request data.".to_string()),
+            errors: vec![],
+            presentation: None,
+            imported: 0,
+        });
+        let db_result = try_do!({
+            let format = Format::from_str(&request_data.format).ok_or("Unknown document format.".to_string())?;
+            let token = request_data.session_token;
+            match request_data.presentation_id {
+                Some(id) => {
+                    let presentation = self.database.perform(Search(Presentation::search_parameter(id)))?;
+                    let presenter = auth::authorize(
+                        &self.database, token, Scope::PresentationsWrite, &presentation, Action::ChangeSettings)?;
+                    Ok((presenter, format, Some(presentation)))
+                },
+                None => {
+                    let presenter = auth::authenticate(&self.database, token, Scope::PresentationsWrite)?;
+                    Ok((presenter, format, None))
+                },
+            }
+        });
+        let (presenter, format, existing) = match db_result {
+            Ok(found) => found,
+            Err(err) => return json_response!(status::BadRequest, ImportResponse {
+                error: Some(err),
+                errors: vec![],
+                presentation: None,
+                imported: 0,
+            }),
+        };
+        let document = match import::parse(format, &request_data.document) {
+            Ok(document) => document,
+            Err(errors) => return json_response!(status::BadRequest, ImportResponse {
+                error: Some("The document could not be imported.".to_string()),
+                errors: errors,
+                presentation: None,
+                imported: 0,
+            }),
+        };
+        let db_result = try_do!({
+            let new_presentation = existing.is_none();
+            let presentation = match existing {
+                Some(presentation) => presentation,
+                None => {
+                    let title = request_data.title.clone().or(document.title.clone())
+                        .map(|title| title.trim().to_string())
+                        .filter(|title| !title.is_empty())
+                        .ok_or("A title is needed for the new presentation.".to_string())?;
+                    Presentation::new(presenter, title)
+                },
+            };
+            let questions = import::records(document, &presentation);
+            self.database.perform(Save(Import {
+                presentation: presentation,
+                new_presentation: new_presentation,
+                questions: questions,
+            }))
+        });
+        match db_result {
+            Ok(import) => json_response!(status::Ok, ImportResponse {
+                error: None,
+                errors: vec![],
+                imported: import.questions.len(),
+                presentation: Some(import.presentation),
+            }),
+            Err(err) => json_response!(status::BadRequest, ImportResponse {
+                error: Some(err),
+                errors: vec![],
+                presentation: None,
+                imported: 0,
+            }),
+        }
+    }
+}
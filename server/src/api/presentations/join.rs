@@ -0,0 +1,54 @@
+use iron::prelude::*;
+use iron::Handler;
+use iron::status;
+use router::Router;
+
+use capabilities::{Capability, Search};
+use models::{JoinCode, Presentation, PublicPresentation};
+
+
+/// Handles requests from audience members to find the presentation a join code belongs to. Only
+/// the public view of the presentation is produced, since anyone holding the code may ask.
+pub struct JoinHandler<DB> {
+    database: DB,
+}
+
+#[derive(Debug, Serialize)]
+struct JoinResponse {
+    pub error: Option<String>,
+    pub presentation: Option<PublicPresentation>,
+}
+
+impl<DB> JoinHandler<DB> {
+    pub fn new(db: DB) -> Self {
+        JoinHandler {
+            database: db,
+        }
+    }
+}
+
+impl<DB> Handler for JoinHandler<DB>
+    where DB: 'static + Sync + Send
+        + Capability<Search<JoinCode>, Data = Presentation, Error = String>
+{
+    fn handle(&self, request: &mut Request) -> IronResult<Response> {
+        let code = request.extensions
+            .get::<Router>()
+            .and_then(|router| router.find("code"))
+            .map(JoinCode::normalize);
+        let db_result = match code {
+            Some(ref code) if code.is_well_formed() => self.database.perform(Search(code.clone())),
+            _ => Err("Invalid join code.".to_string()),
+        };
+        match db_result {
+            Ok(presentation) => json_response!(status::Ok, JoinResponse {
+                error: None,
+                presentation: Some(presentation.public_view()),
+            }),
+            _ => json_response!(status::NotFound, JoinResponse {
+                error: Some("No open presentation has that join code.".to_string()),
+                presentation: None,
+            }),
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use std::error::Error;
+
+use iron::prelude::*;
+use iron::Handler;
+use iron::status;
+
+use auth::{self, Authenticate, Authorize};
+use capabilities::{Capability, Search, Update};
+use models::{Id, Action, JoinCode, Presentation, Scope};
+
+
+/// Handles requests from a presenter to replace the join code of one of their presentations, such
+/// as when the old one has been shared further than intended.
+pub struct RegenerateJoinCodeHandler<DB> {
+    database: DB,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct RegenerateRequest {
+    #[serde(rename = "sessionToken")]
+    pub session_token: Id,
+    #[serde(rename = "presentation")]
+    pub presentation_id: Id,
+}
+
+#[derive(Debug, Serialize)]
+struct RegenerateResponse {
+    pub error: Option<String>,
+    #[serde(rename = "joinCode")]
+    pub join_code: Option<JoinCode>,
+}
+
+impl<DB> RegenerateJoinCodeHandler<DB> {
+    pub fn new(db: DB) -> Self {
+        RegenerateJoinCodeHandler {
+            database: db,
+        }
+    }
+}
+
+impl<DB> Handler for RegenerateJoinCodeHandler<DB>
+    where DB: 'static + Sync + Send
+        + Capability<Search<Presentation>, Data = Presentation, Error = String>
+        + Capability<Update<Presentation>, Data = Presentation, Error = String>
+        + Authenticate
+        + Authorize
+{
+    fn handle(&self, request: &mut Request) -> IronResult<Response> {
+        let request_data = decode_body_or_write_error!(request, RegenerateRequest, |_: Option<&Error>| RegenerateResponse {
+            error: Some("Missing or invalid request data.".to_string()),
+            join_code: None,
+        });
+        let db_result = try_do!({
+            let presentation = Presentation::search_parameter(request_data.presentation_id);
+            let mut presentation = self.database.perform(Search(presentation))?;
+            auth::authorize(
+                &self.database, request_data.session_token, Scope::PresentationsWrite, &presentation, Action::ChangeSettings)?;
+            if !presentation.is_open_to_questions {
+                return Err("Closed presentations cannot be joined.".to_string());
+            }
+            presentation.join_code = Some(JoinCode::generate());
+            self.database.perform(Update(presentation))
+        });
+        match db_result {
+            Ok(presentation) => json_response!(status::Ok, RegenerateResponse {
+                error: None,
+                join_code: presentation.join_code,
+            }),
+            Err(err) => json_response!(status::BadRequest, RegenerateResponse {
+                error: Some(err),
+                join_code: None,
+            }),
+        }
+    }
+}
@@ -2,12 +2,17 @@ use iron::prelude::*;
 use iron::Handler;
 use iron::status;
 
+use auth::{self, Authenticate};
 use capabilities::{Capability, FindAll};
 use capabilities::sqlite::PresentationsForPresenter;
-use models::{Id, Presentation};
+use models::{Id, PresentationWithRole, PublicPresentation, Scope};
 
 
-/// Handles requests to get a list of presentations created by a presenter.
+/// Handles requests to get a list of presentations created by a presenter or that they are a
+/// member of, optionally only those with a particular `tag`.
+///
+/// Only the public view of each presentation is listed, unless the `sessionToken` given signs in
+/// as the presenter themselves, who is also told their role in each.
 pub struct ListHandler<DB> {
     database: DB,
 }
@@ -15,12 +20,14 @@ pub struct ListHandler<DB> {
 #[derive(Clone, Debug, Deserialize)]
 struct ListRequest {
     pub presenter: Id,
+    #[serde(rename = "sessionToken")]
+    pub session_token: Option<Id>,
 }
 
 #[derive(Debug, Serialize)]
-struct ListResponse {
+struct ListResponse<P> {
     pub error: Option<String>,
-    pub presentations: Vec<Presentation>,
+    pub presentations: Vec<P>,
 }
 
 impl<DB> ListHandler<DB> {
@@ -33,29 +40,45 @@ impl<DB> ListHandler<DB> {
 
 impl<DB> Handler for ListHandler<DB> 
     where DB: 'static + Sync + Send
-        + Capability<FindAll<PresentationsForPresenter>, Data = Vec<Presentation>>
+        + Capability<FindAll<PresentationsForPresenter>, Data = Vec<PresentationWithRole>>
+        + Authenticate
 {
     fn handle(&self, request: &mut Request) -> IronResult<Response> {
         let input_err = "Missing or invalid request data.".to_string();
-        let presenter_id = decode_query_or_write_error!(
+        let (request_data, tag) = decode_query_or_write_error!(
             request,
-            extract = |query| query
-                .get("presenter")
-                .and_then(|strings| strings.first())
-                .map(|id| Id(id.clone())),
-            missing = ListResponse {
+            extract = |query| {
+                let param = |name: &str| query.get(name).and_then(|strings| strings.first()).cloned();
+                param("presenter").map(|id| (ListRequest {
+                    presenter: Id(id),
+                    session_token: param("sessionToken").map(Id),
+                }, param("tag")))
+            },
+            missing = ListResponse::<PublicPresentation> {
                 error: Some(input_err),
                 presentations: vec![],
             });
+        let is_own = request_data.session_token
+            .and_then(|token| auth::authenticate(&self.database, token, Scope::PresentationsRead).ok())
+            .map(|presenter| presenter == request_data.presenter)
+            .unwrap_or(false);
         let db_result = self.database.perform(FindAll(PresentationsForPresenter {
-            presenter_id: presenter_id,
+            presenter_id: request_data.presenter,
+            tag: tag,
         }));
         match db_result {
-            Ok(presentations) => json_response!(status::Ok, ListResponse {
-                error: None,
-                presentations: presentations,
-            }),
-            _ => json_response!(status::BadRequest, ListResponse {
+            Ok(presentations) => if is_own {
+                json_response!(status::Ok, ListResponse {
+                    error: None,
+                    presentations: presentations,
+                })
+            } else {
+                json_response!(status::Ok, ListResponse {
+                    error: None,
+                    presentations: presentations.iter().map(|found| found.presentation.public_view()).collect(),
+                })
+            },
+            _ => json_response!(status::BadRequest, ListResponse::<PublicPresentation> {
                 error: Some("Unknown presenter.".to_string()),
                 presentations: vec![],
             }),
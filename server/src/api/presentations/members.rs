@@ -0,0 +1,282 @@
+use std::error::Error;
+
+use chrono::prelude::*;
+use iron::prelude::*;
+use iron::Handler;
+use iron::status;
+use router::Router;
+
+use auth::{self, Authenticate, Authorize};
+use capabilities::{Capability, Delete, FindAll, Save, Search};
+use capabilities::sqlite::MembersOfPresentation;
+use mailer::{Mailer, Message};
+use models::{Id, Action, Invitation, Membership, Presentation, Role, Scope};
+
+
+/// Handles requests from the owner of a presentation to invite another presenter, by email, to
+/// take on a role in it.
+pub struct InviteMemberHandler<DB, M> {
+    database: DB,
+    mailer: M,
+}
+
+/// Handles requests from a presenter to accept an invitation sent to their email address.
+pub struct AcceptInvitationHandler<DB> {
+    database: DB,
+}
+
+/// Handles requests from the members of a presentation to see who else takes part in it.
+pub struct ListMembersHandler<DB> {
+    database: DB,
+}
+
+/// Handles requests from the owner of a presentation to take away another presenter's role in it.
+pub struct RemoveMemberHandler<DB> {
+    database: DB,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct InviteRequest {
+    #[serde(rename = "sessionToken")]
+    pub session_token: Id,
+    #[serde(rename = "presentation")]
+    pub presentation_id: Id,
+    pub email: Id,
+    pub role: Role,
+}
+
+#[derive(Debug, Serialize)]
+struct InviteResponse {
+    pub error: Option<String>,
+    pub invitation: Option<Invitation>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct AcceptRequest {
+    #[serde(rename = "sessionToken")]
+    pub session_token: Id,
+    pub token: Id,
+}
+
+#[derive(Debug, Serialize)]
+struct MembershipResponse {
+    pub error: Option<String>,
+    pub membership: Option<Membership>,
+}
+
+#[derive(Debug, Serialize)]
+struct ListMembersResponse {
+    pub error: Option<String>,
+    pub owner: Option<Id>,
+    pub members: Vec<Membership>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct RemoveRequest {
+    #[serde(rename = "sessionToken")]
+    pub session_token: Id,
+    #[serde(rename = "presentation")]
+    pub presentation_id: Id,
+    pub member: Id,
+}
+
+#[derive(Debug, Serialize)]
+struct RemoveResponse {
+    pub error: Option<String>,
+}
+
+impl<DB, M> InviteMemberHandler<DB, M> {
+    pub fn new(db: DB, mailer: M) -> Self {
+        InviteMemberHandler {
+            database: db,
+            mailer: mailer,
+        }
+    }
+}
+
+impl<DB> AcceptInvitationHandler<DB> {
+    pub fn new(db: DB) -> Self {
+        AcceptInvitationHandler {
+            database: db,
+        }
+    }
+}
+
+impl<DB> ListMembersHandler<DB> {
+    pub fn new(db: DB) -> Self {
+        ListMembersHandler {
+            database: db,
+        }
+    }
+}
+
+impl<DB> RemoveMemberHandler<DB> {
+    pub fn new(db: DB) -> Self {
+        RemoveMemberHandler {
+            database: db,
+        }
+    }
+}
+
+impl<DB, M> Handler for InviteMemberHandler<DB, M>
+    where DB: 'static + Sync + Send
+        + Capability<Search<Presentation>, Data = Presentation, Error = String>
+        + Capability<Save<Invitation>, Data = Invitation, Error = String>
+        + Authenticate
+        + Authorize,
+          M: 'static + Sync + Send + Mailer
+{
+    fn handle(&self, request: &mut Request) -> IronResult<Response> {
+        let request_data = decode_body_or_write_error!(request, InviteRequest, |_: Option<&Error>| InviteResponse {
+            error: Some("Missing or invalid request data.".to_string()),
+            invitation: None,
+        });
+        let db_result = try_do!({
+            let presentation = Presentation::search_parameter(request_data.presentation_id);
+            let presentation = self.database.perform(Search(presentation))?;
+            let presenter = auth::authorize(
+                &self.database, request_data.session_token, Scope::PresentationsWrite, &presentation, Action::ManageMembers)?;
+            let email = Id(request_data.email.0.trim().to_string());
+            if !email.0.contains('@') {
+                return Err("Invalid email address.".to_string());
+            }
+            let invitation = Invitation::new(presentation.id.clone(), email, request_data.role, presenter);
+            let invitation = self.database.perform(Save(invitation))?;
+            // The invitation can be sent again if the email never arrives.
+            let _ = self.mailer.send(Message::new(
+                invitation.email.0.clone(),
+                format!("You have been invited to \"{}\" on AsQ", presentation.title),
+                format!("{} has invited you to join their presentation \"{}\" as a {}. Sign in and use the \
+                         following token to accept.\n\n{}",
+                        invitation.invited_by.0, presentation.title, invitation.role.as_str(), invitation.token.0)));
+            Ok(invitation)
+        });
+        match db_result {
+            Ok(invitation) => json_response!(status::Ok, InviteResponse {
+                error: None,
+                invitation: Some(invitation),
+            }),
+            Err(err) => json_response!(status::BadRequest, InviteResponse {
+                error: Some(err),
+                invitation: None,
+            }),
+        }
+    }
+}
+
+impl<DB> Handler for AcceptInvitationHandler<DB>
+    where DB: 'static + Sync + Send
+        + Capability<Search<Invitation>, Data = Invitation, Error = String>
+        + Capability<Delete<Invitation>, Data = (), Error = String>
+        + Capability<Save<Membership>, Data = Membership, Error = String>
+        + Authenticate
+{
+    fn handle(&self, request: &mut Request) -> IronResult<Response> {
+        let request_data = decode_body_or_write_error!(request, AcceptRequest, |_: Option<&Error>| MembershipResponse {
+            error: Some("Missing or invalid request data.".to_string()),
+            membership: None,
+        });
+        let db_result = try_do!({
+            let presenter = auth::authenticate(&self.database, request_data.session_token, Scope::PresentationsWrite)?;
+            let invitation = self.database.perform(Search(Invitation::search_parameter(request_data.token)))?;
+            if !invitation.may_be_accepted_by(&presenter, Utc::now()) {
+                return Err("No such invitation.".to_string());
+            }
+            let membership = Membership::new(invitation.presentation.clone(), presenter, invitation.role);
+            let membership = self.database.perform(Save(membership))?;
+            self.database.perform(Delete(invitation))?;
+            Ok(membership)
+        });
+        match db_result {
+            Ok(membership) => json_response!(status::Ok, MembershipResponse {
+                error: None,
+                membership: Some(membership),
+            }),
+            Err(err) => json_response!(status::BadRequest, MembershipResponse {
+                error: Some(err),
+                membership: None,
+            }),
+        }
+    }
+}
+
+impl<DB> Handler for ListMembersHandler<DB>
+    where DB: 'static + Sync + Send
+        + Capability<Search<Presentation>, Data = Presentation, Error = String>
+        + Capability<FindAll<MembersOfPresentation>, Data = Vec<Membership>, Error = String>
+        + Authenticate
+        + Authorize
+{
+    fn handle(&self, request: &mut Request) -> IronResult<Response> {
+        let presentation_id = request.extensions
+            .get::<Router>()
+            .and_then(|router| router.find("id"))
+            .map(|id| Id(id.to_string()));
+        let input_err = "Missing or invalid request data.".to_string();
+        let session_token = decode_query_or_write_error!(
+            request,
+            extract = |query| query.get("sessionToken")
+                .and_then(|strings| strings.first())
+                .map(|token| Id(token.clone())),
+            missing = ListMembersResponse {
+                error: Some(input_err),
+                owner: None,
+                members: vec![],
+            }
+        );
+        let db_result = try_do!({
+            let presentation_id = presentation_id.ok_or("Missing presentation.".to_string())?;
+            let presentation = self.database.perform(Search(Presentation::search_parameter(presentation_id)))?;
+            auth::authorize(
+                &self.database, session_token, Scope::PresentationsRead, &presentation, Action::ViewQuestions)?;
+            let members = self.database.perform(FindAll(MembersOfPresentation {
+                presentation_id: presentation.id,
+            }))?;
+            Ok((presentation.creator, members))
+        });
+        match db_result {
+            Ok((owner, members)) => json_response!(status::Ok, ListMembersResponse {
+                error: None,
+                owner: Some(owner),
+                members: members,
+            }),
+            Err(err) => json_response!(status::BadRequest, ListMembersResponse {
+                error: Some(err),
+                owner: None,
+                members: vec![],
+            }),
+        }
+    }
+}
+
+impl<DB> Handler for RemoveMemberHandler<DB>
+    where DB: 'static + Sync + Send
+        + Capability<Search<Presentation>, Data = Presentation, Error = String>
+        + Capability<Delete<Membership>, Data = (), Error = String>
+        + Authenticate
+        + Authorize
+{
+    fn handle(&self, request: &mut Request) -> IronResult<Response> {
+        let request_data = decode_body_or_write_error!(request, RemoveRequest, |_: Option<&Error>| RemoveResponse {
+            error: Some("Missing or invalid request data.".to_string()),
+        });
+        let db_result = try_do!({
+            let presentation = Presentation::search_parameter(request_data.presentation_id);
+            let presentation = self.database.perform(Search(presentation))?;
+            auth::authorize(
+                &self.database, request_data.session_token, Scope::PresentationsWrite, &presentation, Action::ManageMembers)?;
+            if request_data.member == presentation.creator {
+                return Err("The creator of a presentation cannot be removed from it.".to_string());
+            }
+            self.database.perform(Delete(Membership::search_parameter(presentation.id, request_data.member)))
+        });
+        match db_result {
+            Ok(()) => json_response!(status::Ok, RemoveResponse {
+                error: None,
+            }),
+            Err(err) => json_response!(status::BadRequest, RemoveResponse {
+                error: Some(err),
+            }),
+        }
+    }
+}
@@ -1 +1,21 @@
+pub mod access;
+pub mod audience;
+pub mod clone;
+pub mod dashboard;
+pub mod details;
+pub mod events;
+pub mod export;
+pub mod filters;
+pub mod import;
+pub mod join;
+pub mod join_code;
 pub mod list;
+pub mod members;
+pub mod moderation;
+pub mod qr_code;
+pub mod rate_limits;
+pub mod report;
+pub mod retention;
+pub mod schedule;
+pub mod stats;
+pub mod transfer;
@@ -0,0 +1,86 @@
+use std::error::Error;
+
+use iron::prelude::*;
+use iron::Handler;
+use iron::status;
+
+use auth::{self, Authenticate, Authorize};
+use capabilities::{Capability, Search, Update};
+use models::{Id, Action, Presentation, Scope};
+
+
+/// Handles requests from a presenter to change how questions asked during one of their
+/// presentations are moderated: whether new questions must be approved before the audience sees
+/// them, and how many flags hide a question for review. Settings left out are unchanged.
+pub struct SetModerationHandler<DB> {
+    database: DB,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct SetModerationRequest {
+    #[serde(rename = "sessionToken")]
+    pub session_token: Id,
+    #[serde(rename = "presentation")]
+    pub presentation_id: Id,
+    #[serde(rename = "requiresApproval")]
+    pub requires_approval: Option<bool>,
+    #[serde(rename = "flagThreshold")]
+    pub flag_threshold: Option<u32>,
+}
+
+#[derive(Debug, Serialize)]
+struct SetModerationResponse {
+    pub error: Option<String>,
+    #[serde(rename = "requiresApproval")]
+    pub requires_approval: Option<bool>,
+    #[serde(rename = "flagThreshold")]
+    pub flag_threshold: Option<u32>,
+}
+
+impl<DB> SetModerationHandler<DB> {
+    pub fn new(db: DB) -> Self {
+        SetModerationHandler {
+            database: db,
+        }
+    }
+}
+
+impl<DB> Handler for SetModerationHandler<DB>
+    where DB: 'static + Sync + Send
+        + Capability<Search<Presentation>, Data = Presentation, Error = String>
+        + Capability<Update<Presentation>, Data = Presentation, Error = String>
+        + Authenticate
+        + Authorize
+{
+    fn handle(&self, request: &mut Request) -> IronResult<Response> {
+        let request_data = decode_body_or_write_error!(request, SetModerationRequest, |_: Option<&Error>| SetModerationResponse {
+            error: Some("Missing or invalid request data.".to_string()),
+            requires_approval: None,
+            flag_threshold: None,
+        });
+        let db_result = try_do!({
+            let presentation = Presentation::search_parameter(request_data.presentation_id);
+            let mut presentation = self.database.perform(Search(presentation))?;
+            auth::authorize(
+                &self.database, request_data.session_token, Scope::PresentationsWrite, &presentation, Action::ChangeSettings)?;
+            if request_data.flag_threshold == Some(0) {
+                return Err("At least one flag must be needed to hide a question.".to_string());
+            }
+            presentation.requires_approval = request_data.requires_approval.unwrap_or(presentation.requires_approval);
+            presentation.flag_threshold = request_data.flag_threshold.unwrap_or(presentation.flag_threshold);
+            self.database.perform(Update(presentation))
+        });
+        match db_result {
+            Ok(presentation) => json_response!(status::Ok, SetModerationResponse {
+                error: None,
+                requires_approval: Some(presentation.requires_approval),
+                flag_threshold: Some(presentation.flag_threshold),
+            }),
+            Err(err) => json_response!(status::BadRequest, SetModerationResponse {
+                error: Some(err),
+                requires_approval: None,
+                flag_threshold: None,
+            }),
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use iron::prelude::*;
+use iron::Handler;
+use iron::mime::{Mime, TopLevel, SubLevel};
+use iron::status;
+use router::Router;
+
+use capabilities::{Capability, Search};
+use models::{Id, Presentation};
+use qr;
+
+
+/// The image formats that QR codes can be rendered in.
+#[derive(Clone, Copy, Debug)]
+pub enum QrFormat {
+    Svg,
+    Png,
+}
+
+/// Handles requests for a QR code linking to the page audience members join a presentation from,
+/// which presenters can put on their slides.
+pub struct QrCodeHandler<DB> {
+    database: DB,
+    public_base_url: String,
+    format: QrFormat,
+}
+
+#[derive(Debug, Serialize)]
+struct QrCodeResponse {
+    pub error: Option<String>,
+}
+
+impl<DB> QrCodeHandler<DB> {
+    /// Create a handler rendering codes in `format` for links to the application served at
+    /// `public_base_url`.
+    pub fn new(db: DB, public_base_url: String, format: QrFormat) -> Self {
+        QrCodeHandler {
+            database: db,
+            public_base_url: public_base_url.trim_right_matches('/').to_string(),
+            format: format,
+        }
+    }
+}
+
+impl<DB> Handler for QrCodeHandler<DB>
+    where DB: 'static + Sync + Send
+        + Capability<Search<Presentation>, Data = Presentation, Error = String>
+{
+    fn handle(&self, request: &mut Request) -> IronResult<Response> {
+        let presentation_id = request.extensions
+            .get::<Router>()
+            .and_then(|router| router.find("id"))
+            .map(|id| Id(id.to_string()));
+        let db_result = try_do!({
+            let presentation_id = presentation_id.ok_or("Missing presentation.".to_string())?;
+            let presentation = self.database.perform(Search(Presentation::search_parameter(presentation_id)))?;
+            let join_code = presentation.join_code.ok_or("Closed presentations cannot be joined.".to_string())?;
+            Ok(format!("{}/#join/{}", self.public_base_url, join_code.0))
+        });
+        let join_url = match db_result {
+            Ok(url) => url,
+            Err(err) => return json_response!(status::NotFound, QrCodeResponse {
+                error: Some(err),
+            }),
+        };
+        let rendered = match self.format {
+            QrFormat::Svg => qr::svg(&join_url)
+                .map(|svg| (Mime(TopLevel::Image, SubLevel::Ext("svg+xml".to_string()), vec![]), svg.into_bytes())),
+            QrFormat::Png => qr::png(&join_url)
+                .map(|png| (Mime(TopLevel::Image, SubLevel::Png, vec![]), png)),
+        };
+        match rendered {
+            Ok((mime, body)) => Ok(Response::with((mime, status::Ok, body))),
+            Err(_) => json_response!(status::InternalServerError, QrCodeResponse {
+                error: Some("Failed to render QR code.".to_string()),
+            }),
+        }
+    }
+}
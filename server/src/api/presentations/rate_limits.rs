@@ -0,0 +1,77 @@
+use std::error::Error;
+
+use iron::prelude::*;
+use iron::Handler;
+use iron::status;
+
+use auth::{self, Authenticate, Authorize};
+use capabilities::{Capability, Search, Update};
+use models::{Id, Action, Presentation, RateLimits, Scope};
+
+
+/// Handles requests from a presenter to change how often audience members may ask and nod to
+/// questions during one of their presentations.
+pub struct SetRateLimitsHandler<DB> {
+    database: DB,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct SetRateLimitsRequest {
+    #[serde(rename = "sessionToken")]
+    pub session_token: Id,
+    #[serde(rename = "presentation")]
+    pub presentation_id: Id,
+    #[serde(rename = "rateLimits")]
+    pub rate_limits: RateLimits,
+}
+
+#[derive(Debug, Serialize)]
+struct SetRateLimitsResponse {
+    pub error: Option<String>,
+    #[serde(rename = "rateLimits")]
+    pub rate_limits: Option<RateLimits>,
+}
+
+impl<DB> SetRateLimitsHandler<DB> {
+    pub fn new(db: DB) -> Self {
+        SetRateLimitsHandler {
+            database: db,
+        }
+    }
+}
+
+impl<DB> Handler for SetRateLimitsHandler<DB>
+    where DB: 'static + Sync + Send
+        + Capability<Search<Presentation>, Data = Presentation, Error = String>
+        + Capability<Update<Presentation>, Data = Presentation, Error = String>
+        + Authenticate
+        + Authorize
+{
+    fn handle(&self, request: &mut Request) -> IronResult<Response> {
+        let request_data = decode_body_or_write_error!(request, SetRateLimitsRequest, |_: Option<&Error>| SetRateLimitsResponse {
+            error: Some("Missing or invalid request data.".to_string()),
+            rate_limits: None,
+        });
+        let db_result = try_do!({
+            let presentation = Presentation::search_parameter(request_data.presentation_id);
+            let mut presentation = self.database.perform(Search(presentation))?;
+            auth::authorize(
+                &self.database, request_data.session_token, Scope::PresentationsWrite, &presentation, Action::ChangeSettings)?;
+            if !request_data.rate_limits.is_valid() {
+                return Err("Budgets must allow at least one request per minute.".to_string());
+            }
+            presentation.rate_limits = request_data.rate_limits;
+            self.database.perform(Update(presentation))
+        });
+        match db_result {
+            Ok(presentation) => json_response!(status::Ok, SetRateLimitsResponse {
+                error: None,
+                rate_limits: Some(presentation.rate_limits),
+            }),
+            Err(err) => json_response!(status::BadRequest, SetRateLimitsResponse {
+                error: Some(err),
+                rate_limits: None,
+            }),
+        }
+    }
+}
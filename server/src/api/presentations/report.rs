@@ -0,0 +1,91 @@
+use iron::prelude::*;
+use iron::Handler;
+use iron::mime::{Mime, TopLevel, SubLevel};
+use iron::status;
+use router::Router;
+
+use auth::{self, Authenticate, Authorize};
+use capabilities::{Capability, FindAll, Search};
+use capabilities::sqlite::{AnswersForQuestion, QuestionsPage};
+use export;
+use models::{Id, Action, Answer, Presentation, Question, Scope};
+use report;
+
+
+/// Handles requests from a presenter for a printable PDF record of one of their presentations,
+/// listing the questions the audience could see along with their answers.
+pub struct ReportHandler<DB> {
+    database: DB,
+}
+
+#[derive(Debug, Serialize)]
+struct ReportResponse {
+    pub error: Option<String>,
+}
+
+impl<DB> ReportHandler<DB> {
+    pub fn new(db: DB) -> Self {
+        ReportHandler {
+            database: db,
+        }
+    }
+}
+
+impl<DB> Handler for ReportHandler<DB>
+    where DB: 'static + Sync + Send
+        + Capability<Search<Presentation>, Data = Presentation, Error = String>
+        + Capability<FindAll<QuestionsPage>, Data = Vec<Question>, Error = String>
+        + Capability<FindAll<AnswersForQuestion>, Data = Vec<Answer>, Error = String>
+        + Authenticate
+        + Authorize
+{
+    fn handle(&self, request: &mut Request) -> IronResult<Response> {
+        let presentation_id = request.extensions
+            .get::<Router>()
+            .and_then(|router| router.find("id"))
+            .map(|id| Id(id.to_string()));
+        let input_err = "Missing or invalid request data.".to_string();
+        let session_token = decode_query_or_write_error!(
+            request,
+            extract = |query| query
+                .get("sessionToken")
+                .and_then(|strings| strings.first())
+                .map(|token| Id(token.clone())),
+            missing = ReportResponse {
+                error: Some(input_err),
+            }
+        );
+        let db_result = try_do!({
+            let presentation_id = presentation_id.ok_or("Missing presentation.".to_string())?;
+            let presentation = self.database.perform(Search(Presentation::search_parameter(presentation_id)))?;
+            auth::authorize(
+                &self.database, session_token, Scope::QuestionsRead, &presentation, Action::ViewQuestions)?;
+            let mut entries = vec![];
+            export::for_each_question(&self.database, &presentation.id, |entry| {
+                if entry.question.is_visible() {
+                    entries.push(entry);
+                }
+                Ok(())
+            })?;
+            Ok((presentation, entries))
+        });
+        let (presentation, entries) = match db_result {
+            Ok(found) => found,
+            Err(err) => return json_response!(status::BadRequest, ReportResponse {
+                error: Some(err),
+            }),
+        };
+        match report::render(&presentation, &entries) {
+            Ok(pdf) => {
+                let mime = Mime(TopLevel::Application, SubLevel::Ext("pdf".to_string()), vec![]);
+                let disposition = format!("inline; filename=\"presentation-{}.pdf\"", presentation.id.0);
+                let mut response = Response::with((mime, status::Ok, pdf));
+                response.headers.set_raw("Content-Disposition", vec![disposition.into_bytes()]);
+                Ok(response)
+            },
+            Err(_) => json_response!(status::InternalServerError, ReportResponse {
+                error: Some("Failed to render report.".to_string()),
+            }),
+        }
+    }
+}
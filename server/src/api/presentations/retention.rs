@@ -0,0 +1,74 @@
+use std::error::Error;
+
+use iron::prelude::*;
+use iron::Handler;
+use iron::status;
+
+use auth::{self, Authenticate, Authorize};
+use capabilities::{Capability, Search, Update};
+use models::{Id, Action, Presentation, Scope};
+
+
+/// Handles requests from a presenter to change how many days the questions asked during one of
+/// their presentations are kept for. Questions are kept forever when no number of days is given.
+pub struct SetRetentionHandler<DB> {
+    database: DB,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct SetRetentionRequest {
+    #[serde(rename = "sessionToken")]
+    pub session_token: Id,
+    #[serde(rename = "presentation")]
+    pub presentation_id: Id,
+    #[serde(rename = "retentionDays", default)]
+    pub retention_days: Option<u32>,
+}
+
+#[derive(Debug, Serialize)]
+struct SetRetentionResponse {
+    pub error: Option<String>,
+    #[serde(rename = "retentionDays")]
+    pub retention_days: Option<u32>,
+}
+
+impl<DB> SetRetentionHandler<DB> {
+    pub fn new(db: DB) -> Self {
+        SetRetentionHandler {
+            database: db,
+        }
+    }
+}
+
+impl<DB> Handler for SetRetentionHandler<DB>
+    where DB: 'static + Sync + Send
+        + Capability<Search<Presentation>, Data = Presentation, Error = String>
+        + Capability<Update<Presentation>, Data = Presentation, Error = String>
+        + Authenticate
+        + Authorize
+{
+    fn handle(&self, request: &mut Request) -> IronResult<Response> {
+        let request_data = decode_body_or_write_error!(request, SetRetentionRequest, |_: Option<&Error>| SetRetentionResponse {
+            error: Some("Missing or invalid request data.".to_string()),
+            retention_days: None,
+        });
+        let db_result = try_do!({
+            let presentation = Presentation::search_parameter(request_data.presentation_id);
+            let mut presentation = self.database.perform(Search(presentation))?;
+            auth::authorize(
+                &self.database, request_data.session_token, Scope::PresentationsWrite, &presentation, Action::ChangeSettings)?;
+            presentation.set_retention(request_data.retention_days)?;
+            self.database.perform(Update(presentation))
+        });
+        match db_result {
+            Ok(presentation) => json_response!(status::Ok, SetRetentionResponse {
+                error: None,
+                retention_days: presentation.retention_days,
+            }),
+            Err(err) => json_response!(status::BadRequest, SetRetentionResponse {
+                error: Some(err),
+                retention_days: None,
+            }),
+        }
+    }
+}
@@ -0,0 +1,83 @@
+use std::error::Error;
+
+use chrono::prelude::*;
+use iron::prelude::*;
+use iron::Handler;
+use iron::status;
+
+use auth::{self, Authenticate, Authorize};
+use capabilities::{Capability, Search, Update};
+use models::{Id, Action, Presentation, Scope};
+
+
+/// Handles requests from a presenter to have one of their presentations opened and closed to
+/// questions automatically. The whole schedule is replaced, so a time that is left out is
+/// cancelled.
+pub struct SetScheduleHandler<DB> {
+    database: DB,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct SetScheduleRequest {
+    #[serde(rename = "sessionToken")]
+    pub session_token: Id,
+    #[serde(rename = "presentation")]
+    pub presentation_id: Id,
+    #[serde(rename = "opensAt", default)]
+    pub opens_at: Option<DateTime<Utc>>,
+    #[serde(rename = "closesAt", default)]
+    pub closes_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize)]
+struct SetScheduleResponse {
+    pub error: Option<String>,
+    #[serde(rename = "opensAt")]
+    pub opens_at: Option<DateTime<Utc>>,
+    #[serde(rename = "closesAt")]
+    pub closes_at: Option<DateTime<Utc>>,
+}
+
+impl<DB> SetScheduleHandler<DB> {
+    pub fn new(db: DB) -> Self {
+        SetScheduleHandler {
+            database: db,
+        }
+    }
+}
+
+impl<DB> Handler for SetScheduleHandler<DB>
+    where DB: 'static + Sync + Send
+        + Capability<Search<Presentation>, Data = Presentation, Error = String>
+        + Capability<Update<Presentation>, Data = Presentation, Error = String>
+        + Authenticate
+        + Authorize
+{
+    fn handle(&self, request: &mut Request) -> IronResult<Response> {
+        let request_data = decode_body_or_write_error!(request, SetScheduleRequest, |_: Option<&Error>| SetScheduleResponse {
+            error: Some("Missing or invalid request data.".to_string()),
+            opens_at: None,
+            closes_at: None,
+        });
+        let db_result = try_do!({
+            let presentation = Presentation::search_parameter(request_data.presentation_id);
+            let mut presentation = self.database.perform(Search(presentation))?;
+            auth::authorize(
+                &self.database, request_data.session_token, Scope::PresentationsWrite, &presentation, Action::ChangeSettings)?;
+            presentation.set_schedule(request_data.opens_at, request_data.closes_at)?;
+            self.database.perform(Update(presentation))
+        });
+        match db_result {
+            Ok(presentation) => json_response!(status::Ok, SetScheduleResponse {
+                error: None,
+                opens_at: presentation.opens_at,
+                closes_at: presentation.closes_at,
+            }),
+            Err(err) => json_response!(status::BadRequest, SetScheduleResponse {
+                error: Some(err),
+                opens_at: None,
+                closes_at: None,
+            }),
+        }
+    }
+}
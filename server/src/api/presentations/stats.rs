@@ -0,0 +1,152 @@
+use iron::prelude::*;
+use iron::Handler;
+use iron::status;
+use router::Router;
+
+use auth::{self, Authenticate, Authorize};
+use capabilities::{Aggregate, Capability, FindAll, Search};
+use capabilities::sqlite::{NodDistribution, QuestionTotals, QuestionsOverTime, TimeToAnswer, TopQuestions};
+use models::{Id, Action, NodCount, Presentation, Question, QuestionCounts, Scope, TimeBucket};
+
+
+/// How many of the most nodded-to questions are listed, unless the presenter asks for another number.
+const DEFAULT_TOP_QUESTIONS: u32 = 5;
+
+/// The most questions that can be listed as the most nodded-to.
+const MAX_TOP_QUESTIONS: u32 = 50;
+
+/// How many minutes of questions are counted together, unless the presenter asks for another span.
+const DEFAULT_BUCKET_MINUTES: u32 = 5;
+
+/// The longest span of time, in minutes, that questions can be counted together over.
+const MAX_BUCKET_MINUTES: u32 = 24 * 60;
+
+
+/// Handles requests from a presenter for statistics about how engaged the audience of one of
+/// their presentations was.
+pub struct StatsHandler<DB> {
+    database: DB,
+}
+
+#[derive(Debug, Serialize)]
+struct StatsResponse {
+    pub error: Option<String>,
+    pub stats: Option<Stats>,
+}
+
+#[derive(Debug, Serialize)]
+struct Stats {
+    #[serde(rename = "totalQuestions")]
+    pub total_questions: u32,
+    #[serde(rename = "answeredQuestions")]
+    pub answered_questions: u32,
+    #[serde(rename = "answeredRatio")]
+    pub answered_ratio: f64,
+    #[serde(rename = "nodDistribution")]
+    pub nod_distribution: Vec<NodCount>,
+    #[serde(rename = "topQuestions")]
+    pub top_questions: Vec<Question>,
+    #[serde(rename = "bucketMinutes")]
+    pub bucket_minutes: u32,
+    #[serde(rename = "questionsOverTime")]
+    pub questions_over_time: Vec<RateBucket>,
+    /// The median number of seconds between a question being asked and first answered.
+    #[serde(rename = "medianSecondsToAnswer")]
+    pub median_seconds_to_answer: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+struct RateBucket {
+    #[serde(flatten)]
+    pub bucket: TimeBucket,
+    #[serde(rename = "questionsPerMinute")]
+    pub questions_per_minute: f64,
+}
+
+impl<DB> StatsHandler<DB> {
+    pub fn new(db: DB) -> Self {
+        StatsHandler {
+            database: db,
+        }
+    }
+}
+
+impl<DB> Handler for StatsHandler<DB>
+    where DB: 'static + Sync + Send
+        + Capability<Search<Presentation>, Data = Presentation, Error = String>
+        + Capability<Aggregate<QuestionTotals>, Data = QuestionCounts, Error = String>
+        + Capability<Aggregate<NodDistribution>, Data = Vec<NodCount>, Error = String>
+        + Capability<Aggregate<QuestionsOverTime>, Data = Vec<TimeBucket>, Error = String>
+        + Capability<Aggregate<TimeToAnswer>, Data = Option<f64>, Error = String>
+        + Capability<FindAll<TopQuestions>, Data = Vec<Question>, Error = String>
+        + Authenticate
+        + Authorize
+{
+    fn handle(&self, request: &mut Request) -> IronResult<Response> {
+        let presentation_id = request.extensions
+            .get::<Router>()
+            .and_then(|router| router.find("id"))
+            .map(|id| Id(id.to_string()));
+        let input_err = "Missing or invalid request data.".to_string();
+        let (session_token, top, bucket_minutes) = decode_query_or_write_error!(
+            request,
+            extract = |query| {
+                let param = |name: &str| query.get(name).and_then(|strings| strings.first());
+                let number = |name: &str, default: u32, max: u32| match param(name) {
+                    Some(value) => value.parse::<u32>().ok().filter(|&value| value > 0 && value <= max),
+                    None        => Some(default),
+                };
+                match (param("sessionToken"), number("top", DEFAULT_TOP_QUESTIONS, MAX_TOP_QUESTIONS),
+                       number("bucketMinutes", DEFAULT_BUCKET_MINUTES, MAX_BUCKET_MINUTES)) {
+                    (Some(token), Some(top), Some(minutes)) => Some((Id(token.clone()), top, minutes)),
+                    _                                       => None,
+                }
+            },
+            missing = StatsResponse {
+                error: Some(input_err),
+                stats: None,
+            }
+        );
+        let db_result = try_do!({
+            let presentation_id = presentation_id.ok_or("Missing presentation.".to_string())?;
+            let presentation = self.database.perform(Search(Presentation::search_parameter(presentation_id)))?;
+            auth::authorize(
+                &self.database, session_token, Scope::PresentationsRead, &presentation, Action::ViewQuestions)?;
+            let id = presentation.id;
+            let counts = self.database.perform(Aggregate(QuestionTotals { presentation_id: id.clone() }))?;
+            let nod_distribution = self.database.perform(Aggregate(NodDistribution { presentation_id: id.clone() }))?;
+            let over_time = self.database.perform(Aggregate(QuestionsOverTime {
+                presentation_id: id.clone(),
+                bucket_minutes: bucket_minutes,
+            }))?;
+            let median = self.database.perform(Aggregate(TimeToAnswer { presentation_id: id.clone() }))?;
+            let top_questions = self.database.perform(FindAll(TopQuestions {
+                presentation_id: id,
+                limit: top,
+            }))?;
+            Ok(Stats {
+                total_questions: counts.questions,
+                answered_questions: counts.answered,
+                answered_ratio: counts.answered_ratio(),
+                nod_distribution: nod_distribution,
+                top_questions: top_questions,
+                bucket_minutes: bucket_minutes,
+                questions_over_time: over_time.into_iter().map(|bucket| RateBucket {
+                    bucket: bucket,
+                    questions_per_minute: bucket.questions as f64 / bucket_minutes as f64,
+                }).collect(),
+                median_seconds_to_answer: median,
+            })
+        });
+        match db_result {
+            Ok(stats) => json_response!(status::Ok, StatsResponse {
+                error: None,
+                stats: Some(stats),
+            }),
+            Err(err) => json_response!(status::BadRequest, StatsResponse {
+                error: Some(err),
+                stats: None,
+            }),
+        }
+    }
+}
@@ -0,0 +1,210 @@
+use std::error::Error;
+
+use chrono::prelude::*;
+use iron::prelude::*;
+use iron::Handler;
+use iron::status;
+
+use auth::{self, Authenticate, Authorize};
+use capabilities::{Capability, Delete, Save, Search, Update};
+use capabilities::sqlite::TransferOwnership;
+use mailer::{Mailer, Message};
+use models::{Id, Action, OwnershipTransfer, Presentation, Scope};
+
+
+/// Handles requests from the creator of a presentation to offer it to another presenter, who is
+/// told about the offer by email. Other owners of the presentation may not give it away.
+pub struct OfferTransferHandler<DB, M> {
+    database: DB,
+    mailer: M,
+}
+
+/// Handles requests from a presenter to accept a presentation offered to them, becoming its
+/// creator. The presenter who offered it stays on as one of its presenters.
+pub struct AcceptTransferHandler<DB> {
+    database: DB,
+}
+
+/// Handles requests to withdraw the offer of a presentation, either from its owner or from the
+/// presenter it was offered to, who declines it.
+pub struct CancelTransferHandler<DB> {
+    database: DB,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct OfferRequest {
+    #[serde(rename = "sessionToken")]
+    pub session_token: Id,
+    #[serde(rename = "presentation")]
+    pub presentation_id: Id,
+    /// The email address of the presenter to offer the presentation to.
+    pub to: Id,
+}
+
+#[derive(Debug, Serialize)]
+struct OfferResponse {
+    pub error: Option<String>,
+    pub transfer: Option<OwnershipTransfer>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct TransferRequest {
+    #[serde(rename = "sessionToken")]
+    pub session_token: Id,
+    #[serde(rename = "presentation")]
+    pub presentation_id: Id,
+}
+
+#[derive(Debug, Serialize)]
+struct TransferResponse {
+    pub error: Option<String>,
+}
+
+impl<DB, M> OfferTransferHandler<DB, M> {
+    pub fn new(db: DB, mailer: M) -> Self {
+        OfferTransferHandler {
+            database: db,
+            mailer: mailer,
+        }
+    }
+}
+
+impl<DB> AcceptTransferHandler<DB> {
+    pub fn new(db: DB) -> Self {
+        AcceptTransferHandler {
+            database: db,
+        }
+    }
+}
+
+impl<DB> CancelTransferHandler<DB> {
+    pub fn new(db: DB) -> Self {
+        CancelTransferHandler {
+            database: db,
+        }
+    }
+}
+
+impl<DB, M> Handler for OfferTransferHandler<DB, M>
+    where DB: 'static + Sync + Send
+        + Capability<Search<Presentation>, Data = Presentation, Error = String>
+        + Capability<Save<OwnershipTransfer>, Data = OwnershipTransfer, Error = String>
+        + Authenticate,
+          M: 'static + Sync + Send + Mailer
+{
+    fn handle(&self, request: &mut Request) -> IronResult<Response> {
+        let request_data = decode_body_or_write_error!(request, OfferRequest, |_: Option<&Error>| OfferResponse {
+            error: Some("Missing or invalid request data.".to_string()),
+            transfer: None,
+        });
+        let db_result = try_do!({
+            let presentation = Presentation::search_parameter(request_data.presentation_id);
+            let presentation = self.database.perform(Search(presentation))?;
+            let presenter = auth::authenticate(&self.database, request_data.session_token, Scope::PresentationsWrite)?;
+            if presenter != presentation.creator {
+                return Err("You are not allowed to do that!".to_string());
+            }
+            let to = Id(request_data.to.0.trim().to_string());
+            if !to.0.contains('@') {
+                return Err("Invalid email address.".to_string());
+            }
+            if to.0.to_lowercase() == presentation.creator.0.to_lowercase() {
+                return Err("The presentation already belongs to that presenter.".to_string());
+            }
+            let transfer = OwnershipTransfer::new(presentation.id.clone(), presentation.creator.clone(), to);
+            let transfer = self.database.perform(Save(transfer))?;
+            // The offer can be made again if the email never arrives.
+            let _ = self.mailer.send(Message::new(
+                transfer.to.0.clone(),
+                format!("You have been offered \"{}\" on AsQ", presentation.title),
+                format!("{} would like to hand their presentation \"{}\" over to you. Sign in to accept it \
+                         before {}.\n\nPresentation: {}",
+                        presenter.0, presentation.title, transfer.expires_at.to_rfc2822(), transfer.presentation.0)));
+            Ok(transfer)
+        });
+        match db_result {
+            Ok(transfer) => json_response!(status::Ok, OfferResponse {
+                error: None,
+                transfer: Some(transfer),
+            }),
+            Err(err) => json_response!(status::BadRequest, OfferResponse {
+                error: Some(err),
+                transfer: None,
+            }),
+        }
+    }
+}
+
+impl<DB> Handler for AcceptTransferHandler<DB>
+    where DB: 'static + Sync + Send
+        + Capability<Search<OwnershipTransfer>, Data = OwnershipTransfer, Error = String>
+        + Capability<Update<TransferOwnership>, Data = (), Error = String>
+        + Authenticate
+{
+    fn handle(&self, request: &mut Request) -> IronResult<Response> {
+        let request_data = decode_body_or_write_error!(request, TransferRequest, |_: Option<&Error>| TransferResponse {
+            error: Some("Missing or invalid request data.".to_string()),
+        });
+        let db_result = try_do!({
+            let presenter = auth::authenticate(&self.database, request_data.session_token, Scope::PresentationsWrite)?;
+            let transfer = OwnershipTransfer::search_parameter(request_data.presentation_id);
+            let transfer = self.database.perform(Search(transfer))?;
+            if !transfer.may_be_accepted_by(&presenter, Utc::now()) {
+                return Err("No such transfer.".to_string());
+            }
+            self.database.perform(Update(TransferOwnership {
+                presentation_id: transfer.presentation,
+                from: transfer.from,
+                to: presenter,
+            }))
+        });
+        match db_result {
+            Ok(()) => json_response!(status::Ok, TransferResponse {
+                error: None,
+            }),
+            Err(err) => json_response!(status::BadRequest, TransferResponse {
+                error: Some(err),
+            }),
+        }
+    }
+}
+
+impl<DB> Handler for CancelTransferHandler<DB>
+    where DB: 'static + Sync + Send
+        + Capability<Search<Presentation>, Data = Presentation, Error = String>
+        + Capability<Search<OwnershipTransfer>, Data = OwnershipTransfer, Error = String>
+        + Capability<Delete<OwnershipTransfer>, Data = (), Error = String>
+        + Authenticate
+        + Authorize
+{
+    fn handle(&self, request: &mut Request) -> IronResult<Response> {
+        let request_data = decode_body_or_write_error!(request, TransferRequest, |_: Option<&Error>| TransferResponse {
+            error: Some("Missing or invalid request data.".to_string()),
+        });
+        let db_result = try_do!({
+            let presenter = auth::authenticate(&self.database, request_data.session_token, Scope::PresentationsWrite)?;
+            let transfer = OwnershipTransfer::search_parameter(request_data.presentation_id);
+            let transfer = self.database.perform(Search(transfer))?;
+            let is_receiver = transfer.to.0.to_lowercase() == presenter.0.to_lowercase();
+            if !is_receiver {
+                let presentation = Presentation::search_parameter(transfer.presentation.clone());
+                let presentation = self.database.perform(Search(presentation))?;
+                let is_owner = auth::role_of(&self.database, &presenter, &presentation)
+                    .map(|role| role.permits(Action::ManageMembers))
+                    .unwrap_or(false);
+                if !is_owner {
+                    return Err("You are not allowed to do that!".to_string());
+                }
+            }
+            self.database.perform(Delete(transfer))
+        });
+        match db_result {
+            Ok(()) => json_response!(status::Ok, TransferResponse {
+                error: None,
+            }),
+            Err(err) => json_response!(status::BadRequest, TransferResponse {
+                error: Some(err),
+            }),
+        }
+    }
+}
@@ -5,10 +5,13 @@ use iron::Handler;
 use iron::status;
 
 use capabilities::{Capability, Save, Search};
-use models::{Id, Presenter, Session};
+use models::{Id, AccountToken, Presenter, Session, TokenPurpose};
 
 
 /// Handles presenter authentication from the landing page.
+///
+/// Presenters who have enabled two-factor authentication receive a short-lived challenge token
+/// instead of a session, which they exchange for a session with `ChallengeHandler`.
 pub struct LoginHandler<DB> {
     database: DB,
 }
@@ -25,6 +28,8 @@ struct LoginResponse {
     pub error: Option<String>,
     #[serde(rename = "sessionToken")]
     pub session_token: Option<Id>,
+    #[serde(rename = "challengeToken")]
+    pub challenge_token: Option<Id>,
 }
 
 impl<DB> LoginHandler<DB> {
@@ -39,6 +44,7 @@ impl<DB> Handler for LoginHandler<DB>
     where DB: 'static + Sync + Send
         + Capability<Search<Presenter>, Data = Presenter, Error = String>
         + Capability<Save<Session>, Data = Session, Error = String>
+        + Capability<Save<AccountToken>, Data = AccountToken, Error = String>
 {
     fn handle(&self, request: &mut Request) -> IronResult<Response> {
         let request_data = decode_body_or_write_error!(
@@ -47,25 +53,31 @@ impl<DB> Handler for LoginHandler<DB>
             |_: Option<&Error>| LoginResponse {
                 error: Some("Missing or invalid request data.".to_string()),
                 session_token: None,
+                challenge_token: None,
             });
         let db_result = try_do!({
             let to_find = Presenter::search_parameter(request_data.email_address);
             let presenter = self.database.perform(Search(to_find))?;
-            if presenter.password_matches(&request_data.password) {
-                let session = Session::new(presenter);
-                self.database.perform(Save(session))
-            } else {
+            if !presenter.password_matches(&request_data.password) {
                 Err("Invalid credentials".to_string())
+            } else if presenter.totp_enabled {
+                let challenge = AccountToken::new(presenter.email_address, TokenPurpose::LoginChallenge);
+                self.database.perform(Save(challenge)).map(|challenge| (None, Some(challenge.token)))
+            } else {
+                let session = Session::new(presenter);
+                self.database.perform(Save(session)).map(|session| (Some(session.token), None))
             }
         });
         match db_result {
-            Ok(session) => json_response!(status::Ok, LoginResponse {
+            Ok((session_token, challenge_token)) => json_response!(status::Ok, LoginResponse {
                 error: None,
-                session_token: Some(session.token),
+                session_token: session_token,
+                challenge_token: challenge_token,
             }),
             _ => json_response!(status::BadRequest, LoginResponse {
                 error: Some("Invalid credentials.".to_string()),
                 session_token: None,
+                challenge_token: None,
             }),
         }
     }
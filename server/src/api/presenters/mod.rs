@@ -1,2 +1,6 @@
 pub mod register;
 pub mod login;
+pub mod reset;
+pub mod verify;
+pub mod two_factor;
+pub mod tokens;
@@ -5,12 +5,14 @@ use iron::Handler;
 use iron::status;
 
 use capabilities::{Capability, Save};
-use models::{Id, Presenter, Session};
+use mailer::{Mailer, Message};
+use models::{Id, AccountToken, Presenter, Session, TokenPurpose};
 
 
 /// Handles presenter registration from the landing page.
-pub struct RegistrationHandler<DB> {
+pub struct RegistrationHandler<DB, M> {
     database: DB,
+    mailer: M,
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -27,18 +29,21 @@ struct RegisterResponse {
     pub session_token: Option<Id>,
 }
 
-impl<DB> RegistrationHandler<DB> {
-    pub fn new(db: DB) -> Self {
+impl<DB, M> RegistrationHandler<DB, M> {
+    pub fn new(db: DB, mailer: M) -> Self {
         RegistrationHandler {
             database: db,
+            mailer: mailer,
         }
     }
 }
 
-impl<DB> Handler for RegistrationHandler<DB>
+impl<DB, M> Handler for RegistrationHandler<DB, M>
     where DB: 'static + Sync + Send
         + Capability<Save<Presenter>, Data = Presenter, Error = String>
         + Capability<Save<Session>, Data = Session, Error = String>
+        + Capability<Save<AccountToken>, Data = AccountToken, Error = String>,
+          M: 'static + Sync + Send + Mailer
 {
     fn handle(&self, request: &mut Request) -> IronResult<Response> {
         let request_data = decode_body_or_write_error!(
@@ -51,6 +56,16 @@ impl<DB> Handler for RegistrationHandler<DB>
         let new_presenter = Presenter::new(request_data.email_address, request_data.password);
         let db_result = try_do!({
             let saved_presenter = self.database.perform(Save(new_presenter))?;
+            let verification = AccountToken::new(
+                saved_presenter.email_address.clone(),
+                TokenPurpose::EmailVerification);
+            let verification = self.database.perform(Save(verification))?;
+            // A failure to deliver the email shouldn't prevent the presenter from getting started.
+            let _ = self.mailer.send(Message::new(
+                saved_presenter.email_address.0.clone(),
+                "Verify your AsQ email address".to_string(),
+                format!("Welcome to AsQ! Use the following token to verify your email address.\n\n{}",
+                        verification.token.0)));
             let session = Session::new(saved_presenter);
             self.database.perform(Save(session))
         });
@@ -0,0 +1,140 @@
+use std::error::Error;
+
+use chrono::prelude::*;
+use iron::prelude::*;
+use iron::Handler;
+use iron::status;
+
+use capabilities::{Capability, Delete, Save, Search, Update};
+use capabilities::sqlite::SessionsForPresenter;
+use mailer::{Mailer, Message};
+use models::{Id, AccountToken, Presenter, TokenPurpose};
+
+
+/// Handles requests from presenters who have forgotten their password to be emailed a reset token.
+pub struct RequestResetHandler<DB, M> {
+    database: DB,
+    mailer: M,
+}
+
+/// Handles requests to redeem a password reset token for a new password. Every session the
+/// presenter had is ended, in case whoever made them knew the old password.
+pub struct ResetPasswordHandler<DB> {
+    database: DB,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct RequestResetRequest {
+    #[serde(rename = "emailAddress")]
+    pub email_address: Id,
+}
+
+#[derive(Debug, Serialize)]
+struct RequestResetResponse {
+    pub error: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct ResetPasswordRequest {
+    pub token: Id,
+    pub password: String,
+}
+
+#[derive(Debug, Serialize)]
+struct ResetPasswordResponse {
+    pub error: Option<String>,
+}
+
+impl<DB, M> RequestResetHandler<DB, M> {
+    pub fn new(db: DB, mailer: M) -> Self {
+        RequestResetHandler {
+            database: db,
+            mailer: mailer,
+        }
+    }
+}
+
+impl<DB> ResetPasswordHandler<DB> {
+    pub fn new(db: DB) -> Self {
+        ResetPasswordHandler {
+            database: db,
+        }
+    }
+}
+
+impl<DB, M> Handler for RequestResetHandler<DB, M>
+    where DB: 'static + Sync + Send
+        + Capability<Search<Presenter>, Data = Presenter, Error = String>
+        + Capability<Save<AccountToken>, Data = AccountToken, Error = String>,
+          M: 'static + Sync + Send + Mailer
+{
+    fn handle(&self, request: &mut Request) -> IronResult<Response> {
+        let request_data = decode_body_or_write_error!(
+            request,
+            RequestResetRequest,
+            |_: Option<&Error>| RequestResetResponse {
+                error: Some("Missing or invalid request data.".to_string()),
+            });
+        let _ = try_do!({
+            let to_find = Presenter::search_parameter(request_data.email_address);
+            let presenter = self.database.perform(Search(to_find))?;
+            let token = AccountToken::new(presenter.email_address.clone(), TokenPurpose::PasswordReset);
+            let token = self.database.perform(Save(token))?;
+            self.mailer.send(Message::new(
+                presenter.email_address.0,
+                "Reset your AsQ password".to_string(),
+                format!("Someone asked to reset the password for your AsQ account. \
+                         If it was you, use the following token within the next hour to choose a \
+                         new password.\n\n{}\n\nIf it wasn't you, you can ignore this email.",
+                        token.token.0)))
+        });
+        // Respond the same way whether or not the presenter exists, so that this endpoint cannot be
+        // used to discover which email addresses are registered.
+        json_response!(status::Ok, RequestResetResponse {
+            error: None,
+        })
+    }
+}
+
+impl<DB> Handler for ResetPasswordHandler<DB>
+    where DB: 'static + Sync + Send
+        + Capability<Search<AccountToken>, Data = AccountToken, Error = String>
+        + Capability<Delete<AccountToken>, Data = (), Error = String>
+        + Capability<Search<Presenter>, Data = Presenter, Error = String>
+        + Capability<Update<Presenter>, Data = (), Error = String>
+        + Capability<Delete<SessionsForPresenter>, Data = usize, Error = String>
+{
+    fn handle(&self, request: &mut Request) -> IronResult<Response> {
+        let request_data = decode_body_or_write_error!(
+            request,
+            ResetPasswordRequest,
+            |_: Option<&Error>| ResetPasswordResponse {
+                error: Some("Missing or invalid request data.".to_string()),
+            });
+        let db_result = try_do!({
+            let to_find = AccountToken::search_parameter(request_data.token);
+            let token = self.database.perform(Search(to_find))?;
+            if !token.is_redeemable_for(TokenPurpose::PasswordReset, Utc::now()) {
+                return Err("Invalid or expired token.".to_string());
+            }
+            let owner = token.owner.clone();
+            self.database.perform(Delete(token))?;
+            let mut presenter = self.database.perform(Search(Presenter::search_parameter(owner.clone())))?;
+            presenter.set_password(&request_data.password);
+            // Receiving the token proves the presenter controls their email address.
+            presenter.email_verified = true;
+            self.database.perform(Update(presenter))?;
+            self.database.perform(Delete(SessionsForPresenter {
+                presenter_id: owner,
+            }))
+        });
+        match db_result {
+            Ok(_) => json_response!(status::Ok, ResetPasswordResponse {
+                error: None,
+            }),
+            _ => json_response!(status::BadRequest, ResetPasswordResponse {
+                error: Some("Invalid or expired token.".to_string()),
+            }),
+        }
+    }
+}
@@ -0,0 +1,196 @@
+use std::error::Error;
+
+use iron::prelude::*;
+use iron::Handler;
+use iron::status;
+
+use capabilities::{Capability, FindAll, Save, Search, Update};
+use capabilities::sqlite::TokensForPresenter;
+use models::{Id, ApiToken, Scope, Session};
+
+
+/// Handles requests from a logged in presenter to mint a new API token.
+pub struct CreateTokenHandler<DB> {
+    database: DB,
+}
+
+/// Handles requests to list the API tokens a presenter has minted.
+pub struct ListTokensHandler<DB> {
+    database: DB,
+}
+
+/// Handles requests to revoke one of a presenter's API tokens.
+pub struct RevokeTokenHandler<DB> {
+    database: DB,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct CreateTokenRequest {
+    #[serde(rename = "sessionToken")]
+    pub session_token: Id,
+    pub name: String,
+    pub scopes: Vec<Scope>,
+}
+
+#[derive(Debug, Serialize)]
+struct CreateTokenResponse {
+    pub error: Option<String>,
+    #[serde(rename = "apiToken")]
+    pub api_token: Option<Id>,
+    pub details: Option<ApiToken>,
+}
+
+#[derive(Debug, Serialize)]
+struct ListTokensResponse {
+    pub error: Option<String>,
+    pub tokens: Vec<ApiToken>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct RevokeTokenRequest {
+    #[serde(rename = "sessionToken")]
+    pub session_token: Id,
+    #[serde(rename = "token")]
+    pub token_id: Id,
+}
+
+#[derive(Debug, Serialize)]
+struct RevokeTokenResponse {
+    pub error: Option<String>,
+}
+
+impl<DB> CreateTokenHandler<DB> {
+    pub fn new(db: DB) -> Self {
+        CreateTokenHandler {
+            database: db,
+        }
+    }
+}
+
+impl<DB> ListTokensHandler<DB> {
+    pub fn new(db: DB) -> Self {
+        ListTokensHandler {
+            database: db,
+        }
+    }
+}
+
+impl<DB> RevokeTokenHandler<DB> {
+    pub fn new(db: DB) -> Self {
+        RevokeTokenHandler {
+            database: db,
+        }
+    }
+}
+
+// Managing API tokens requires an interactive session, so that a leaked token can't be used to
+// mint more of them.
+
+impl<DB> Handler for CreateTokenHandler<DB>
+    where DB: 'static + Sync + Send
+        + Capability<Search<Session>, Data = Session, Error = String>
+        + Capability<Save<ApiToken>, Data = ApiToken, Error = String>
+{
+    fn handle(&self, request: &mut Request) -> IronResult<Response> {
+        let request_data = decode_body_or_write_error!(
+            request,
+            CreateTokenRequest,
+            |_: Option<&Error>| CreateTokenResponse {
+                error: Some("Missing or invalid request data.".to_string()),
+                api_token: None,
+                details: None,
+            });
+        let db_result = try_do!({
+            if request_data.name.trim().is_empty() || request_data.scopes.is_empty() {
+                return Err("API tokens need a name and at least one scope.".to_string());
+            }
+            let session = self.database.perform(Search(Session::search_parameter(request_data.session_token)))?;
+            let token = ApiToken::new(session.owner, request_data.name, request_data.scopes);
+            self.database.perform(Save(token))
+        });
+        match db_result {
+            Ok(token) => json_response!(status::Ok, CreateTokenResponse {
+                error: None,
+                api_token: Some(token.token.clone()),
+                details: Some(token),
+            }),
+            Err(err) => json_response!(status::BadRequest, CreateTokenResponse {
+                error: Some(err),
+                api_token: None,
+                details: None,
+            }),
+        }
+    }
+}
+
+impl<DB> Handler for ListTokensHandler<DB>
+    where DB: 'static + Sync + Send
+        + Capability<Search<Session>, Data = Session, Error = String>
+        + Capability<FindAll<TokensForPresenter>, Data = Vec<ApiToken>, Error = String>
+{
+    fn handle(&self, request: &mut Request) -> IronResult<Response> {
+        let input_err = "Missing or invalid request data.".to_string();
+        let session_token = decode_query_or_write_error!(
+            request,
+            extract = |query| query
+                .get("sessionToken")
+                .and_then(|strings| strings.first())
+                .map(|token| Id(token.clone())),
+            missing = ListTokensResponse {
+                error: Some(input_err),
+                tokens: vec![],
+            });
+        let db_result = try_do!({
+            let session = self.database.perform(Search(Session::search_parameter(session_token)))?;
+            self.database.perform(FindAll(TokensForPresenter {
+                presenter_id: session.owner,
+            }))
+        });
+        match db_result {
+            Ok(tokens) => json_response!(status::Ok, ListTokensResponse {
+                error: None,
+                tokens: tokens,
+            }),
+            _ => json_response!(status::BadRequest, ListTokensResponse {
+                error: Some("Invalid session.".to_string()),
+                tokens: vec![],
+            }),
+        }
+    }
+}
+
+impl<DB> Handler for RevokeTokenHandler<DB>
+    where DB: 'static + Sync + Send
+        + Capability<Search<Session>, Data = Session, Error = String>
+        + Capability<FindAll<TokensForPresenter>, Data = Vec<ApiToken>, Error = String>
+        + Capability<Update<ApiToken>, Data = (), Error = String>
+{
+    fn handle(&self, request: &mut Request) -> IronResult<Response> {
+        let request_data = decode_body_or_write_error!(
+            request,
+            RevokeTokenRequest,
+            |_: Option<&Error>| RevokeTokenResponse {
+                error: Some("Missing or invalid request data.".to_string()),
+            });
+        let db_result = try_do!({
+            let session = self.database.perform(Search(Session::search_parameter(request_data.session_token)))?;
+            let tokens = self.database.perform(FindAll(TokensForPresenter {
+                presenter_id: session.owner,
+            }))?;
+            let mut token = tokens
+                .into_iter()
+                .find(|token| token.id == request_data.token_id)
+                .ok_or("No such token.".to_string())?;
+            token.revoked = true;
+            self.database.perform(Update(token))
+        });
+        match db_result {
+            Ok(_) => json_response!(status::Ok, RevokeTokenResponse {
+                error: None,
+            }),
+            Err(err) => json_response!(status::BadRequest, RevokeTokenResponse {
+                error: Some(err),
+            }),
+        }
+    }
+}
@@ -0,0 +1,218 @@
+use std::error::Error;
+
+use chrono::prelude::*;
+use iron::prelude::*;
+use iron::Handler;
+use iron::status;
+
+use auth::totp;
+use capabilities::{Capability, Delete, Save, Search, Update};
+use models::{Id, AccountToken, Presenter, Session, TokenPurpose};
+
+
+/// Handles requests from a logged in presenter to begin enrolling an authenticator app.
+pub struct EnrollHandler<DB> {
+    database: DB,
+}
+
+/// Handles requests to confirm enrollment with a code from the presenter's authenticator app,
+/// after which the second factor is required to log in.
+pub struct ConfirmHandler<DB> {
+    database: DB,
+}
+
+/// Handles the second step of logging in for presenters with two-factor authentication enabled.
+pub struct ChallengeHandler<DB> {
+    database: DB,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct EnrollRequest {
+    #[serde(rename = "sessionToken")]
+    pub session_token: Id,
+}
+
+#[derive(Debug, Serialize)]
+struct EnrollResponse {
+    pub error: Option<String>,
+    pub secret: Option<String>,
+    #[serde(rename = "provisioningUri")]
+    pub provisioning_uri: Option<String>,
+    #[serde(rename = "recoveryCodes")]
+    pub recovery_codes: Vec<String>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct ConfirmRequest {
+    #[serde(rename = "sessionToken")]
+    pub session_token: Id,
+    pub code: String,
+}
+
+#[derive(Debug, Serialize)]
+struct ConfirmResponse {
+    pub error: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct ChallengeRequest {
+    #[serde(rename = "challengeToken")]
+    pub challenge_token: Id,
+    pub code: String,
+}
+
+#[derive(Debug, Serialize)]
+struct ChallengeResponse {
+    pub error: Option<String>,
+    #[serde(rename = "sessionToken")]
+    pub session_token: Option<Id>,
+}
+
+impl<DB> EnrollHandler<DB> {
+    pub fn new(db: DB) -> Self {
+        EnrollHandler {
+            database: db,
+        }
+    }
+}
+
+impl<DB> ConfirmHandler<DB> {
+    pub fn new(db: DB) -> Self {
+        ConfirmHandler {
+            database: db,
+        }
+    }
+}
+
+impl<DB> ChallengeHandler<DB> {
+    pub fn new(db: DB) -> Self {
+        ChallengeHandler {
+            database: db,
+        }
+    }
+}
+
+impl<DB> Handler for EnrollHandler<DB>
+    where DB: 'static + Sync + Send
+        + Capability<Search<Session>, Data = Session, Error = String>
+        + Capability<Search<Presenter>, Data = Presenter, Error = String>
+        + Capability<Update<Presenter>, Data = (), Error = String>
+{
+    fn handle(&self, request: &mut Request) -> IronResult<Response> {
+        let request_data = decode_body_or_write_error!(
+            request,
+            EnrollRequest,
+            |_: Option<&Error>| EnrollResponse {
+                error: Some("Missing or invalid request data.".to_string()),
+                secret: None,
+                provisioning_uri: None,
+                recovery_codes: vec![],
+            });
+        let db_result = try_do!({
+            let session = self.database.perform(Search(Session::search_parameter(request_data.session_token)))?;
+            let mut presenter = self.database.perform(Search(Presenter::search_parameter(session.owner)))?;
+            if presenter.totp_enabled {
+                return Err("Two-factor authentication is already enabled.".to_string());
+            }
+            let recovery_codes = presenter.begin_totp_enrollment();
+            let secret = presenter.totp_secret.clone().unwrap_or_default();
+            let uri = totp::provisioning_uri(&presenter.email_address.0, &secret);
+            self.database
+                .perform(Update(presenter))
+                .map(|_| (secret, uri, recovery_codes))
+        });
+        match db_result {
+            Ok((secret, uri, recovery_codes)) => json_response!(status::Ok, EnrollResponse {
+                error: None,
+                secret: Some(secret),
+                provisioning_uri: Some(uri),
+                recovery_codes: recovery_codes,
+            }),
+            Err(err) => json_response!(status::BadRequest, EnrollResponse {
+                error: Some(err),
+                secret: None,
+                provisioning_uri: None,
+                recovery_codes: vec![],
+            }),
+        }
+    }
+}
+
+impl<DB> Handler for ConfirmHandler<DB>
+    where DB: 'static + Sync + Send
+        + Capability<Search<Session>, Data = Session, Error = String>
+        + Capability<Search<Presenter>, Data = Presenter, Error = String>
+        + Capability<Update<Presenter>, Data = (), Error = String>
+{
+    fn handle(&self, request: &mut Request) -> IronResult<Response> {
+        let request_data = decode_body_or_write_error!(
+            request,
+            ConfirmRequest,
+            |_: Option<&Error>| ConfirmResponse {
+                error: Some("Missing or invalid request data.".to_string()),
+            });
+        let db_result = try_do!({
+            let session = self.database.perform(Search(Session::search_parameter(request_data.session_token)))?;
+            let mut presenter = self.database.perform(Search(Presenter::search_parameter(session.owner)))?;
+            if !presenter.confirm_totp_enrollment(&request_data.code, Utc::now()) {
+                return Err("Invalid code.".to_string());
+            }
+            self.database.perform(Update(presenter))
+        });
+        match db_result {
+            Ok(_) => json_response!(status::Ok, ConfirmResponse {
+                error: None,
+            }),
+            Err(err) => json_response!(status::BadRequest, ConfirmResponse {
+                error: Some(err),
+            }),
+        }
+    }
+}
+
+impl<DB> Handler for ChallengeHandler<DB>
+    where DB: 'static + Sync + Send
+        + Capability<Search<AccountToken>, Data = AccountToken, Error = String>
+        + Capability<Delete<AccountToken>, Data = (), Error = String>
+        + Capability<Search<Presenter>, Data = Presenter, Error = String>
+        + Capability<Update<Presenter>, Data = (), Error = String>
+        + Capability<Save<Session>, Data = Session, Error = String>
+{
+    fn handle(&self, request: &mut Request) -> IronResult<Response> {
+        let request_data = decode_body_or_write_error!(
+            request,
+            ChallengeRequest,
+            |_: Option<&Error>| ChallengeResponse {
+                error: Some("Missing or invalid request data.".to_string()),
+                session_token: None,
+            });
+        let db_result = try_do!({
+            let to_find = AccountToken::search_parameter(request_data.challenge_token);
+            let challenge = self.database.perform(Search(to_find))?;
+            if !challenge.is_redeemable_for(TokenPurpose::LoginChallenge, Utc::now()) {
+                return Err("Invalid credentials".to_string());
+            }
+            // Challenges are single-use, even when the code is wrong, so that guessing codes
+            // requires the presenter's password for every attempt.
+            let owner = challenge.owner.clone();
+            self.database.perform(Delete(challenge))?;
+            let mut presenter = self.database.perform(Search(Presenter::search_parameter(owner)))?;
+            if !presenter.verify_second_factor(&request_data.code, Utc::now()) {
+                return Err("Invalid credentials".to_string());
+            }
+            let owner = presenter.email_address.clone();
+            self.database.perform(Update(presenter))?;
+            self.database.perform(Save(Session::new(Presenter::search_parameter(owner))))
+        });
+        match db_result {
+            Ok(session) => json_response!(status::Ok, ChallengeResponse {
+                error: None,
+                session_token: Some(session.token),
+            }),
+            _ => json_response!(status::BadRequest, ChallengeResponse {
+                error: Some("Invalid credentials.".to_string()),
+                session_token: None,
+            }),
+        }
+    }
+}
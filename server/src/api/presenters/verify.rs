@@ -0,0 +1,70 @@
+use std::error::Error;
+
+use chrono::prelude::*;
+use iron::prelude::*;
+use iron::Handler;
+use iron::status;
+
+use capabilities::{Capability, Delete, Search, Update};
+use models::{Id, AccountToken, Presenter, TokenPurpose};
+
+
+/// Handles requests to redeem the email verification token sent to a presenter at registration.
+pub struct VerifyEmailHandler<DB> {
+    database: DB,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct VerifyEmailRequest {
+    pub token: Id,
+}
+
+#[derive(Debug, Serialize)]
+struct VerifyEmailResponse {
+    pub error: Option<String>,
+}
+
+impl<DB> VerifyEmailHandler<DB> {
+    pub fn new(db: DB) -> Self {
+        VerifyEmailHandler {
+            database: db,
+        }
+    }
+}
+
+impl<DB> Handler for VerifyEmailHandler<DB>
+    where DB: 'static + Sync + Send
+        + Capability<Search<AccountToken>, Data = AccountToken, Error = String>
+        + Capability<Delete<AccountToken>, Data = (), Error = String>
+        + Capability<Search<Presenter>, Data = Presenter, Error = String>
+        + Capability<Update<Presenter>, Data = (), Error = String>
+{
+    fn handle(&self, request: &mut Request) -> IronResult<Response> {
+        let request_data = decode_body_or_write_error!(
+            request,
+            VerifyEmailRequest,
+            |_: Option<&Error>| VerifyEmailResponse {
+                error: Some("Missing or invalid request data.".to_string()),
+            });
+        let db_result = try_do!({
+            let to_find = AccountToken::search_parameter(request_data.token);
+            let token = self.database.perform(Search(to_find))?;
+            if !token.is_redeemable_for(TokenPurpose::EmailVerification, Utc::now()) {
+                return Err("Invalid or expired token.".to_string());
+            }
+            let owner = token.owner.clone();
+            self.database.perform(Delete(token))?;
+            let mut presenter = self.database.perform(Search(Presenter::search_parameter(owner)))?;
+            presenter.email_verified = true;
+            self.database.perform(Update(presenter))
+        });
+        match db_result {
+            Ok(_) => json_response!(status::Ok, VerifyEmailResponse {
+                error: None,
+            }),
+            _ => json_response!(status::BadRequest, VerifyEmailResponse {
+                error: Some("Invalid or expired token.".to_string()),
+            }),
+        }
+    }
+}
@@ -4,8 +4,9 @@ use iron::Handler;
 use iron::prelude::*;
 use iron::status;
 
+use auth::{self, Authenticate, Authorize};
 use capabilities::{Capability, Save, Search};
-use models::{Id, Answer, Presentation, Question, Session};
+use models::{Id, Action, Answer, Presentation, Question, Scope};
 
 
 /// Handles requests to post an answer to a question.
@@ -40,8 +41,9 @@ impl<DB> Handler for AnswerHandler<DB>
     where DB: 'static + Sync + Send
         + Capability<Search<Question>, Data = Question, Error = String>
         + Capability<Search<Presentation>, Data = Presentation, Error = String>
-        + Capability<Search<Session>, Data = Session, Error = String>
         + Capability<Save<Answer>, Data = Answer, Error = String>
+        + Authenticate
+        + Authorize
 {
     fn handle(&self, request: &mut Request) -> IronResult<Response> {
         let request_data = decode_body_or_write_error!(request, AnswerRequest, |_: Option<&Error>| AnswerResponse {
@@ -53,14 +55,10 @@ impl<DB> Handler for AnswerHandler<DB>
             let question = self.database.perform(Search(question))?;
             let presentation = Presentation::search_parameter(question.presentation);
             let presentation = self.database.perform(Search(presentation))?;
-            let session = Session::search_parameter(request_data.session_token);
-            let session = self.database.perform(Search(session))?;
-            if presentation.creator == session.owner {
-                let answer = Answer::new(session.owner, question.id, request_data.text);
-                self.database.perform(Save(answer))
-            } else {
-                Err("You are not allowed to do that!".to_string())
-            }
+            let presenter = auth::authorize(
+                &self.database, request_data.session_token, Scope::QuestionsWrite, &presentation, Action::AnswerQuestions)?;
+            let answer = Answer::new(presenter, question.id, request_data.text);
+            self.database.perform(Save(answer))
         });
         match db_result {
             Ok(answer) => json_response!(status::Ok, AnswerResponse {
@@ -4,11 +4,28 @@ use iron::Handler;
 use iron::prelude::*;
 use iron::status;
 
-use capabilities::{Capability, Save};
-use models::{Id, Question};
+use auth::{self, CheckAccess};
+use capabilities::{Capability, FindAll, Save, Search};
+use capabilities::sqlite::QuestionsForPresentation;
+use filters::{self, Verdict};
+use models::{Id, Presentation, Question, QuestionStatus};
+use similarity;
+
+
+/// The most similar questions to suggest to an asker who may have asked a duplicate.
+const MAX_SUGGESTIONS: usize = 3;
 
 
 /// Handles requests to have a new question asked during a presentation.
+///
+/// Questions can only be asked while the presentation is open to them. They are run through the
+/// filters configured for the presentation before they are saved, which may turn them away or hold
+/// them back for the presenter to review. Presentations that require approval hold back every
+/// question.
+///
+/// Askers who opt in are also sent the questions already asked that look like duplicates of theirs,
+/// so that they can nod to one of them as well. Suggestions are only advice, and never stop a
+/// question from being asked.
 pub struct AskHandler<DB> {
     database: DB,
 }
@@ -18,12 +35,20 @@ struct AskRequest {
     #[serde(rename = "presentation")]
     pub presentation_id: Id,
     pub question: String,
+    #[serde(rename = "accessToken")]
+    pub access_token: Option<Id>,
+    /// Whether to be sent similar questions that have already been asked.
+    #[serde(rename = "suggestSimilar")]
+    pub suggest_similar: Option<bool>,
 }
 
 #[derive(Debug, Serialize)]
 struct AskResponse {
     pub error: Option<String>,
     pub question: Option<Question>,
+    /// Questions already asked that are similar to the one asked, which the asker may want to nod
+    /// to as well.
+    pub suggestions: Vec<Question>,
 }
 
 impl<DB> AskHandler<DB> {
@@ -35,22 +60,74 @@ impl<DB> AskHandler<DB> {
 }
 
 impl<DB> Handler for AskHandler<DB>
-    where DB: 'static + Sync + Send + Capability<Save<Question>, Data = Question>
+    where DB: 'static + Sync + Send
+        + Capability<Save<Question>, Data = Question>
+        + Capability<Search<Presentation>, Data = Presentation, Error = String>
+        + Capability<FindAll<QuestionsForPresentation>, Data = Vec<Question>, Error = String>
+        + CheckAccess
 {
     fn handle(&self, request: &mut Request) -> IronResult<Response> {
         let req_data = decode_body_or_write_error!(request, AskRequest, |_: Option<&Error>| AskResponse {
             error: Some("Missing or invalid request data.".to_string()),
             question: None,
+            suggestions: vec![],
         });
-        let new_question = Question::new(req_data.presentation_id, req_data.question);
+        let presentation = match self.database.perform(Search(Presentation::search_parameter(req_data.presentation_id))) {
+            Ok(presentation) => presentation,
+            Err(_) => return json_response!(status::BadRequest, AskResponse {
+                error: Some("Invalid presentation.".to_string()),
+                question: None,
+                suggestions: vec![],
+            }),
+        };
+        if let Err(err) = auth::check_access(&self.database, &presentation, req_data.access_token) {
+            return json_response!(status::Forbidden, AskResponse {
+                error: Some(err),
+                question: None,
+                suggestions: vec![],
+            });
+        }
+        if !presentation.is_open_to_questions {
+            return json_response!(status::Forbidden, AskResponse {
+                error: Some("This presentation is closed to questions.".to_string()),
+                question: None,
+                suggestions: vec![],
+            });
+        }
+        let suggestions = if req_data.suggest_similar.unwrap_or(false) {
+            let existing = self.database
+                .perform(FindAll(QuestionsForPresentation { presentation_id: presentation.id.clone() }))
+                .unwrap_or(vec![]);
+            let visible: Vec<Question> = existing.into_iter().filter(|question| question.is_visible()).collect();
+            similarity::find_duplicates(&req_data.question, &visible, MAX_SUGGESTIONS)
+                .into_iter()
+                .cloned()
+                .collect()
+        } else {
+            vec![]
+        };
+        let mut new_question = Question::new(presentation.id, req_data.question);
+        match filters::check_all(&presentation.filters, &new_question.text) {
+            Verdict::Accept         => if presentation.requires_approval {
+                new_question.status = QuestionStatus::Pending;
+            },
+            Verdict::Review(reason) => new_question.hold_for_review(reason),
+            Verdict::Reject(reason) => return json_response!(status::BadRequest, AskResponse {
+                error: Some(reason),
+                question: None,
+                suggestions: vec![],
+            }),
+        }
         match self.database.perform(Save(new_question)) {
             Ok(saved) => json_response!(status::Ok, AskResponse {
                 error: None,
                 question: Some(saved),
+                suggestions: suggestions,
             }),
             _ => json_response!(status::InternalServerError, AskResponse {
                 error: Some("Failed to save question. Try again later.".to_string()),
                 question: None,
+                suggestions: vec![],
             }),
         }
     }
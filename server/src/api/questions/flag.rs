@@ -0,0 +1,208 @@
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+
+use chrono::prelude::*;
+use iron::Handler;
+use iron::prelude::*;
+use iron::status;
+
+use api::rate_limit;
+use auth::{self, Authenticate, Authorize, CheckAccess, IdentifyAudience};
+use capabilities::{Capability, FindAll, Save, Search, Update};
+use capabilities::sqlite::{FlagsForPresentation, FlagsForQuestion, QuestionsForPresentation};
+use models::{Id, Action, Budget, Flag, Presentation, Question, Scope};
+use ratelimit::BucketStore;
+
+
+/// How many questions may be flagged from each client IP address, in a burst and then per minute.
+const FLAGS_PER_ADDRESS: (u32, u32) = (20, 5);
+
+
+/// Handles requests from audience members to flag a question as inappropriate. Questions flagged
+/// by enough audience members are hidden until a presenter reviews them.
+///
+/// Audience members who join anonymously are counted once per client IP address towards hiding a
+/// question, since anyone can join again to flag a question as someone new. Flags are also limited
+/// per address.
+pub struct FlagHandler<DB, S> {
+    database: DB,
+    buckets: S,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct FlagRequest {
+    #[serde(rename = "question")]
+    pub question_id: Id,
+    pub audience: Id,
+    #[serde(rename = "submissionToken")]
+    pub submission_token: String,
+    pub reason: String,
+    #[serde(rename = "accessToken")]
+    pub access_token: Option<Id>,
+}
+
+#[derive(Debug, Serialize)]
+struct FlagResponse {
+    pub error: Option<String>,
+    pub flag: Option<Flag>,
+}
+
+/// Handles requests from a presenter to list the flagged questions asked during one of their
+/// presentations.
+pub struct FlaggedHandler<DB> {
+    database: DB,
+}
+
+#[derive(Debug, Serialize)]
+struct FlaggedQuestion {
+    pub question: Question,
+    #[serde(rename = "flagCount")]
+    pub flag_count: usize,
+    pub reasons: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct FlaggedResponse {
+    pub error: Option<String>,
+    pub flagged: Vec<FlaggedQuestion>,
+}
+
+impl<DB, S> FlagHandler<DB, S> {
+    pub fn new(db: DB, buckets: S) -> Self {
+        FlagHandler {
+            database: db,
+            buckets: buckets,
+        }
+    }
+}
+
+impl<DB> FlaggedHandler<DB> {
+    pub fn new(db: DB) -> Self {
+        FlaggedHandler {
+            database: db,
+        }
+    }
+}
+
+impl<DB, S> Handler for FlagHandler<DB, S>
+    where DB: 'static + Sync + Send
+        + Capability<Search<Question>, Data = Question, Error = String>
+        + Capability<Update<Question>, Data = (), Error = String>
+        + Capability<Search<Presentation>, Data = Presentation, Error = String>
+        + Capability<Save<Flag>, Data = Flag, Error = String>
+        + Capability<FindAll<FlagsForQuestion>, Data = Vec<Flag>, Error = String>
+        + CheckAccess
+        + IdentifyAudience,
+          S: 'static + Sync + Send + BucketStore
+{
+    fn handle(&self, request: &mut Request) -> IronResult<Response> {
+        let request_data = decode_body_or_write_error!(request, FlagRequest, |_: Option<&Error>| FlagResponse {
+            error: Some("Missing or invalid request data.".to_string()),
+            flag: None,
+        });
+        let address = request.remote_addr.ip();
+        let (capacity, per_minute) = FLAGS_PER_ADDRESS;
+        let key = format!("flag:ip:{}", address);
+        if let Ok(Err(wait)) = self.buckets.take(&key, Budget::new(capacity, per_minute), Utc::now()) {
+            return Ok(rate_limit::too_many_requests(&FlagResponse {
+                error: Some("You're doing that too often. Try again shortly.".to_string()),
+                flag: None,
+            }, wait));
+        }
+        let db_result = try_do!({
+            let audience = auth::identify_audience(
+                &self.database, request_data.audience.clone(), &request_data.submission_token)?;
+            let question = Question::search_parameter(request_data.question_id.clone());
+            let mut question = self.database.perform(Search(question))?;
+            if !question.is_visible() {
+                return Err("Invalid question.".to_string());
+            }
+            let presentation = Presentation::search_parameter(question.presentation.clone());
+            let presentation = self.database.perform(Search(presentation))?;
+            auth::check_access(&self.database, &presentation, request_data.access_token.clone())?;
+            if request_data.reason.trim().is_empty() {
+                return Err("Give a reason for flagging the question.".to_string());
+            }
+            let flag = Flag::new(question.id.clone(), audience, address, request_data.reason.trim().to_string());
+            let flag = self.database.perform(Save(flag))?;
+
+            // Questions a presenter has already reviewed stay as the presenter left them.
+            let flags = self.database.perform(FindAll(FlagsForQuestion {
+                question_id: question.id.clone(),
+            }))?;
+            let sources = flags.iter().map(|flag| &flag.source).collect::<HashSet<_>>();
+            if question.reviewed_at.is_none() && presentation.flags_warrant_review(sources.len()) {
+                question.hold_for_review("Flagged by the audience.".to_string());
+                self.database.perform(Update(question))?;
+            }
+            Ok(flag)
+        });
+        match db_result {
+            Ok(flag) => json_response!(status::Ok, FlagResponse {
+                error: None,
+                flag: Some(flag),
+            }),
+            Err(err) => json_response!(status::BadRequest, FlagResponse {
+                error: Some(err),
+                flag: None,
+            }),
+        }
+    }
+}
+
+impl<DB> Handler for FlaggedHandler<DB>
+    where DB: 'static + Sync + Send
+        + Capability<Search<Presentation>, Data = Presentation, Error = String>
+        + Capability<FindAll<QuestionsForPresentation>, Data = Vec<Question>, Error = String>
+        + Capability<FindAll<FlagsForPresentation>, Data = Vec<Flag>, Error = String>
+        + Authenticate
+        + Authorize
+{
+    fn handle(&self, request: &mut Request) -> IronResult<Response> {
+        let input_err = "Missing or invalid request data.".to_string();
+        let (presentation_id, session_token) = decode_query_or_write_error!(
+            request,
+            extract = |query| {
+                let param = |name: &str| query
+                    .get(name)
+                    .and_then(|strings| strings.first())
+                    .map(|value| Id(value.clone()));
+                param("presentation").and_then(|presentation| param("sessionToken").map(|token| (presentation, token)))
+            },
+            missing = FlaggedResponse {
+                error: Some(input_err),
+                flagged: vec![],
+            }
+        );
+        let db_result = try_do!({
+            let presentation = self.database.perform(Search(Presentation::search_parameter(presentation_id)))?;
+            auth::authorize(
+                &self.database, session_token, Scope::QuestionsRead, &presentation, Action::ModerateQuestions)?;
+            let mut reasons: HashMap<String, Vec<String>> = HashMap::new();
+            for flag in self.database.perform(FindAll(FlagsForPresentation { presentation_id: presentation.id.clone() }))? {
+                reasons.entry(flag.question.0).or_insert_with(Vec::new).push(flag.reason);
+            }
+            let questions = self.database.perform(FindAll(QuestionsForPresentation { presentation_id: presentation.id }))?;
+            let mut flagged: Vec<FlaggedQuestion> = questions
+                .into_iter()
+                .filter_map(|question| reasons.remove(&question.id.0).map(|reasons| FlaggedQuestion {
+                    question: question,
+                    flag_count: reasons.len(),
+                    reasons: reasons,
+                }))
+                .collect();
+            flagged.sort_by(|a, b| b.flag_count.cmp(&a.flag_count));
+            Ok(flagged)
+        });
+        match db_result {
+            Ok(flagged) => json_response!(status::Ok, FlaggedResponse {
+                error: None,
+                flagged: flagged,
+            }),
+            Err(err) => json_response!(status::BadRequest, FlaggedResponse {
+                error: Some(err),
+                flagged: vec![],
+            }),
+        }
+    }
+}
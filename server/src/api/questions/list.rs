@@ -2,12 +2,18 @@ use iron::Handler;
 use iron::prelude::*;
 use iron::status;
 
-use capabilities::{Capability, FindAll};
+use auth::{self, Authenticate, Authorize, CheckAccess};
+use capabilities::{Capability, FindAll, Search};
 use capabilities::sqlite::QuestionsForPresentation;
-use models::{Id, Question};
+use models::{Id, Action, Presentation, Question, QuestionStatus, Scope};
 
 
 /// Handles requests to list questions asked during a presentation.
+///
+/// Questions asked during a private presentation are only listed for audience members holding an
+/// access grant, and for presenters whose role in it lets them view its questions. The audience
+/// only ever sees approved questions, while those presenters see every question, or only those with
+/// a given `status`.
 pub struct ListHandler<DB> {
     database: DB,
 }
@@ -16,6 +22,18 @@ pub struct ListHandler<DB> {
 struct ListRequest {
     #[serde(rename = "presentation")]
     pub presentation_id: Id,
+    #[serde(rename = "accessToken")]
+    pub access_token: Option<Id>,
+    #[serde(rename = "sessionToken")]
+    pub session_token: Option<Id>,
+    pub status: Option<QuestionStatus>,
+}
+
+/// The parties that questions are listed for, who are shown different questions.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Caller {
+    Presenter,
+    Audience,
 }
 
 #[derive(Debug, Serialize)]
@@ -33,28 +51,73 @@ impl<DB> ListHandler<DB> {
 }
 
 impl<DB> Handler for ListHandler<DB>
-    where DB: 'static + Sync + Send + Capability<FindAll<QuestionsForPresentation>, Data = Vec<Question>>
+    where DB: 'static + Sync + Send
+        + Capability<FindAll<QuestionsForPresentation>, Data = Vec<Question>>
+        + Capability<Search<Presentation>, Data = Presentation, Error = String>
+        + Authenticate
+        + Authorize
+        + CheckAccess
 {
     fn handle(&self, request: &mut Request) -> IronResult<Response> {
         let input_err = "Missing or invalid request data.".to_string();
-        let presentation_id = decode_query_or_write_error!(
+        let request_data = decode_query_or_write_error!(
             request,
-            extract = |query| query
-                .get("presentation")
-                .and_then(|strings| strings.first())
-                .map(|id| Id(id.clone())),
+            extract = |query| {
+                let param = |name: &str| query
+                    .get(name)
+                    .and_then(|strings| strings.first())
+                    .map(|value| Id(value.clone()));
+                let status = match param("status") {
+                    Some(status) => Some(QuestionStatus::from_str(&status.0)?),
+                    None         => None,
+                };
+                param("presentation").map(|presentation_id| ListRequest {
+                    presentation_id: presentation_id,
+                    access_token: param("accessToken"),
+                    session_token: param("sessionToken"),
+                    status: status,
+                })
+            },
             missing = ListResponse {
                 error: Some(input_err),
                 questions: vec![],
             }
         );
+        let presentation = Presentation::search_parameter(request_data.presentation_id);
+        let presentation = match self.database.perform(Search(presentation)) {
+            Ok(presentation) => presentation,
+            Err(_) => return json_response!(status::BadRequest, ListResponse {
+                error: Some("Invalid presentation.".to_string()),
+                questions: vec![],
+            }),
+        };
+        let is_member = request_data.session_token
+            .and_then(|token| auth::authenticate(&self.database, token, Scope::QuestionsRead).ok())
+            .and_then(|presenter| auth::role_of(&self.database, &presenter, &presentation))
+            .map(|role| role.permits(Action::ViewQuestions))
+            .unwrap_or(false);
+        let caller = if is_member { Caller::Presenter } else { Caller::Audience };
+        if caller == Caller::Audience {
+            if let Err(err) = auth::check_access(&self.database, &presentation, request_data.access_token) {
+                return json_response!(status::Forbidden, ListResponse {
+                    error: Some(err),
+                    questions: vec![],
+                });
+            }
+        }
         let db_result = self.database.perform(FindAll(QuestionsForPresentation {
-            presentation_id: presentation_id,
+            presentation_id: presentation.id,
         }));
         match db_result {
             Ok(questions) => json_response!(status::Ok, ListResponse {
                 error: None,
-                questions: questions,
+                questions: questions
+                    .into_iter()
+                    .filter(|question| match caller {
+                        Caller::Presenter => request_data.status.map(|status| question.status == status).unwrap_or(true),
+                        Caller::Audience  => question.is_visible(),
+                    })
+                    .collect(),
             }),
             _ => json_response!(status::BadRequest, ListResponse {
                 error: Some("Invalid presentation.".to_string()),
@@ -0,0 +1,81 @@
+use std::error::Error;
+
+use iron::Handler;
+use iron::prelude::*;
+use iron::status;
+
+use auth::{self, Authenticate, Authorize};
+use capabilities::{Capability, Search, Update};
+use capabilities::sqlite::MergeQuestions;
+use models::{Id, Action, Presentation, Question, Scope};
+
+
+/// Handles requests from a presenter to merge duplicate questions into one. The merged question
+/// collects the nods of the duplicates, which are hidden from the audience but remember which
+/// question they were merged into.
+pub struct MergeHandler<DB> {
+    database: DB,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct MergeRequest {
+    #[serde(rename = "sessionToken")]
+    pub session_token: Id,
+    #[serde(rename = "question")]
+    pub question_id: Id,
+    pub duplicates: Vec<Id>,
+}
+
+#[derive(Debug, Serialize)]
+struct MergeResponse {
+    pub error: Option<String>,
+    pub question: Option<Question>,
+}
+
+impl<DB> MergeHandler<DB> {
+    pub fn new(db: DB) -> Self {
+        MergeHandler {
+            database: db,
+        }
+    }
+}
+
+impl<DB> Handler for MergeHandler<DB>
+    where DB: 'static + Sync + Send
+        + Capability<Search<Question>, Data = Question, Error = String>
+        + Capability<Search<Presentation>, Data = Presentation, Error = String>
+        + Capability<Update<MergeQuestions>, Data = Question, Error = String>
+        + Authenticate
+        + Authorize
+{
+    fn handle(&self, request: &mut Request) -> IronResult<Response> {
+        let request_data = decode_body_or_write_error!(request, MergeRequest, |_: Option<&Error>| MergeResponse {
+            error: Some("Missing or invalid request data.".to_string()),
+            question: None,
+        });
+        let db_result = try_do!({
+            let question = self.database.perform(Search(Question::search_parameter(request_data.question_id)))?;
+            let presentation = Presentation::search_parameter(question.presentation.clone());
+            let presentation = self.database.perform(Search(presentation))?;
+            auth::authorize(
+                &self.database, request_data.session_token, Scope::QuestionsWrite, &presentation, Action::ModerateQuestions)?;
+            if request_data.duplicates.is_empty() {
+                return Err("Choose at least one duplicate to merge.".to_string());
+            }
+            self.database.perform(Update(MergeQuestions {
+                target: question.id,
+                originals: request_data.duplicates,
+            }))
+        });
+        match db_result {
+            Ok(question) => json_response!(status::Ok, MergeResponse {
+                error: None,
+                question: Some(question),
+            }),
+            Err(err) => json_response!(status::BadRequest, MergeResponse {
+                error: Some(err),
+                question: None,
+            }),
+        }
+    }
+}
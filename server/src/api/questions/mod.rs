@@ -1,4 +1,8 @@
 pub mod answer;
 pub mod ask;
+pub mod flag;
 pub mod list;
+pub mod merge;
 pub mod nod;
+pub mod review;
+pub mod search;
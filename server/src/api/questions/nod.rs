@@ -4,8 +4,10 @@ use iron::Handler;
 use iron::prelude::*;
 use iron::status;
 
+use auth::{self, CheckAccess};
 use capabilities::{Capability, Search, Update};
-use models::{Id, Question};
+use capabilities::sqlite::QuestionNod;
+use models::{Id, Presentation, Question};
 
 
 /// Handles requests to have a question nodded to.
@@ -17,6 +19,8 @@ pub struct NodHandler<DB> {
 struct NodRequest {
     #[serde(rename = "question")]
     pub question_id: Id,
+    #[serde(rename = "accessToken")]
+    pub access_token: Option<Id>,
 }
 
 #[derive(Debug, Serialize)]
@@ -34,19 +38,43 @@ impl<DB> NodHandler<DB> {
 }
 
 impl<DB> Handler for NodHandler<DB>
-    where DB: 'static + Sync + Send + Capability<Search<Question>, Data = Question, Error = String> + Capability<Update<Question>, Error = String>
+    where DB: 'static + Sync + Send
+        + Capability<Search<Question>, Data = Question, Error = String>
+        + Capability<Update<QuestionNod>, Data = usize, Error = String>
+        + Capability<Search<Presentation>, Data = Presentation, Error = String>
+        + CheckAccess
 {
     fn handle(&self, request: &mut Request) -> IronResult<Response> {
         let req_data = decode_body_or_write_error!(request, NodRequest, |_: Option<&Error>| NodResponse {
             error: Some("Missing or invalid request data.".to_string()),
             question: None,
         });
+        let found = try_do!({
+            let question = self.database.perform(Search(Question::search_parameter(req_data.question_id)))?;
+            let presentation = Presentation::search_parameter(question.presentation.clone());
+            self.database.perform(Search(presentation)).map(|presentation| (question, presentation))
+        });
+        let (question, presentation) = match found {
+            Ok((question, presentation)) if question.is_visible() => (question, presentation),
+            _ => return json_response!(status::BadRequest, NodResponse {
+                error: Some("Invalid question.".to_string()),
+                question: None,
+            }),
+        };
+        if let Err(err) = auth::check_access(&self.database, &presentation, req_data.access_token) {
+            return json_response!(status::Forbidden, NodResponse {
+                error: Some(err),
+                question: None,
+            });
+        }
         let db_result = try_do!({
-            let mut question = self.database.perform(Search(Question::search_parameter(req_data.question_id)))?;
-            question.nods += 1;
-            self.database
-                .perform(Update(question.clone()))
-                .map(|_| question)
+            let nodded = self.database.perform(Update(QuestionNod {
+                question_id: question.id.clone(),
+            }))?;
+            if nodded == 0 {
+                return Err("Invalid question.".to_string());
+            }
+            self.database.perform(Search(Question::search_parameter(question.id)))
         });
         match db_result {
             Ok(question) => json_response!(status::Ok, NodResponse {
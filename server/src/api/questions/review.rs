@@ -0,0 +1,76 @@
+use std::error::Error;
+
+use iron::Handler;
+use iron::prelude::*;
+use iron::status;
+
+use auth::{self, Authenticate, Authorize};
+use capabilities::{Capability, Search, Update};
+use models::{Id, Action, Presentation, Question, QuestionStatus, Scope};
+
+
+/// Handles requests from a presenter to approve or reject a question held back for review. Each
+/// handler records one decision, so that approving and rejecting are separate endpoints.
+pub struct ReviewHandler<DB> {
+    database: DB,
+    decision: QuestionStatus,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct ReviewRequest {
+    #[serde(rename = "sessionToken")]
+    pub session_token: Id,
+    #[serde(rename = "question")]
+    pub question_id: Id,
+}
+
+#[derive(Debug, Serialize)]
+struct ReviewResponse {
+    pub error: Option<String>,
+    pub question: Option<Question>,
+}
+
+impl<DB> ReviewHandler<DB> {
+    pub fn new(db: DB, decision: QuestionStatus) -> Self {
+        ReviewHandler {
+            database: db,
+            decision: decision,
+        }
+    }
+}
+
+impl<DB> Handler for ReviewHandler<DB>
+    where DB: 'static + Sync + Send
+        + Capability<Search<Question>, Data = Question, Error = String>
+        + Capability<Update<Question>, Data = (), Error = String>
+        + Capability<Search<Presentation>, Data = Presentation, Error = String>
+        + Authenticate
+        + Authorize
+{
+    fn handle(&self, request: &mut Request) -> IronResult<Response> {
+        let request_data = decode_body_or_write_error!(request, ReviewRequest, |_: Option<&Error>| ReviewResponse {
+            error: Some("Missing or invalid request data.".to_string()),
+            question: None,
+        });
+        let db_result = try_do!({
+            let question = Question::search_parameter(request_data.question_id);
+            let mut question = self.database.perform(Search(question))?;
+            let presentation = Presentation::search_parameter(question.presentation.clone());
+            let presentation = self.database.perform(Search(presentation))?;
+            auth::authorize(
+                &self.database, request_data.session_token, Scope::QuestionsWrite, &presentation, Action::ModerateQuestions)?;
+            question.review(self.decision);
+            self.database.perform(Update(question.clone())).map(|_| question)
+        });
+        match db_result {
+            Ok(question) => json_response!(status::Ok, ReviewResponse {
+                error: None,
+                question: Some(question),
+            }),
+            Err(err) => json_response!(status::BadRequest, ReviewResponse {
+                error: Some(err),
+                question: None,
+            }),
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use iron::Handler;
+use iron::prelude::*;
+use iron::status;
+
+use auth::{self, Authenticate, Authorize, CheckAccess};
+use capabilities::{Capability, FindAll, Search};
+use capabilities::sqlite::TextSearch;
+use models::{Id, Action, Presentation, Scope, SearchResult};
+
+
+/// The most results returned for a single search.
+const MAX_RESULTS: u32 = 50;
+
+
+/// Handles full-text searches over the questions asked during a presentation and their answers.
+///
+/// Words are matched individually, and text in double quotes as a phrase. The presenter who
+/// created the presentation also finds questions hidden from the audience.
+pub struct SearchHandler<DB> {
+    database: DB,
+}
+
+#[derive(Clone, Debug)]
+struct SearchRequest {
+    pub presentation_id: Id,
+    pub text: String,
+    pub access_token: Option<Id>,
+    pub session_token: Option<Id>,
+}
+
+#[derive(Debug, Serialize)]
+struct SearchResponse {
+    pub error: Option<String>,
+    pub results: Vec<SearchResult>,
+}
+
+impl<DB> SearchHandler<DB> {
+    pub fn new(db: DB) -> Self {
+        SearchHandler {
+            database: db,
+        }
+    }
+}
+
+impl<DB> Handler for SearchHandler<DB>
+    where DB: 'static + Sync + Send
+        + Capability<FindAll<TextSearch>, Data = Vec<SearchResult>, Error = String>
+        + Capability<Search<Presentation>, Data = Presentation, Error = String>
+        + Authenticate
+        + Authorize
+        + CheckAccess
+{
+    fn handle(&self, request: &mut Request) -> IronResult<Response> {
+        let input_err = "Missing or invalid request data.".to_string();
+        let request_data = decode_query_or_write_error!(
+            request,
+            extract = |query| {
+                let param = |name: &str| query
+                    .get(name)
+                    .and_then(|strings| strings.first())
+                    .map(|value| value.clone());
+                match (param("presentation"), param("q")) {
+                    (Some(presentation), Some(text)) => Some(SearchRequest {
+                        presentation_id: Id(presentation),
+                        text: text,
+                        access_token: param("accessToken").map(Id),
+                        session_token: param("sessionToken").map(Id),
+                    }),
+                    _ => None,
+                }
+            },
+            missing = SearchResponse {
+                error: Some(input_err),
+                results: vec![],
+            }
+        );
+        let presentation = Presentation::search_parameter(request_data.presentation_id);
+        let presentation = match self.database.perform(Search(presentation)) {
+            Ok(presentation) => presentation,
+            Err(_) => return json_response!(status::BadRequest, SearchResponse {
+                error: Some("Invalid presentation.".to_string()),
+                results: vec![],
+            }),
+        };
+        let is_member = request_data.session_token
+            .and_then(|token| auth::authenticate(&self.database, token, Scope::QuestionsRead).ok())
+            .and_then(|presenter| auth::role_of(&self.database, &presenter, &presentation))
+            .map(|role| role.permits(Action::ViewQuestions))
+            .unwrap_or(false);
+        if !is_member {
+            if let Err(err) = auth::check_access(&self.database, &presentation, request_data.access_token) {
+                return json_response!(status::Forbidden, SearchResponse {
+                    error: Some(err),
+                    results: vec![],
+                });
+            }
+        }
+        let db_result = self.database.perform(FindAll(TextSearch {
+            presentation_id: presentation.id,
+            text: request_data.text,
+            include_hidden: is_member,
+            limit: MAX_RESULTS,
+        }));
+        match db_result {
+            Ok(results) => json_response!(status::Ok, SearchResponse {
+                error: None,
+                results: results,
+            }),
+            Err(err) => json_response!(status::BadRequest, SearchResponse {
+                error: Some(err),
+                results: vec![],
+            }),
+        }
+    }
+}
@@ -0,0 +1,169 @@
+use std::error::Error;
+use std::fmt;
+
+use bodyparser;
+use chrono::prelude::*;
+use iron::prelude::*;
+use iron::BeforeMiddleware;
+use iron::headers::ContentType;
+use iron::status;
+use serde::Serialize;
+use serde_json;
+
+use auth::{self, IdentifyAudience};
+use capabilities::{Capability, Search};
+use models::{Id, Presentation, Question};
+use ratelimit::BucketStore;
+
+
+/// The number of audience members assumed to share an IP address, such as behind a school or
+/// conference network, when limiting identified audience members by their address as well.
+const AUDIENCE_MEMBERS_PER_ADDRESS: u32 = 25;
+
+/// The kinds of request that audience members have separate budgets for.
+#[derive(Clone, Copy, Debug)]
+pub enum Action {
+    Ask,
+    Nod,
+}
+
+/// Middleware that turns away audience members who ask or nod to questions more often than the
+/// presentation they are taking part in allows.
+///
+/// Requests from audience members who prove their identity with a submission token are limited
+/// per audience member, and also per client IP address with a budget large enough to share, so
+/// that joining as many new audience members as one likes doesn't lift the limit. All other
+/// requests are limited per client IP address. Requests that don't name a known question or
+/// presentation are passed on for the handler to reject.
+pub struct RateLimiter<DB, S> {
+    database: DB,
+    buckets: S,
+    action: Action,
+}
+
+#[derive(Debug, Serialize)]
+struct RateLimitedResponse {
+    pub error: Option<String>,
+}
+
+/// The error raised when a request is turned away, carrying the number of seconds to wait.
+#[derive(Debug)]
+struct RateLimited(u64);
+
+impl Action {
+    fn as_str(&self) -> &'static str {
+        match *self {
+            Action::Ask => "ask",
+            Action::Nod => "nod",
+        }
+    }
+}
+
+impl fmt::Display for RateLimited {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Rate limited for {} seconds.", self.0)
+    }
+}
+
+impl Error for RateLimited {
+    fn description(&self) -> &str {
+        "Rate limited."
+    }
+}
+
+/// Produce the response that turns a client away for `wait` seconds, with `body` saying why.
+pub fn too_many_requests<T: Serialize>(body: &T, wait: u64) -> Response {
+    let body = serde_json::to_string(body).unwrap();
+    let mut response = Response::with((ContentType::json().0, status::TooManyRequests, body));
+    response.headers.set_raw("Retry-After", vec![wait.to_string().into_bytes()]);
+    response
+}
+
+impl<DB, S> RateLimiter<DB, S> {
+    pub fn new(db: DB, buckets: S, action: Action) -> Self {
+        RateLimiter {
+            database: db,
+            buckets: buckets,
+            action: action,
+        }
+    }
+}
+
+impl<DB, S> RateLimiter<DB, S>
+    where DB: Capability<Search<Question>, Data = Question, Error = String>
+{
+    /// Find the presentation that a request to ask or nod to a question is directed at.
+    fn presentation_id(&self, body: &serde_json::Value) -> Option<Id> {
+        let field = |name: &str| body.get(name).and_then(|value| value.as_str()).map(|value| Id(value.to_string()));
+        match self.action {
+            Action::Ask => field("presentation"),
+            Action::Nod => field("question")
+                .and_then(|question| self.database.perform(Search(Question::search_parameter(question))).ok())
+                .map(|question| question.presentation),
+        }
+    }
+}
+
+impl<DB, S> BeforeMiddleware for RateLimiter<DB, S>
+    where DB: 'static + Sync + Send
+        + Capability<Search<Question>, Data = Question, Error = String>
+        + Capability<Search<Presentation>, Data = Presentation, Error = String>
+        + IdentifyAudience,
+          S: 'static + Sync + Send + BucketStore
+{
+    fn before(&self, request: &mut Request) -> IronResult<()> {
+        let body = match request.get::<bodyparser::Json>() {
+            Ok(Some(body)) => body,
+            _ => return Ok(()),
+        };
+        let presentation = self.presentation_id(&body)
+            .and_then(|id| self.database.perform(Search(Presentation::search_parameter(id))).ok());
+        let presentation = match presentation {
+            Some(presentation) => presentation,
+            None => return Ok(()),
+        };
+        let budget = match self.action {
+            Action::Ask => presentation.rate_limits.ask,
+            Action::Nod => presentation.rate_limits.nod,
+        };
+        let field = |name: &str| body.get(name).and_then(|value| value.as_str());
+        let audience = match (field("audience"), field("submissionToken")) {
+            (Some(audience), Some(token)) => auth::identify_audience(&self.database, Id(audience.to_string()), token).ok(),
+            _ => None,
+        };
+        let address = request.remote_addr.ip();
+        let limits = match audience {
+            Some(audience) => vec![
+                (format!("audience:{}", audience.0), budget),
+                (format!("address:{}", address), budget.shared_by(AUDIENCE_MEMBERS_PER_ADDRESS)),
+            ],
+            None => vec![(format!("ip:{}", address), budget)],
+        };
+
+        // Audience members shouldn't be locked out of a presentation because the limiter broke.
+        // Buckets are taken from in order and lazily, so a request refused to an audience member
+        // doesn't also spend from their address's budget.
+        let now = Utc::now();
+        let wait = limits.into_iter()
+            .map(|(client, budget)| {
+                let key = format!("{}:{}:{}", self.action.as_str(), presentation.id.0, client);
+                self.buckets.take(&key, budget, now)
+            })
+            .filter_map(|decision| match decision {
+                Ok(Err(wait)) => Some(wait),
+                _ => None,
+            })
+            .next();
+        let wait = match wait {
+            Some(wait) => wait,
+            None => return Ok(()),
+        };
+        let response = too_many_requests(&RateLimitedResponse {
+            error: Some("You're doing that too often. Try again shortly.".to_string()),
+        }, wait);
+        Err(IronError {
+            error: Box::new(RateLimited(wait)),
+            response: response,
+        })
+    }
+}
@@ -0,0 +1,197 @@
+//! The tool side of an LTI 1.3 resource link launch, which lets learning management systems embed
+//! presentations in their courses.
+//!
+//! A launch begins with the platform calling our login initiation endpoint, from which we redirect
+//! back to the platform with a `state` and `nonce`. The platform then posts a signed ID token to our
+//! launch endpoint, which is validated here.
+
+use base64;
+use chrono::prelude::*;
+use ring::digest::{digest, SHA256};
+use serde_json::Value;
+use url::Url;
+
+use auth::jwt::{self, JwkSet};
+use models::Id;
+
+
+const MESSAGE_TYPE_CLAIM: &'static str = "https://purl.imsglobal.org/spec/lti/claim/message_type";
+const VERSION_CLAIM: &'static str = "https://purl.imsglobal.org/spec/lti/claim/version";
+const DEPLOYMENT_ID_CLAIM: &'static str = "https://purl.imsglobal.org/spec/lti/claim/deployment_id";
+const RESOURCE_LINK_CLAIM: &'static str = "https://purl.imsglobal.org/spec/lti/claim/resource_link";
+
+/// Prefixes the IDs of the presenters made for instructors' platform accounts, which are never
+/// valid email addresses and so never collide with a presenter who registered.
+const PLATFORM_PRESENTER_PREFIX: &'static str = "lti:";
+
+/// Prefixes the audience identities issued to learners by launches, which are never generated for
+/// audience members who join anonymously.
+const LAUNCH_AUDIENCE_PREFIX: &'static str = "lti:";
+
+/// Roles which let a user manage the presentation attached to a resource link.
+const INSTRUCTOR_ROLES: [&'static str; 4] = [
+    "http://purl.imsglobal.org/vocab/lis/v2/membership#Instructor",
+    "http://purl.imsglobal.org/vocab/lis/v2/membership#Administrator",
+    "http://purl.imsglobal.org/vocab/lis/v2/membership/Instructor#TeachingAssistant",
+    "http://purl.imsglobal.org/vocab/lis/v2/institution/person#Administrator",
+];
+
+
+/// A learning management system registered to launch AsQ.
+#[derive(Clone, Debug, Deserialize)]
+pub struct LtiPlatform {
+    pub issuer: String,
+    /// The client ID the platform assigned to AsQ when it was registered as a tool.
+    #[serde(rename = "clientId")]
+    pub client_id: String,
+    #[serde(rename = "deploymentIds")]
+    pub deployment_ids: Vec<String>,
+    /// The platform's OIDC authorization endpoint, which launches are redirected through.
+    #[serde(rename = "authLoginUrl")]
+    pub auth_login_url: String,
+    /// The keys the platform signs launches with.
+    pub keys: JwkSet,
+}
+
+/// The resource link a launch was made from, which identifies one placement of AsQ in a course.
+#[derive(Clone, Debug, Deserialize)]
+pub struct ResourceLink {
+    pub id: String,
+    pub title: Option<String>,
+}
+
+/// The claims of a validated resource link launch that AsQ makes use of.
+#[derive(Clone, Debug, Deserialize)]
+pub struct LaunchClaims {
+    pub iss: String,
+    pub sub: String,
+    pub aud: Value,
+    pub exp: i64,
+    pub nonce: String,
+    pub email: Option<String>,
+    pub name: Option<String>,
+    #[serde(rename = "https://purl.imsglobal.org/spec/lti/claim/message_type")]
+    pub message_type: String,
+    #[serde(rename = "https://purl.imsglobal.org/spec/lti/claim/version")]
+    pub version: String,
+    #[serde(rename = "https://purl.imsglobal.org/spec/lti/claim/deployment_id")]
+    pub deployment_id: String,
+    #[serde(rename = "https://purl.imsglobal.org/spec/lti/claim/roles")]
+    pub roles: Vec<String>,
+    #[serde(rename = "https://purl.imsglobal.org/spec/lti/claim/resource_link")]
+    pub resource_link: ResourceLink,
+}
+
+/// The parameters a platform sends to initiate a launch.
+#[derive(Clone, Debug)]
+pub struct LoginInitiation {
+    pub issuer: String,
+    pub login_hint: String,
+    pub target_link_uri: String,
+    pub client_id: Option<String>,
+    pub lti_message_hint: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct UnverifiedClaims {
+    iss: String,
+    aud: Value,
+}
+
+
+impl LaunchClaims {
+    /// Determine whether the launching user teaches, rather than attends, the course.
+    pub fn is_instructor(&self) -> bool {
+        self.roles.iter().any(|role| INSTRUCTOR_ROLES.contains(&role.as_str()))
+    }
+
+    /// The presenter an instructor is signed in as until they link their platform account to a
+    /// presenter of their own. It is derived from the platform's user ID alone, since the `email`
+    /// claim is whatever the platform says it is, and must not lead to an existing account.
+    pub fn presenter_id(&self) -> Id {
+        Id(format!("{}{}:{}", PLATFORM_PRESENTER_PREFIX, self.iss, self.sub))
+    }
+
+    /// The audience identity of a learner, which is stable across launches without revealing who
+    /// the learner is.
+    pub fn audience_id(&self) -> Id {
+        let user = format!("{}\n{}", self.iss, self.sub);
+        let hashed = digest(&SHA256, user.as_bytes());
+        Id(format!("{}{}", LAUNCH_AUDIENCE_PREFIX, base64::encode_config(hashed.as_ref(), base64::URL_SAFE_NO_PAD)))
+    }
+}
+
+/// Determine whether `audience` is the identity a launch issued to a learner's platform account.
+pub fn is_launch_audience(audience: &Id) -> bool {
+    audience.0.starts_with(LAUNCH_AUDIENCE_PREFIX)
+}
+
+/// Determine whether `presenter` was made for an instructor's platform account by a launch.
+pub fn is_platform_presenter(presenter: &Id) -> bool {
+    presenter.0.starts_with(PLATFORM_PRESENTER_PREFIX)
+}
+
+/// Find the platform that a login initiation came from.
+pub fn find_platform<'a>(platforms: &'a [LtiPlatform], issuer: &str, client_id: Option<&str>) -> Option<&'a LtiPlatform> {
+    platforms
+        .iter()
+        .find(|platform| platform.issuer == issuer && client_id.map(|id| id == platform.client_id).unwrap_or(true))
+}
+
+/// The URL on the platform to redirect a login initiation to, so that the platform posts the
+/// launch itself back to `redirect_uri`.
+pub fn authentication_request_url(
+    platform: &LtiPlatform,
+    initiation: &LoginInitiation,
+    redirect_uri: &str,
+    state: &str,
+    nonce: &str,
+) -> Result<String, String> {
+    let mut url = Url::parse(&platform.auth_login_url).map_err(|err| err.to_string())?;
+    url.query_pairs_mut()
+        .append_pair("scope", "openid")
+        .append_pair("response_type", "id_token")
+        .append_pair("response_mode", "form_post")
+        .append_pair("prompt", "none")
+        .append_pair("client_id", &platform.client_id)
+        .append_pair("redirect_uri", redirect_uri)
+        .append_pair("login_hint", &initiation.login_hint)
+        .append_pair("state", state)
+        .append_pair("nonce", nonce);
+    if let Some(ref hint) = initiation.lti_message_hint {
+        url.query_pairs_mut().append_pair("lti_message_hint", hint);
+    }
+    Ok(url.into_string())
+}
+
+/// Validate a launch's ID token against the keys of the platform that claims to have issued it,
+/// and check that it is a resource link launch made for us in response to the login identified by
+/// `nonce`, which has not expired at time `now`.
+pub fn validate_launch(platforms: &[LtiPlatform], id_token: &str, nonce: &str, now: DateTime<Utc>) -> Result<LaunchClaims, String> {
+    let unverified: UnverifiedClaims = jwt::unverified_claims(id_token)?;
+    let audiences = match unverified.aud {
+        Value::String(ref aud) => vec![aud.clone()],
+        Value::Array(ref auds) => auds.iter().filter_map(|aud| aud.as_str().map(String::from)).collect(),
+        _ => vec![],
+    };
+    let platform = platforms
+        .iter()
+        .find(|platform| platform.issuer == unverified.iss && audiences.contains(&platform.client_id))
+        .ok_or("Launch did not come from a registered platform.".to_string())?;
+    let claims: LaunchClaims = jwt::verify(id_token, &platform.keys)?;
+    if claims.exp <= now.timestamp() {
+        Err("Launch has expired.".to_string())
+    } else if claims.nonce != nonce {
+        Err("Launch does not belong to this login.".to_string())
+    } else if !platform.deployment_ids.contains(&claims.deployment_id) {
+        Err(format!("Unknown {}.", DEPLOYMENT_ID_CLAIM))
+    } else if claims.message_type != "LtiResourceLinkRequest" {
+        Err(format!("Unsupported {}.", MESSAGE_TYPE_CLAIM))
+    } else if claims.version != "1.3.0" {
+        Err(format!("Unsupported {}.", VERSION_CLAIM))
+    } else if claims.resource_link.id.is_empty() {
+        Err(format!("Missing {}.", RESOURCE_LINK_CLAIM))
+    } else {
+        Ok(claims)
+    }
+}
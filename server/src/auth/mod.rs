@@ -0,0 +1,136 @@
+use chrono::prelude::*;
+use ring::constant_time::verify_slices_are_equal;
+
+use capabilities::{Capability, Search, Update};
+use capabilities::sqlite::{ApiTokenUse, SQLite};
+use models::{Id, AccessGrant, Action, ApiToken, Audience, Membership, OrganizationMember, OrganizationRole,
+             Presentation, Role, Scope, Session};
+
+
+pub mod jwt;
+pub mod lti;
+pub mod oidc;
+pub mod password;
+pub mod totp;
+
+
+capability!(Authenticate for SQLite,
+            composing { Search<Session>,     Session,  String },
+                      { Search<ApiToken>,    ApiToken, String },
+                      { Update<ApiTokenUse>, usize,    String });
+
+capability!(Authorize for SQLite,
+            composing { Search<Membership>,         Membership,         String },
+                      { Search<OrganizationMember>, OrganizationMember, String });
+
+capability!(CheckAccess for SQLite,
+            composing { Search<AccessGrant>, AccessGrant, String });
+
+capability!(IdentifyAudience for SQLite,
+            composing { Search<Audience>, Audience, String });
+
+/// Resolve a credential supplied by a client to the presenter it acts on behalf of.
+///
+/// Both interactive sessions and API tokens are accepted. A session may perform any operation,
+/// while an API token must have been granted `scope`. Every successful use of an API token is
+/// recorded as its `last_used` time.
+pub fn authenticate<DB>(db: &DB, credential: Id, scope: Scope) -> Result<Id, String>
+    where DB: Authenticate
+{
+    if !ApiToken::is_api_token(&credential) {
+        return db.perform(Search(Session::search_parameter(credential))).map(|session| session.owner);
+    }
+    let token = db.perform(Search(ApiToken::search_parameter(credential)))?;
+    if !token.permits(scope) {
+        return Err("You are not allowed to do that!".to_string());
+    }
+    // A token revoked since it was read is no longer updated, and must not be honoured either.
+    match db.perform(Update(ApiTokenUse { token_id: token.id, used_at: Utc::now() }))? {
+        0 => Err("You are not allowed to do that!".to_string()),
+        _ => Ok(token.owner),
+    }
+}
+
+/// Determine the part `presenter` plays in `presentation`, if any. The presenter who created a
+/// presentation is always its owner, as are the admins of the organization it belongs to.
+pub fn role_of<DB>(db: &DB, presenter: &Id, presentation: &Presentation) -> Option<Role>
+    where DB: Authorize
+{
+    if presentation.creator == *presenter {
+        return Some(Role::Owner);
+    }
+    if let Some(ref organization) = presentation.organization {
+        if organization_role_of(db, presenter, organization) == Some(OrganizationRole::Admin) {
+            return Some(Role::Owner);
+        }
+    }
+    let membership = Membership::search_parameter(presentation.id.clone(), presenter.clone());
+    db.perform(Search(membership)).ok().map(|membership| membership.role)
+}
+
+/// Determine the part `presenter` plays in `organization`, if any.
+pub fn organization_role_of<DB>(db: &DB, presenter: &Id, organization: &Id) -> Option<OrganizationRole>
+    where DB: Authorize
+{
+    let member = OrganizationMember::search_parameter(organization.clone(), presenter.clone());
+    db.perform(Search(member)).ok().map(|member| member.role)
+}
+
+/// Resolve a credential to the presenter it acts on behalf of, as `authenticate` does, and ensure
+/// that their role in `presentation` allows them to take `action`.
+pub fn authorize<DB>(db: &DB, credential: Id, scope: Scope, presentation: &Presentation, action: Action)
+    -> Result<Id, String>
+    where DB: Authenticate + Authorize
+{
+    let presenter = authenticate(db, credential, scope)?;
+    match role_of(db, &presenter, presentation) {
+        Some(role) if role.permits(action) => Ok(presenter),
+        _                                  => Err("You are not allowed to do that!".to_string()),
+    }
+}
+
+/// Resolve a credential to the presenter it acts on behalf of, as `authenticate` does, and ensure
+/// that they belong to `organization`. When `role` is `Admin`, they must be one of its admins.
+pub fn authorize_in_organization<DB>(db: &DB, credential: Id, scope: Scope, organization: &Id,
+                                    role: OrganizationRole) -> Result<Id, String>
+    where DB: Authenticate + Authorize
+{
+    let presenter = authenticate(db, credential, scope)?;
+    match (organization_role_of(db, &presenter, organization), role) {
+        (Some(OrganizationRole::Admin), _)                         => Ok(presenter),
+        (Some(OrganizationRole::Member), OrganizationRole::Member) => Ok(presenter),
+        _ => Err("You are not allowed to do that!".to_string()),
+    }
+}
+
+/// Ensure that an audience member may take part in `presentation`. Anyone may take part in a
+/// public presentation, but private ones require an access grant obtained with the passcode.
+pub fn check_access<DB>(db: &DB, presentation: &Presentation, access_token: Option<Id>) -> Result<(), String>
+    where DB: CheckAccess
+{
+    if !presentation.is_private {
+        return Ok(());
+    }
+    let token = access_token.ok_or("This presentation requires a passcode.".to_string())?;
+    let grant = db.perform(Search(AccessGrant::search_parameter(token)))
+        .map_err(|_| "This presentation requires a passcode.".to_string())?;
+    if grant.permits(&presentation.id, Utc::now()) {
+        Ok(())
+    } else {
+        Err("This presentation requires a passcode.".to_string())
+    }
+}
+
+/// Confirm that a request claiming to come from the audience member `audience` carries their
+/// submission token.
+pub fn identify_audience<DB>(db: &DB, audience: Id, submission_token: &str) -> Result<Id, String>
+    where DB: IdentifyAudience
+{
+    let found = db.perform(Search(Audience {
+        id: audience,
+        submission_token: String::new(),
+    }))?;
+    verify_slices_are_equal(found.submission_token.as_bytes(), submission_token.as_bytes())
+        .map(|_| found.id)
+        .map_err(|_| "No such audience member.".to_string())
+}
@@ -0,0 +1,206 @@
+//! The relying party side of the OpenID Connect authorization code flow, with PKCE.
+
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use base64;
+use chrono::prelude::*;
+use rand;
+use rand::Rng;
+use reqwest;
+use ring::digest::{digest, SHA256};
+use serde_json::Value;
+use url::Url;
+
+use auth::jwt::{self, JwkSet};
+use models::Id;
+
+
+/// The prefix of the IDs of presenters created for provider accounts whose email address already
+/// belongs to another presenter. Such presenters are folded into the other once the two are linked.
+const PROVIDER_PRESENTER_PREFIX: &'static str = "oidc:";
+
+
+/// The settings needed to sign presenters in through an OpenID Connect provider.
+#[derive(Clone, Debug)]
+pub struct OidcConfig {
+    /// The issuer identifier, from which the provider's configuration is discovered.
+    pub issuer: String,
+    pub client_id: String,
+    pub client_secret: String,
+    /// Where the provider sends presenters back to after they sign in, i.e. our callback endpoint.
+    pub redirect_uri: String,
+    /// Where presenters are sent once they have a session. The session token is appended as a
+    /// URL fragment, so that it never reaches server logs.
+    pub post_login_redirect: String,
+}
+
+/// An OpenID Connect provider whose configuration has been discovered.
+pub struct OidcProvider {
+    pub config: OidcConfig,
+    authorization_endpoint: String,
+    token_endpoint: String,
+    jwks_uri: String,
+    keys: RwLock<JwkSet>,
+}
+
+/// The claims from an ID token that AsQ makes use of.
+#[derive(Clone, Debug, Deserialize)]
+pub struct IdTokenClaims {
+    pub iss: String,
+    pub sub: String,
+    pub aud: Value,
+    pub exp: i64,
+    pub nonce: Option<String>,
+    pub email: Option<String>,
+    pub email_verified: Option<bool>,
+}
+
+impl IdTokenClaims {
+    /// The ID of the presenter to create for the provider account when its email address is taken.
+    pub fn provider_presenter_id(&self) -> Id {
+        Id(format!("{}{}:{}", PROVIDER_PRESENTER_PREFIX, self.iss, self.sub))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Discovery {
+    issuer: String,
+    authorization_endpoint: String,
+    token_endpoint: String,
+    jwks_uri: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    id_token: String,
+}
+
+
+impl OidcProvider {
+    /// Fetch the provider's configuration and signing keys from its discovery document.
+    pub fn discover(config: OidcConfig) -> Result<Self, String> {
+        let discovery_url = format!("{}/.well-known/openid-configuration", config.issuer.trim_right_matches('/'));
+        let discovery: Discovery = fetch_json(&discovery_url)?;
+        if discovery.issuer != config.issuer {
+            return Err("Discovered issuer does not match the configured issuer.".to_string());
+        }
+        let keys: JwkSet = fetch_json(&discovery.jwks_uri)?;
+        Ok(OidcProvider {
+            config: config,
+            authorization_endpoint: discovery.authorization_endpoint,
+            token_endpoint: discovery.token_endpoint,
+            jwks_uri: discovery.jwks_uri,
+            keys: RwLock::new(keys),
+        })
+    }
+
+    /// The URL to send a presenter to in order to sign in with the provider.
+    pub fn authorization_url(&self, state: &str, nonce: &str, code_verifier: &str) -> Result<String, String> {
+        let mut url = Url::parse(&self.authorization_endpoint).map_err(|err| err.to_string())?;
+        url.query_pairs_mut()
+            .append_pair("response_type", "code")
+            .append_pair("scope", "openid email")
+            .append_pair("client_id", &self.config.client_id)
+            .append_pair("redirect_uri", &self.config.redirect_uri)
+            .append_pair("state", state)
+            .append_pair("nonce", nonce)
+            .append_pair("code_challenge", &pkce_challenge(code_verifier))
+            .append_pair("code_challenge_method", "S256");
+        Ok(url.into_string())
+    }
+
+    /// Redeem an authorization code at the provider's token endpoint for an ID token.
+    pub fn exchange_code(&self, code: &str, code_verifier: &str) -> Result<String, String> {
+        let mut params = HashMap::new();
+        params.insert("grant_type", "authorization_code");
+        params.insert("code", code);
+        params.insert("redirect_uri", &self.config.redirect_uri);
+        params.insert("code_verifier", code_verifier);
+        let client = reqwest::Client::new();
+        let mut response = client
+            .post(&self.token_endpoint)
+            .basic_auth(self.config.client_id.clone(), Some(self.config.client_secret.clone()))
+            .form(&params)
+            .send()
+            .map_err(|err| err.to_string())?;
+        if !response.status().is_success() {
+            return Err(format!("Token endpoint responded with {}.", response.status()));
+        }
+        response
+            .json::<TokenResponse>()
+            .map(|tokens| tokens.id_token)
+            .map_err(|err| err.to_string())
+    }
+
+    /// Verify an ID token's signature and check that it was issued by this provider, for us, in
+    /// response to the login attempt identified by `nonce`, and has not expired at time `now`.
+    pub fn validate_id_token(&self, id_token: &str, nonce: &str, now: DateTime<Utc>) -> Result<IdTokenClaims, String> {
+        let claims: IdTokenClaims = match self.verify_signature(id_token) {
+            Ok(claims) => claims,
+            Err(_) => {
+                // The provider may have rotated its keys since we last fetched them.
+                self.refresh_keys()?;
+                self.verify_signature(id_token)?
+            },
+        };
+        let audience_matches = match claims.aud {
+            Value::String(ref aud) => *aud == self.config.client_id,
+            Value::Array(ref auds) => auds.iter().any(|aud| aud.as_str() == Some(&self.config.client_id)),
+            _ => false,
+        };
+        if claims.iss != self.config.issuer {
+            Err("ID token was not issued by the configured provider.".to_string())
+        } else if !audience_matches {
+            Err("ID token was not issued for this client.".to_string())
+        } else if claims.exp <= now.timestamp() {
+            Err("ID token has expired.".to_string())
+        } else if claims.nonce.as_ref().map(String::as_str) != Some(nonce) {
+            Err("ID token does not belong to this login attempt.".to_string())
+        } else {
+            Ok(claims)
+        }
+    }
+
+    fn verify_signature(&self, id_token: &str) -> Result<IdTokenClaims, String> {
+        let keys = self.keys.read().map_err(|_| "Signing keys unavailable.".to_string())?;
+        jwt::verify(id_token, &keys)
+    }
+
+    fn refresh_keys(&self) -> Result<(), String> {
+        let fresh: JwkSet = fetch_json(&self.jwks_uri)?;
+        let mut keys = self.keys.write().map_err(|_| "Signing keys unavailable.".to_string())?;
+        *keys = fresh;
+        Ok(())
+    }
+}
+
+/// Determine whether a presenter was created for a provider account, and has yet to be linked to
+/// the presenter who owns its email address.
+pub fn is_provider_presenter(presenter: &Id) -> bool {
+    presenter.0.starts_with(PROVIDER_PRESENTER_PREFIX)
+}
+
+/// Generate a random value suitable for use as a `state`, `nonce` or PKCE code verifier.
+pub fn random_value() -> String {
+    let mut rng = rand::thread_rng();
+    let mut bytes = [0u8; 32];
+    rng.fill_bytes(&mut bytes);
+    base64::encode_config(&bytes, base64::URL_SAFE_NO_PAD)
+}
+
+/// Derive the S256 PKCE code challenge for a code verifier, as described in RFC 7636.
+pub fn pkce_challenge(code_verifier: &str) -> String {
+    let hashed = digest(&SHA256, code_verifier.as_bytes());
+    base64::encode_config(hashed.as_ref(), base64::URL_SAFE_NO_PAD)
+}
+
+fn fetch_json<T>(url: &str) -> Result<T, String>
+    where T: ::serde::de::DeserializeOwned
+{
+    let mut response = reqwest::get(url).map_err(|err| err.to_string())?;
+    if !response.status().is_success() {
+        return Err(format!("{} responded with {}.", url, response.status()));
+    }
+    response.json::<T>().map_err(|err| err.to_string())
+}
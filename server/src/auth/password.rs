@@ -0,0 +1,16 @@
+//! Hashing of secrets chosen by people, such as presenters' passwords and presentation passcodes,
+//! which are too guessable to store as a plain digest.
+
+use password_hash::scrypt::{ScryptParams, scrypt_simple, scrypt_check};
+
+
+/// Hash a secret for storage.
+pub fn hash(secret: &str) -> String {
+    let params = ScryptParams::new(14, 8, 1);
+    scrypt_simple(secret, &params).unwrap()
+}
+
+/// Check whether a secret matches a hash produced by `hash`.
+pub fn matches(secret: &str, hashed: &str) -> bool {
+    scrypt_check(secret, hashed).unwrap_or(false)
+}
@@ -0,0 +1,109 @@
+//! Time-based one-time passwords as described in RFC 6238, compatible with common authenticator
+//! apps (HMAC-SHA1, six digits, thirty second steps).
+
+use base32;
+use chrono::prelude::*;
+use rand;
+use rand::Rng;
+use ring::digest::{digest, SHA1, SHA256};
+use ring::hmac;
+use url::percent_encoding::{utf8_percent_encode, QUERY_ENCODE_SET};
+
+
+/// The number of seconds each code remains current for.
+pub const TIME_STEP: i64 = 30;
+
+/// The number of digits in each code.
+pub const DIGITS: u32 = 6;
+
+/// How many steps before or after the current one we accept, to tolerate clock drift.
+const ALLOWED_DRIFT_STEPS: i64 = 1;
+
+/// The name shown in authenticator apps next to the presenter's email address.
+const ISSUER: &'static str = "AsQ";
+
+/// The number of recovery codes issued at enrollment.
+const RECOVERY_CODE_COUNT: usize = 10;
+
+const BASE32: base32::Alphabet = base32::Alphabet::RFC4648 { padding: false };
+
+
+/// Generate a new random 160-bit shared secret, encoded in base32.
+pub fn generate_secret() -> String {
+    let mut rng = rand::thread_rng();
+    let mut bytes = [0u8; 20];
+    rng.fill_bytes(&mut bytes);
+    base32::encode(BASE32, &bytes)
+}
+
+/// Compute the code for a base32-encoded secret at the given time.
+pub fn code_at(secret: &str, time: DateTime<Utc>) -> Option<u32> {
+    let key = base32::decode(BASE32, secret)?;
+    Some(hotp(&key, (time.timestamp() / TIME_STEP) as u64))
+}
+
+/// Check a code supplied by a presenter against their secret at time `now`.
+pub fn verify(secret: &str, code: &str, now: DateTime<Utc>) -> bool {
+    verify_step(secret, code, now, None).is_some()
+}
+
+/// Check a code supplied by a presenter against their secret at time `now`, returning the time
+/// step it belongs to. Only steps after `last_step`, the step of the last code the presenter got
+/// in with, are accepted, so that a code cannot be replayed while it remains current.
+pub fn verify_step(secret: &str, code: &str, now: DateTime<Utc>, last_step: Option<i64>) -> Option<i64> {
+    let code = code.trim();
+    if code.len() != DIGITS as usize || !code.chars().all(|c| c.is_digit(10)) {
+        return None;
+    }
+    let code = code.parse::<u32>().ok()?;
+    let key = base32::decode(BASE32, secret)?;
+    let current_step = now.timestamp() / TIME_STEP;
+    (-ALLOWED_DRIFT_STEPS..ALLOWED_DRIFT_STEPS + 1)
+        .map(|offset| current_step + offset)
+        .filter(|step| last_step.map(|last| *step > last).unwrap_or(true))
+        .fold(None, |matched, step| if hotp(&key, step as u64) == code { Some(step) } else { matched })
+}
+
+/// Build the `otpauth://` URI that authenticator apps scan to enroll a secret.
+pub fn provisioning_uri(account: &str, secret: &str) -> String {
+    let label = utf8_percent_encode(&format!("{}:{}", ISSUER, account), QUERY_ENCODE_SET).to_string();
+    format!("otpauth://totp/{}?secret={}&issuer={}&algorithm=SHA1&digits={}&period={}",
+            label.replace("/", "%2F"), secret, ISSUER, DIGITS, TIME_STEP)
+}
+
+/// Generate a set of single-use recovery codes for a presenter who has lost their authenticator.
+pub fn generate_recovery_codes() -> Vec<String> {
+    let mut rng = rand::thread_rng();
+    (0..RECOVERY_CODE_COUNT)
+        .map(|_| {
+            let mut bytes = [0u8; 5];
+            rng.fill_bytes(&mut bytes);
+            let code = base32::encode(BASE32, &bytes).to_lowercase();
+            format!("{}-{}", &code[..4], &code[4..])
+        })
+        .collect()
+}
+
+/// The digest under which a recovery code is stored, so that a leaked database can't be used to
+/// bypass a presenter's second factor.
+pub fn recovery_code_digest(code: &str) -> String {
+    let normalized = code.trim().to_lowercase();
+    base32::encode(BASE32, digest(&SHA256, normalized.as_bytes()).as_ref())
+}
+
+/// The HOTP algorithm from RFC 4226, which TOTP applies to a time-derived counter.
+fn hotp(key: &[u8], counter: u64) -> u32 {
+    let mut message = [0u8; 8];
+    for i in 0..8 {
+        message[i] = (counter >> (56 - 8 * i)) as u8;
+    }
+    let signing_key = hmac::SigningKey::new(&SHA1, key);
+    let signature = hmac::sign(&signing_key, &message);
+    let mac = signature.as_ref();
+    let offset = (mac[mac.len() - 1] & 0x0f) as usize;
+    let truncated = ((mac[offset] as u32 & 0x7f) << 24)
+        | ((mac[offset + 1] as u32) << 16)
+        | ((mac[offset + 2] as u32) << 8)
+        | (mac[offset + 3] as u32);
+    truncated % 10u32.pow(DIGITS)
+}
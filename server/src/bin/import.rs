@@ -0,0 +1,91 @@
+//! Imports the questions in a CSV or JSON document, along with their answers, straight into the
+//! database of a server.
+//!
+//!     server_import <presenter> <document> [presentation]
+//!
+//! The document's format is taken from its extension. Questions are imported into the given
+//! presentation, or into a new one created by the presenter when none is given. Nothing is
+//! imported unless every question in the document is valid.
+
+extern crate rusqlite as sqlite;
+extern crate server_lib as server;
+
+use std::env;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use std::process;
+use std::sync::{Arc, Mutex};
+
+use server::capabilities::{Capability, Save, Search};
+use server::capabilities::initializers::init_sqlite_tables;
+use server::capabilities::sqlite::{Import, SQLite};
+use server::export::Format;
+use server::import;
+use server::models::{Id, Presentation};
+
+
+const DATABASE_FILE: &'static str = "asq.db";
+
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    if args.len() < 3 || args.len() > 4 {
+        fail("Usage: server_import <presenter> <document> [presentation]");
+    }
+    let presenter = Id(args[1].clone());
+    let path = Path::new(&args[2]);
+    let format = path.extension()
+        .and_then(|extension| extension.to_str())
+        .and_then(Format::from_str)
+        .unwrap_or_else(|| fail("Documents must be .csv or .json files."));
+    let mut text = String::new();
+    File::open(path)
+        .and_then(|mut file| file.read_to_string(&mut text))
+        .unwrap_or_else(|err| fail(&format!("Could not read {}: {}", path.display(), err)));
+    let document = import::parse(format, &text).unwrap_or_else(|errors| {
+        for error in errors {
+            eprintln!("Row {}: {}", error.row, error.message);
+        }
+        fail("Nothing was imported.")
+    });
+
+    let db_connection = Arc::new(Mutex::new(
+        sqlite::Connection::open(DATABASE_FILE).expect("Could not connect to database.")
+    ));
+    let db = SQLite::new(db_connection);
+    init_sqlite_tables(&db).expect("Could not create tables.");
+
+    let (presentation, new_presentation) = match args.get(3) {
+        Some(id) => {
+            let presentation = db.perform(Search(Presentation::search_parameter(Id(id.clone()))))
+                .unwrap_or_else(|_| fail("No such presentation."));
+            if presentation.creator != presenter {
+                fail("The presentation belongs to another presenter.");
+            }
+            (presentation, false)
+        },
+        None => {
+            let title = document.title.clone()
+                .or(path.file_stem().and_then(|stem| stem.to_str()).map(|stem| stem.to_string()))
+                .unwrap_or_else(|| fail("The new presentation needs a title."));
+            (Presentation::new(presenter, title), true)
+        },
+    };
+    let questions = import::records(document, &presentation);
+    let import = Import {
+        presentation: presentation,
+        new_presentation: new_presentation,
+        questions: questions,
+    };
+    match db.perform(Save(import)) {
+        Ok(import) => println!("Imported {} questions into presentation {}.",
+                               import.questions.len(), import.presentation.id.0),
+        Err(err) => fail(&format!("Nothing was imported: {}", err)),
+    }
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("{}", message);
+    process::exit(1)
+}
@@ -1,15 +1,55 @@
 use capabilities::{Capability, CreateTable};
 use capabilities::sqlite::SQLite;
-use models::Question;
+use models::{AccessGrant, AccountToken, Answer, ApiToken, Audience, ExternalIdentity, Flag, Invitation,
+             LtiResourceLink, Membership, OidcLoginAttempt, Organization, OrganizationMember, OwnershipTransfer,
+             Presentation, Presenter, Question, SearchResult, Session, TokenBucket};
 
 
 capability!(CreateAllTables for SQLite,
-            composing { CreateTable<Question>, (), String });
+            composing { CreateTable<Presenter>,          (), String },
+                      { CreateTable<Question>,           (), String },
+                      { CreateTable<AccountToken>,       (), String },
+                      { CreateTable<Session>,            (), String },
+                      { CreateTable<ApiToken>,           (), String },
+                      { CreateTable<OidcLoginAttempt>,   (), String },
+                      { CreateTable<ExternalIdentity>,   (), String },
+                      { CreateTable<Presentation>,       (), String },
+                      { CreateTable<Audience>,           (), String },
+                      { CreateTable<LtiResourceLink>,    (), String },
+                      { CreateTable<Answer>,             (), String },
+                      { CreateTable<AccessGrant>,        (), String },
+                      { CreateTable<TokenBucket>,        (), String },
+                      { CreateTable<Flag>,               (), String },
+                      { CreateTable<Membership>,         (), String },
+                      { CreateTable<Invitation>,         (), String },
+                      { CreateTable<Organization>,       (), String },
+                      { CreateTable<OrganizationMember>, (), String },
+                      { CreateTable<OwnershipTransfer>,  (), String },
+                      { CreateTable<SearchResult>,       (), String });
 
 /// Run `create table` operations for every table in the database.
 pub fn init_sqlite_tables<DB>(db: &DB) -> Result<(), String>
     where DB: CreateAllTables
 {
-    db.perform(CreateTable::new())?;
+    db.perform(CreateTable::<Presenter>::new())?;
+    db.perform(CreateTable::<Question>::new())?;
+    db.perform(CreateTable::<AccountToken>::new())?;
+    db.perform(CreateTable::<Session>::new())?;
+    db.perform(CreateTable::<ApiToken>::new())?;
+    db.perform(CreateTable::<OidcLoginAttempt>::new())?;
+    db.perform(CreateTable::<ExternalIdentity>::new())?;
+    db.perform(CreateTable::<Presentation>::new())?;
+    db.perform(CreateTable::<Audience>::new())?;
+    db.perform(CreateTable::<LtiResourceLink>::new())?;
+    db.perform(CreateTable::<Answer>::new())?;
+    db.perform(CreateTable::<AccessGrant>::new())?;
+    db.perform(CreateTable::<TokenBucket>::new())?;
+    db.perform(CreateTable::<Flag>::new())?;
+    db.perform(CreateTable::<Membership>::new())?;
+    db.perform(CreateTable::<Invitation>::new())?;
+    db.perform(CreateTable::<Organization>::new())?;
+    db.perform(CreateTable::<OrganizationMember>::new())?;
+    db.perform(CreateTable::<OwnershipTransfer>::new())?;
+    db.perform(CreateTable::<SearchResult>::new())?;
     Ok(())
 }
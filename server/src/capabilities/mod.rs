@@ -42,6 +42,9 @@ pub struct Search<T>(pub T);
 /// A name to tie to a find-all operation on a particular data type.
 pub struct FindAll<T>(pub T);
 
+/// A name to tie to operations that summarize many records without reading each of them.
+pub struct Aggregate<T>(pub T);
+
 impl<T> CreateTable<T> {
     pub fn new() -> Self {
         CreateTable(PhantomData)
@@ -1,9 +1,17 @@
+use std::os::raw::c_int;
 use std::sync::{Arc, Mutex};
 
-use sqlite::Connection;
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use ring::constant_time::verify_slices_are_equal;
+use serde_json;
+use sqlite::{Connection, Error as SQLiteError, ErrorCode, Result as SQLiteResult};
 
-use capabilities::{Capability, CreateTable, FindAll, Save, Update, Delete, Search};
-use models::{Id, Answer, Question, Presenter, Presentation, Session};
+use capabilities::{Aggregate, Capability, CreateTable, FindAll, Save, Update, Delete, Search};
+use models::{Id, AccessGrant, AccountToken, Answer, ApiToken, Audience, Budget, ExternalIdentity, FilterSetting,
+             Flag, Invitation, JoinCode, LtiResourceLink, Membership, NodCount, OidcLoginAttempt, Organization,
+             OrganizationDefaults, OrganizationMember, OrganizationRole, OwnershipTransfer, PresentationActivity,
+             PresentationWithRole, Question, QuestionCounts, QuestionStatus, Presenter, Presentation, RateLimits,
+             Role, Scope, SearchResult, Session, TimeBucket, TokenBucket, TokenPurpose};
 
 
 /// SQLite implements a number of capabilities enabling CRUD operations on various models.
@@ -17,9 +25,181 @@ pub struct QuestionsForPresentation {
     pub presentation_id: Id,
 }
 
-/// A type used as an input for queries to find all of the presentations that a presenter has created.
+/// A type used as an input to end every session that a presenter has.
+pub struct SessionsForPresenter {
+    pub presenter_id: Id,
+}
+
+/// A type used as an input for queries to find all of the presentations that a presenter has
+/// created or is a member of, along with their role in each, optionally only those with a
+/// particular tag.
 pub struct PresentationsForPresenter {
     pub presenter_id: Id,
+    pub tag: Option<String>,
+}
+
+/// The orders that a presenter's dashboard can list their presentations in.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DashboardOrder {
+    /// Most recently active first.
+    LastActivity,
+    /// Most recently created first.
+    Created,
+}
+
+/// A type used as an input to summarize the activity in each presentation a presenter has created
+/// or is a member of, optionally only those created within a range of dates, that are open or closed, or that have a
+/// particular tag.
+pub struct PresenterDashboard {
+    pub presenter_id: Id,
+    pub created_after: Option<DateTime<Utc>>,
+    pub created_before: Option<DateTime<Utc>>,
+    pub open: Option<bool>,
+    pub tag: Option<String>,
+    pub order: DashboardOrder,
+}
+
+/// A type used as an input for queries to find all of the presentations that belong to an
+/// organization.
+pub struct PresentationsForOrganization {
+    pub organization_id: Id,
+}
+
+/// A type used as an input for queries to find every presentation that is due to be opened or
+/// closed to questions by `now`.
+pub struct DueSchedules {
+    pub now: DateTime<Utc>,
+}
+
+/// A type used as an input to delete every question that has been kept for longer than its
+/// presentation keeps questions for, as of `now`.
+pub struct ExpiredQuestions {
+    pub now: DateTime<Utc>,
+}
+
+/// A type used as an input for full-text searches over the questions asked during a presentation
+/// and their answers.
+pub struct TextSearch {
+    pub presentation_id: Id,
+    pub text: String,
+    pub include_hidden: bool,
+    pub limit: u32,
+}
+
+/// A type used as an input for queries to find one page of the questions asked during a
+/// presentation, in the order they were asked.
+pub struct QuestionsPage {
+    pub presentation_id: Id,
+    pub offset: u32,
+    pub limit: u32,
+}
+
+/// A type used as an input for queries to find the questions asked during a presentation that
+/// received the most nods. Only questions the audience can see are found.
+pub struct TopQuestions {
+    pub presentation_id: Id,
+    pub limit: u32,
+}
+
+/// A type used as an input to count the questions asked during a presentation, and how many of
+/// them were answered. Like the other statistics, only questions the audience can see are counted,
+/// so those held back, rejected or merged into another are left out.
+pub struct QuestionTotals {
+    pub presentation_id: Id,
+}
+
+/// A type used as an input to count how many questions asked during a presentation received each
+/// number of nods.
+pub struct NodDistribution {
+    pub presentation_id: Id,
+}
+
+/// A type used as an input to count how many questions were asked during a presentation in each
+/// span of `bucket_minutes` minutes.
+pub struct QuestionsOverTime {
+    pub presentation_id: Id,
+    pub bucket_minutes: u32,
+}
+
+/// A type used as an input to find the median number of seconds that passed between a question
+/// being asked during a presentation and its first answer.
+pub struct TimeToAnswer {
+    pub presentation_id: Id,
+}
+
+/// A type used as an input to nod to the question identified by `question_id`. The count is
+/// incremented in place, so that nods made at the same time are all counted.
+pub struct QuestionNod {
+    pub question_id: Id,
+}
+
+/// A type used as an input for queries to find all of the answers posted to a question.
+pub struct AnswersForQuestion {
+    pub question_id: Id,
+}
+
+/// A type used as an input to merge duplicate questions into the question `target`.
+pub struct MergeQuestions {
+    pub target: Id,
+    pub originals: Vec<Id>,
+}
+
+/// A type used as an input to create a batch of questions, along with their answers, all at once.
+/// The presentation they were asked during is created with them if it is `new_presentation`.
+pub struct Import {
+    pub presentation: Presentation,
+    pub new_presentation: bool,
+    pub questions: Vec<(Question, Vec<Answer>)>,
+}
+
+/// A type used as an input for queries to find all of the flags raised against a question.
+pub struct FlagsForQuestion {
+    pub question_id: Id,
+}
+
+/// A type used as an input for queries to find all of the flags raised against questions asked
+/// during a presentation.
+pub struct FlagsForPresentation {
+    pub presentation_id: Id,
+}
+
+/// A type used as an input for queries to find every presenter who has been given a role in a
+/// presentation.
+pub struct MembersOfPresentation {
+    pub presentation_id: Id,
+}
+
+/// A type used as an input to hand the presentation `presentation_id` over from the presenter
+/// `from` to the presenter `to`, once `to` has accepted the offer.
+pub struct TransferOwnership {
+    pub presentation_id: Id,
+    pub from: Id,
+    pub to: Id,
+}
+
+/// A type used as an input to fold the presenter `from`, made for an instructor's account on a
+/// learning management system or for an identity provider account, into the presenter `to` that
+/// the account was linked to.
+pub struct MergePlatformPresenter {
+    pub from: Id,
+    pub to: Id,
+}
+
+/// A type used as an input for queries to find every presenter in an organization.
+pub struct MembersOfOrganization {
+    pub organization_id: Id,
+}
+
+/// A type used as an input to record that the API token identified by `token_id` was used at
+/// `used_at`. Only that column is written, so that a revocation made meanwhile is never undone.
+pub struct ApiTokenUse {
+    pub token_id: Id,
+    pub used_at: DateTime<Utc>,
+}
+
+/// A type used as an input for queries to find all of the API tokens a presenter has minted.
+pub struct TokensForPresenter {
+    pub presenter_id: Id,
 }
 
 impl SQLite {
@@ -31,12 +211,51 @@ impl SQLite {
     }
 }
 
+/// Find the names of the columns of a table, of which there are none if the table doesn't exist.
+fn table_columns(db: &Connection, table: &str) -> SQLiteResult<Vec<String>> {
+    let mut statement = db.prepare(&format!("pragma table_info({})", table))?;
+    let columns = statement.query_map(&[], |row| row.get::<_, String>(1))?.collect();
+    columns
+}
+
+/// Add whichever of `columns` a table doesn't have yet, so that databases created by earlier
+/// versions of the server gain the columns added to their tables since. Each column is given with
+/// its type and, for columns that can't be null, the default that rows already in the table get.
+fn add_missing_columns(db: &Connection, table: &str, columns: &[(&str, &str)]) -> SQLiteResult<()> {
+    let existing = table_columns(db, table)?;
+    for &(name, definition) in columns.iter().filter(|&&(name, _)| !existing.iter().any(|column| column == name)) {
+        db.execute(&format!("alter table {} add column {} {}", table, name, definition), &[])?;
+    }
+    Ok(())
+}
+
 impl Capability<CreateTable<Question>> for SQLite {
     type Data = ();
     type Error = String;
 
     fn perform(&self, _operation: CreateTable<Question>) -> Result<Self::Data, Self::Error> {
-        Ok(())
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        db.execute_batch(
+            "create table if not exists questions (
+                id              text primary key,
+                presentation    text not null,
+                text            text not null,
+                nods            integer not null,
+                answered        integer not null,
+                ask_date        text not null,
+                status          text not null,
+                review_reason   text,
+                reviewed_at     text,
+                merged_into     text
+            );
+            create index if not exists questions_presentation on questions (presentation);")
+            .and_then(|_| add_missing_columns(&db, "questions", &[
+                ("status",        "text not null default 'approved'"),
+                ("review_reason", "text"),
+                ("reviewed_at",   "text"),
+                ("merged_into",   "text"),
+            ]))
+            .map_err(|err| err.to_string())
     }
 }
 
@@ -45,7 +264,11 @@ impl Capability<Save<Question>> for SQLite {
     type Error = String;
 
     fn perform(&self, operation: Save<Question>) -> Result<Self::Data, Self::Error> {
-        Ok(operation.0)
+        let question = operation.0;
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        insert_question(&db, &question)
+            .map(|_| question)
+            .map_err(|err| err.to_string())
     }
 }
 
@@ -55,7 +278,12 @@ impl Capability<Search<Question>> for SQLite {
 
 
     fn perform(&self, operation: Search<Question>) -> Result<Self::Data, Self::Error> {
-        Ok(operation.0)
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        db.query_row(
+            &format!("select {} from questions where id = ?1", QUESTION_COLUMNS),
+            &[&(operation.0).id.0],
+            |row| question_from_row(row, 0))
+            .map_err(|err| err.to_string())
     }
 }
 
@@ -63,17 +291,379 @@ impl Capability<Update<Question>> for SQLite {
     type Data = ();
     type Error = String;
 
-    fn perform(&self, _operation: Update<Question>) -> Result<Self::Data, Self::Error> {
-        Ok(())
+    fn perform(&self, operation: Update<Question>) -> Result<Self::Data, Self::Error> {
+        let question = operation.0;
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        db.execute(
+            "update questions
+             set text = ?1, nods = ?2, answered = ?3, status = ?4, review_reason = ?5, reviewed_at = ?6
+             where id = ?7",
+            &[&question.text, &question.nods, &question.answered, &question.status.as_str(),
+              &question.review_reason, &question.reviewed_at, &question.id.0])
+            .and_then(|_| db.execute(
+                "update search_index set text = ?1 where kind = 'question' and id = ?2",
+                &[&question.text, &question.id.0]))
+            .map(|_| ())
+            .map_err(|err| err.to_string())
+    }
+}
+
+impl Capability<Update<QuestionNod>> for SQLite {
+    type Data = usize;
+    type Error = String;
+
+    /// Produces the number of questions nodded to, which is zero unless the question is visible to
+    /// the audience.
+    fn perform(&self, operation: Update<QuestionNod>) -> Result<Self::Data, Self::Error> {
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        db.execute(
+            "update questions set nods = nods + 1
+             where id = ?1 and status = 'approved' and merged_into is null",
+            &[&(operation.0).question_id.0])
+            .map(|updated| updated as usize)
+            .map_err(|err| err.to_string())
+    }
+}
+
+impl Capability<Delete<Question>> for SQLite {
+    type Data = ();
+    type Error = String;
+
+    /// Deleting a question also deletes its answers.
+    fn perform(&self, operation: Delete<Question>) -> Result<Self::Data, Self::Error> {
+        let id = &(operation.0).id.0;
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        let deleted = db.execute("delete from questions where id = ?1", &[id])
+            .and_then(|deleted| db.execute("delete from answers where question = ?1", &[id]).map(|_| deleted))
+            .and_then(|deleted| db.execute("delete from search_index where question = ?1", &[id]).map(|_| deleted))
+            .map_err(|err| err.to_string())?;
+        if deleted == 1 {
+            Ok(())
+        } else {
+            Err("No such question.".to_string())
+        }
+    }
+}
+
+impl Capability<Update<MergeQuestions>> for SQLite {
+    type Data = Question;
+    type Error = String;
+
+    /// The nods of the original questions are added to the target question, and the originals
+    /// remember which question they were merged into. Nothing changes unless every question belongs
+    /// to the same presentation and none has already been merged.
+    fn perform(&self, operation: Update<MergeQuestions>) -> Result<Self::Data, Self::Error> {
+        let merge = operation.0;
+        let mut db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        let transaction = db.transaction().map_err(|err| err.to_string())?;
+        let find = |id: &Id| transaction.query_row(
+            &format!("select {} from questions where id = ?1", QUESTION_COLUMNS),
+            &[&id.0],
+            |row| question_from_row(row, 0))
+            .map_err(|_| "No such question.".to_string());
+        let mut target = find(&merge.target)?;
+        if target.merged_into.is_some() {
+            return Err("Questions cannot be merged into a merged question.".to_string());
+        }
+        for id in merge.originals.iter() {
+            let original = find(id)?;
+            let is_mergeable = original.id != target.id && original.presentation == target.presentation
+                && original.merged_into.is_none();
+            if !is_mergeable {
+                return Err("Only other unmerged questions from the same presentation can be merged.".to_string());
+            }
+            target.nods += original.nods;
+            transaction.execute(
+                "update questions set merged_into = ?1 where id = ?2",
+                &[&target.id.0, &original.id.0])
+                .map_err(|err| err.to_string())?;
+        }
+        transaction.execute("update questions set nods = ?1 where id = ?2", &[&target.nods, &target.id.0])
+            .and_then(|_| transaction.commit())
+            .map(|_| target)
+            .map_err(|err| err.to_string())
+    }
+}
+
+impl Capability<Save<Import>> for SQLite {
+    type Data = Import;
+    type Error = String;
+
+    /// Either every record is created, or none are.
+    fn perform(&self, operation: Save<Import>) -> Result<Self::Data, Self::Error> {
+        let mut import = operation.0;
+        let mut db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        let transaction = db.transaction().map_err(|err| err.to_string())?;
+        if import.new_presentation {
+            insert_presentation(&transaction, &mut import.presentation)?;
+        }
+        for &(ref question, ref answers) in import.questions.iter() {
+            if question.presentation != import.presentation.id {
+                return Err("Questions can only be imported into a single presentation.".to_string());
+            }
+            insert_question(&transaction, question).map_err(|err| err.to_string())?;
+            for answer in answers.iter() {
+                insert_answer(&transaction, answer).map_err(|err| err.to_string())?;
+            }
+        }
+        transaction.commit()
+            .map(|_| import)
+            .map_err(|err| err.to_string())
+    }
+}
+
+impl Capability<Delete<ExpiredQuestions>> for SQLite {
+    type Data = usize;
+    type Error = String;
+
+    /// Questions are deleted along with their answers and flags, and the number of questions
+    /// deleted is returned.
+    fn perform(&self, operation: Delete<ExpiredQuestions>) -> Result<Self::Data, Self::Error> {
+        let now = (operation.0).now;
+        let mut db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        let transaction = db.transaction().map_err(|err| err.to_string())?;
+        let retention = {
+            let mut statement = transaction.prepare(
+                "select id, retention_days from presentations where retention_days is not null")
+                .map_err(|err| err.to_string())?;
+            let retention = statement
+                .query_map(&[], |row| (row.get::<_, String>(0), row.get::<_, u32>(1)))
+                .map_err(|err| err.to_string())?
+                .collect::<Result<Vec<(String, u32)>, _>>()
+                .map_err(|err| err.to_string())?;
+            retention
+        };
+        let mut deleted = 0;
+        for (presentation, days) in retention {
+            let cutoff = now - Duration::days(days as i64);
+            let expired = "select id from questions where presentation = ?1 and ask_date < ?2";
+            for table in &["answers", "flags", "search_index"] {
+                transaction.execute(&format!("delete from {} where question in ({})", table, expired),
+                                    &[&presentation, &cutoff])
+                    .map_err(|err| err.to_string())?;
+            }
+            deleted += transaction.execute("delete from questions where presentation = ?1 and ask_date < ?2",
+                                           &[&presentation, &cutoff])
+                .map_err(|err| err.to_string())? as usize;
+        }
+        transaction.commit()
+            .map(|_| deleted)
+            .map_err(|err| err.to_string())
     }
 }
 
+/// Insert a question, and index its text for searches.
+fn insert_question(db: &Connection, question: &Question) -> SQLiteResult<c_int> {
+    db.execute(
+        "insert into questions
+            (id, presentation, text, nods, answered, ask_date, status, review_reason, reviewed_at,
+             merged_into)
+         values (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+        &[&question.id.0, &question.presentation.0, &question.text, &question.nods,
+          &question.answered, &question.ask_date, &question.status.as_str(), &question.review_reason,
+          &question.reviewed_at, &question.merged_into.as_ref().map(|id| id.0.clone())])
+        .and_then(|_| db.execute(
+            "insert into search_index (kind, id, question, presentation, text)
+             values ('question', ?1, ?1, ?2, ?3)",
+            &[&question.id.0, &question.presentation.0, &question.text]))
+}
+
 impl Capability<FindAll<QuestionsForPresentation>> for SQLite {
     type Data = Vec<Question>;
     type Error = String;
 
-    fn perform(&self, _operation: FindAll<QuestionsForPresentation>) -> Result<Self::Data, Self::Error> {
-        Ok(vec![])
+    fn perform(&self, operation: FindAll<QuestionsForPresentation>) -> Result<Self::Data, Self::Error> {
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        let mut statement = db.prepare(
+            &format!("select {} from questions where presentation = ?1 order by ask_date", QUESTION_COLUMNS))
+            .map_err(|err| err.to_string())?;
+        let questions = statement
+            .query_map(&[&(operation.0).presentation_id.0], |row| question_from_row(row, 0))
+            .map_err(|err| err.to_string())?
+            .collect::<Result<Vec<Question>, _>>()
+            .map_err(|err| err.to_string());
+        questions
+    }
+}
+
+impl Capability<FindAll<QuestionsPage>> for SQLite {
+    type Data = Vec<Question>;
+    type Error = String;
+
+    fn perform(&self, operation: FindAll<QuestionsPage>) -> Result<Self::Data, Self::Error> {
+        let page = operation.0;
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        let mut statement = db.prepare(
+            &format!("select {} from questions where presentation = ?1 order by ask_date, id limit ?2 offset ?3",
+                     QUESTION_COLUMNS))
+            .map_err(|err| err.to_string())?;
+        let questions = statement
+            .query_map(&[&page.presentation_id.0, &page.limit, &page.offset], |row| question_from_row(row, 0))
+            .map_err(|err| err.to_string())?
+            .collect::<Result<Vec<Question>, _>>()
+            .map_err(|err| err.to_string());
+        questions
+    }
+}
+
+/// The columns of the `questions` table that `question_from_row` reads, in order.
+const QUESTION_COLUMNS: &'static str =
+    "id, presentation, text, nods, answered, ask_date, status, review_reason, reviewed_at, merged_into";
+
+/// Read a `Question` out of a row containing its `QUESTION_COLUMNS`, starting at column `first`.
+fn question_from_row(row: &::sqlite::Row, first: i32) -> Question {
+    let status: String = row.get(first + 6);
+    Question {
+        id: Id(row.get(first)),
+        presentation: Id(row.get(first + 1)),
+        text: row.get(first + 2),
+        nods: row.get(first + 3),
+        answered: row.get(first + 4),
+        ask_date: row.get(first + 5),
+        status: QuestionStatus::from_str(&status).unwrap_or(QuestionStatus::Pending),
+        review_reason: row.get(first + 7),
+        reviewed_at: row.get(first + 8),
+        merged_into: row.get::<_, Option<String>>(first + 9).map(Id),
+    }
+}
+
+impl Capability<FindAll<TopQuestions>> for SQLite {
+    type Data = Vec<Question>;
+    type Error = String;
+
+    fn perform(&self, operation: FindAll<TopQuestions>) -> Result<Self::Data, Self::Error> {
+        let top = operation.0;
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        let mut statement = db.prepare(
+            &format!("select {} from questions
+                      where presentation = ?1 and status = ?2 and merged_into is null
+                      order by nods desc, ask_date limit ?3",
+                     QUESTION_COLUMNS))
+            .map_err(|err| err.to_string())?;
+        let questions = statement
+            .query_map(&[&top.presentation_id.0, &QuestionStatus::Approved.as_str(), &top.limit],
+                       |row| question_from_row(row, 0))
+            .map_err(|err| err.to_string())?
+            .collect::<Result<Vec<Question>, _>>()
+            .map_err(|err| err.to_string());
+        questions
+    }
+}
+
+impl Capability<Aggregate<QuestionTotals>> for SQLite {
+    type Data = QuestionCounts;
+    type Error = String;
+
+    fn perform(&self, operation: Aggregate<QuestionTotals>) -> Result<Self::Data, Self::Error> {
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        db.query_row(
+            "select count(*), coalesce(sum(answered), 0) from questions
+             where presentation = ?1 and status = 'approved' and merged_into is null",
+            &[&(operation.0).presentation_id.0],
+            |row| QuestionCounts {
+                questions: row.get(0),
+                answered: row.get(1),
+            })
+            .map_err(|err| err.to_string())
+    }
+}
+
+impl Capability<Aggregate<NodDistribution>> for SQLite {
+    type Data = Vec<NodCount>;
+    type Error = String;
+
+    fn perform(&self, operation: Aggregate<NodDistribution>) -> Result<Self::Data, Self::Error> {
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        let mut statement = db.prepare(
+            "select nods, count(*) from questions
+             where presentation = ?1 and status = 'approved' and merged_into is null
+             group by nods order by nods")
+            .map_err(|err| err.to_string())?;
+        let counts = statement
+            .query_map(&[&(operation.0).presentation_id.0], |row| NodCount {
+                nods: row.get(0),
+                questions: row.get(1),
+            })
+            .map_err(|err| err.to_string())?
+            .collect::<Result<Vec<NodCount>, _>>()
+            .map_err(|err| err.to_string());
+        counts
+    }
+}
+
+impl Capability<Aggregate<QuestionsOverTime>> for SQLite {
+    type Data = Vec<TimeBucket>;
+    type Error = String;
+
+    /// Spans of time in which no questions were asked are left out.
+    fn perform(&self, operation: Aggregate<QuestionsOverTime>) -> Result<Self::Data, Self::Error> {
+        let over_time = operation.0;
+        let bucket_seconds = over_time.bucket_minutes.max(1) as i64 * 60;
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        let mut statement = db.prepare(
+            "select cast(strftime('%s', ask_date) as integer) / ?2 * ?2 as bucket, count(*) from questions
+             where presentation = ?1 and status = 'approved' and merged_into is null
+             group by bucket order by bucket")
+            .map_err(|err| err.to_string())?;
+        let buckets = statement
+            .query_map(&[&over_time.presentation_id.0, &bucket_seconds], |row| TimeBucket {
+                start: Utc.timestamp(row.get(0), 0),
+                questions: row.get(1),
+            })
+            .map_err(|err| err.to_string())?
+            .collect::<Result<Vec<TimeBucket>, _>>()
+            .map_err(|err| err.to_string());
+        buckets
+    }
+}
+
+impl Capability<Aggregate<TimeToAnswer>> for SQLite {
+    type Data = Option<f64>;
+    type Error = String;
+
+    /// Questions that have not been answered are left out, so there is no median until at least
+    /// one question has been.
+    fn perform(&self, operation: Aggregate<TimeToAnswer>) -> Result<Self::Data, Self::Error> {
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        db.query_row(
+            "with delays as (
+                 select (julianday(min(answers.written_date)) - julianday(questions.ask_date)) * 86400.0 as delay
+                 from questions join answers on answers.question = questions.id
+                 where questions.presentation = ?1 and questions.status = 'approved'
+                     and questions.merged_into is null
+                 group by questions.id
+             )
+             select avg(delay) from (
+                 select delay from delays order by delay
+                 limit 2 - (select count(*) from delays) % 2
+                 offset ((select count(*) from delays) - 1) / 2
+             )",
+            &[&(operation.0).presentation_id.0],
+            |row| row.get(0))
+            .map_err(|err| err.to_string())
+    }
+}
+
+impl Capability<CreateTable<Presenter>> for SQLite {
+    type Data = ();
+    type Error = String;
+
+    fn perform(&self, _operation: CreateTable<Presenter>) -> Result<Self::Data, Self::Error> {
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        db.execute(
+            "create table if not exists presenters (
+                email_address   text primary key,
+                password_hash   text not null,
+                join_date       text not null,
+                email_verified  integer not null,
+                totp_secret     text,
+                totp_enabled    integer not null,
+                totp_last_step  integer,
+                recovery_codes  text not null
+            )",
+            &[])
+            .and_then(|_| add_missing_columns(&db, "presenters", &[("totp_last_step", "integer")]))
+            .map_err(|err| err.to_string())
     }
 }
 
@@ -81,10 +671,20 @@ impl Capability<Save<Presenter>> for SQLite {
     type Data = Presenter;
     type Error = String;
 
+    /// Saving fails if a presenter with the same email address already exists.
     fn perform(&self, operation: Save<Presenter>) -> Result<Self::Data, Self::Error> {
-        let mut presenter = operation.0;
-        presenter.email_address = Id("saved".to_string());
-        Ok(presenter)
+        let presenter = operation.0;
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        db.execute(
+            "insert into presenters
+                (email_address, password_hash, join_date, email_verified, totp_secret, totp_enabled,
+                 totp_last_step, recovery_codes)
+             values (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            &[&presenter.email_address.0, &presenter.password_hash, &presenter.join_date,
+              &presenter.email_verified, &presenter.totp_secret, &presenter.totp_enabled,
+              &presenter.totp_last_step, &recovery_codes_to_json(&presenter.recovery_codes)])
+            .map(|_| presenter)
+            .map_err(|err| err.to_string())
     }
 }
 
@@ -93,7 +693,163 @@ impl Capability<Search<Presenter>> for SQLite {
     type Error = String;
 
     fn perform(&self, operation: Search<Presenter>) -> Result<Self::Data, Self::Error> {
-        Ok(operation.0)
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        db.query_row(
+            "select email_address, password_hash, join_date, email_verified, totp_secret, totp_enabled,
+                    totp_last_step, recovery_codes
+             from presenters where email_address = ?1",
+            &[&(operation.0).email_address.0],
+            |row| {
+                let recovery_codes: String = row.get(7);
+                Presenter {
+                    email_address: Id(row.get(0)),
+                    password_hash: row.get(1),
+                    join_date: row.get(2),
+                    email_verified: row.get(3),
+                    totp_secret: row.get(4),
+                    totp_enabled: row.get(5),
+                    totp_last_step: row.get(6),
+                    recovery_codes: serde_json::from_str(&recovery_codes).unwrap_or(vec![]),
+                }
+            })
+            .map_err(|_| "No such presenter.".to_string())
+    }
+}
+
+/// Encode the digests of a presenter's unused recovery codes for storage.
+fn recovery_codes_to_json(recovery_codes: &[String]) -> String {
+    serde_json::to_string(recovery_codes).unwrap_or("[]".to_string())
+}
+
+impl Capability<CreateTable<Presentation>> for SQLite {
+    type Data = ();
+    type Error = String;
+
+    fn perform(&self, _operation: CreateTable<Presentation>) -> Result<Self::Data, Self::Error> {
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        db.execute_batch(
+            "create table if not exists presentations (
+                id                      text primary key,
+                creator                 text not null,
+                title                   text not null,
+                is_open_to_questions    integer not null,
+                creation_date           text not null,
+                join_code               text,
+                passcode_hash           text,
+                ask_capacity            integer not null,
+                ask_per_minute          integer not null,
+                nod_capacity            integer not null,
+                nod_per_minute          integer not null,
+                filters                 text not null,
+                requires_approval       integer not null,
+                flag_threshold          integer not null,
+                description             text not null,
+                scheduled_start         text,
+                scheduled_end           text,
+                language                text,
+                tags                    text not null,
+                external_link           text,
+                opens_at                text,
+                closes_at               text,
+                organization            text,
+                retention_days          integer
+            );")
+            .and_then(|_| add_missing_columns(&db, "presentations", &[
+                ("join_code",         "text"),
+                ("passcode_hash",     "text"),
+                ("ask_capacity",      "integer not null default 5"),
+                ("ask_per_minute",    "integer not null default 2"),
+                ("nod_capacity",      "integer not null default 30"),
+                ("nod_per_minute",    "integer not null default 30"),
+                ("filters",           "text not null default '[]'"),
+                ("requires_approval", "integer not null default 0"),
+                ("flag_threshold",    "integer not null default 3"),
+                ("description",       "text not null default ''"),
+                ("scheduled_start",   "text"),
+                ("scheduled_end",     "text"),
+                ("language",          "text"),
+                ("tags",              "text not null default '[]'"),
+                ("external_link",     "text"),
+                ("opens_at",          "text"),
+                ("closes_at",         "text"),
+                ("organization",      "text"),
+                ("retention_days",    "integer"),
+            ]))
+            .and_then(|_| db.execute_batch(
+                "create unique index if not exists presentations_join_code
+                    on presentations (join_code) where join_code is not null;"))
+            .map_err(|err| err.to_string())
+    }
+}
+
+impl Capability<Save<Presentation>> for SQLite {
+    type Data = Presentation;
+    type Error = String;
+
+    /// If the presentation's join code is already in use by another open presentation, it is
+    /// given a new one.
+    fn perform(&self, operation: Save<Presentation>) -> Result<Self::Data, Self::Error> {
+        let mut presentation = operation.0;
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        insert_presentation(&db, &mut presentation).map(|_| presentation)
+    }
+}
+
+/// Insert a presentation, giving it a new join code if its own is already in use.
+fn insert_presentation(db: &Connection, presentation: &mut Presentation) -> Result<c_int, String> {
+    retry_on_join_code_conflict(presentation, |presentation| db.execute(
+        "insert into presentations
+            (id, creator, title, is_open_to_questions, creation_date, join_code, passcode_hash,
+             ask_capacity, ask_per_minute, nod_capacity, nod_per_minute, filters, requires_approval,
+             flag_threshold, description, scheduled_start, scheduled_end, language, tags, external_link,
+             opens_at, closes_at, organization, retention_days)
+         values (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20,
+                 ?21, ?22, ?23, ?24)",
+        &[&presentation.id.0, &presentation.creator.0, &presentation.title,
+          &presentation.is_open_to_questions, &presentation.creation_date,
+          &presentation.join_code.as_ref().map(|code| code.0.clone()), &presentation.passcode_hash,
+          &presentation.rate_limits.ask.capacity, &presentation.rate_limits.ask.per_minute,
+          &presentation.rate_limits.nod.capacity, &presentation.rate_limits.nod.per_minute,
+          &filters_to_json(&presentation.filters), &presentation.requires_approval,
+          &presentation.flag_threshold, &presentation.description, &presentation.scheduled_start,
+          &presentation.scheduled_end, &presentation.language, &tags_to_json(&presentation.tags),
+          &presentation.external_link, &presentation.opens_at, &presentation.closes_at,
+          &presentation.organization.as_ref().map(|organization| organization.0.clone()),
+          &presentation.retention_days]))
+}
+
+impl Capability<Update<Presentation>> for SQLite {
+    type Data = Presentation;
+    type Error = String;
+
+    /// Closed presentations never keep a join code. If an open presentation's join code is already
+    /// in use by another, it is given a new one.
+    fn perform(&self, operation: Update<Presentation>) -> Result<Self::Data, Self::Error> {
+        let mut presentation = operation.0;
+        if !presentation.is_open_to_questions {
+            presentation.join_code = None;
+        }
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        retry_on_join_code_conflict(&mut presentation, |presentation| db.execute(
+            "update presentations
+             set title = ?1, is_open_to_questions = ?2, join_code = ?3, passcode_hash = ?4,
+                 ask_capacity = ?5, ask_per_minute = ?6, nod_capacity = ?7, nod_per_minute = ?8,
+                 filters = ?9, requires_approval = ?10, flag_threshold = ?11, description = ?12,
+                 scheduled_start = ?13, scheduled_end = ?14, language = ?15, tags = ?16, external_link = ?17,
+                 opens_at = ?18, closes_at = ?19, organization = ?20, retention_days = ?21
+             where id = ?22",
+            &[&presentation.title, &presentation.is_open_to_questions,
+              &presentation.join_code.as_ref().map(|code| code.0.clone()), &presentation.passcode_hash,
+              &presentation.rate_limits.ask.capacity, &presentation.rate_limits.ask.per_minute,
+              &presentation.rate_limits.nod.capacity, &presentation.rate_limits.nod.per_minute,
+              &filters_to_json(&presentation.filters), &presentation.requires_approval,
+              &presentation.flag_threshold, &presentation.description, &presentation.scheduled_start,
+              &presentation.scheduled_end, &presentation.language, &tags_to_json(&presentation.tags),
+              &presentation.external_link, &presentation.opens_at, &presentation.closes_at,
+              &presentation.organization.as_ref().map(|organization| organization.0.clone()),
+              &presentation.retention_days, &presentation.id.0]))
+            .and_then(|updated| if updated == 1 { Ok(()) } else { Err("No such presentation.".to_string()) })
+            .map(|_| presentation)
     }
 }
 
@@ -102,7 +858,161 @@ impl Capability<Search<Presentation>> for SQLite {
     type Error = String;
 
     fn perform(&self, operation: Search<Presentation>) -> Result<Self::Data, Self::Error> {
-        Ok(operation.0)
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        db.query_row(
+            &format!("select {} from presentations where id = ?1", PRESENTATION_COLUMNS),
+            &[&(operation.0).id.0],
+            |row| presentation_from_row(row, 0))
+            .map_err(|err| err.to_string())
+    }
+}
+
+impl Capability<FindAll<DueSchedules>> for SQLite {
+    type Data = Vec<Presentation>;
+    type Error = String;
+
+    fn perform(&self, operation: FindAll<DueSchedules>) -> Result<Self::Data, Self::Error> {
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        let mut statement = db.prepare(
+            &format!("select {} from presentations where opens_at <= ?1 or closes_at <= ?1", PRESENTATION_COLUMNS))
+            .map_err(|err| err.to_string())?;
+        let presentations = statement
+            .query_map(&[&(operation.0).now], |row| presentation_from_row(row, 0))
+            .map_err(|err| err.to_string())?
+            .collect::<Result<Vec<Presentation>, _>>()
+            .map_err(|err| err.to_string());
+        presentations
+    }
+}
+
+impl Capability<Search<JoinCode>> for SQLite {
+    type Data = Presentation;
+    type Error = String;
+
+    /// Find the open presentation that a join code belongs to.
+    fn perform(&self, operation: Search<JoinCode>) -> Result<Self::Data, Self::Error> {
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        db.query_row(
+            &format!("select {} from presentations where join_code = ?1 and is_open_to_questions = 1",
+                     PRESENTATION_COLUMNS),
+            &[&(operation.0).0],
+            |row| presentation_from_row(row, 0))
+            .map_err(|err| err.to_string())
+    }
+}
+
+/// The number of times to try a fresh join code before giving up on saving a presentation.
+const JOIN_CODE_ATTEMPTS: usize = 10;
+
+/// Run a statement that writes a presentation, generating a new join code for it each time the
+/// statement fails because its current join code is taken.
+fn retry_on_join_code_conflict<F>(presentation: &mut Presentation, mut write: F) -> Result<c_int, String>
+    where F: FnMut(&Presentation) -> SQLiteResult<c_int>
+{
+    for _ in 0..JOIN_CODE_ATTEMPTS {
+        match write(presentation) {
+            Err(SQLiteError::SqliteFailure(ref err, _))
+                if err.code == ErrorCode::ConstraintViolation && presentation.join_code.is_some() => {
+                presentation.join_code = Some(JoinCode::generate());
+            },
+            result => return result.map_err(|err| err.to_string()),
+        }
+    }
+    Err("Could not find an unused join code.".to_string())
+}
+
+/// The columns of the `presentations` table, in the order `presentation_from_row` expects them.
+const PRESENTATION_COLUMNS: &'static str =
+    "id, creator, title, is_open_to_questions, creation_date, join_code, passcode_hash, \
+     ask_capacity, ask_per_minute, nod_capacity, nod_per_minute, filters, requires_approval, \
+     flag_threshold, description, scheduled_start, scheduled_end, language, tags, external_link, \
+     opens_at, closes_at, organization, retention_days";
+
+/// How many columns `PRESENTATION_COLUMNS` names.
+const PRESENTATION_COLUMN_COUNT: i32 = 24;
+
+/// Joins presentations to the memberships of the presenter given as `?1`.
+const MEMBER_JOIN: &'static str =
+    "left join memberships on memberships.presentation = presentations.id and memberships.member = ?1";
+
+/// Selects the role in a presentation of the presenter given as `?1`, who either created it or
+/// has a membership found by `MEMBER_JOIN`.
+const MEMBER_ROLE: &'static str = "case when creator = ?1 then 'owner' else memberships.role end";
+
+/// Read a `Role` out of the column `column` of a row.
+fn role_from_row(row: &::sqlite::Row, column: i32) -> Role {
+    let role: String = row.get(column);
+    Role::from_str(&role).unwrap_or(Role::Moderator)
+}
+
+/// Read a `Presentation` out of a row containing its `PRESENTATION_COLUMNS`, starting at column
+/// `first`.
+fn presentation_from_row(row: &::sqlite::Row, first: i32) -> Presentation {
+    let join_code: Option<String> = row.get(first + 5);
+    let passcode_hash: Option<String> = row.get(first + 6);
+    let filters: String = row.get(first + 11);
+    let tags: String = row.get(first + 18);
+    let organization: Option<String> = row.get(first + 22);
+    Presentation {
+        id: Id(row.get(first)),
+        creator: Id(row.get(first + 1)),
+        title: row.get(first + 2),
+        is_open_to_questions: row.get(first + 3),
+        creation_date: row.get(first + 4),
+        join_code: join_code.map(JoinCode),
+        is_private: passcode_hash.is_some(),
+        passcode_hash: passcode_hash,
+        rate_limits: RateLimits {
+            ask: Budget::new(row.get(first + 7), row.get(first + 8)),
+            nod: Budget::new(row.get(first + 9), row.get(first + 10)),
+        },
+        filters: serde_json::from_str(&filters).unwrap_or(vec![]),
+        requires_approval: row.get(first + 12),
+        flag_threshold: row.get(first + 13),
+        description: row.get(first + 14),
+        scheduled_start: row.get(first + 15),
+        scheduled_end: row.get(first + 16),
+        language: row.get(first + 17),
+        tags: serde_json::from_str(&tags).unwrap_or(vec![]),
+        external_link: row.get(first + 19),
+        opens_at: row.get(first + 20),
+        closes_at: row.get(first + 21),
+        organization: organization.map(Id),
+        retention_days: row.get(first + 23),
+    }
+}
+
+/// Encode the filters a presenter chose for a presentation for storage.
+fn filters_to_json(filters: &[FilterSetting]) -> String {
+    serde_json::to_string(filters).unwrap_or("[]".to_string())
+}
+
+/// Encode a presentation's tags for storage. Presentations with a tag are found by searching the
+/// encoded tags for the tag in quotes, so tags must never contain quotes themselves.
+fn tags_to_json(tags: &[String]) -> String {
+    serde_json::to_string(tags).unwrap_or("[]".to_string())
+}
+
+/// Encode a tag the way it appears among the encoded tags of the presentations that have it.
+fn tag_pattern(tag: &Option<String>) -> Option<String> {
+    tag.as_ref().map(|tag| serde_json::to_string(&tag.trim().to_lowercase()).unwrap_or(String::new()))
+}
+
+impl Capability<CreateTable<Session>> for SQLite {
+    type Data = ();
+    type Error = String;
+
+    fn perform(&self, _operation: CreateTable<Session>) -> Result<Self::Data, Self::Error> {
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        db.execute(
+            "create table if not exists sessions (
+                token_digest    blob primary key,
+                owner           text not null,
+                created_at      text not null
+            )",
+            &[])
+            .map(|_| ())
+            .map_err(|err| err.to_string())
     }
 }
 
@@ -111,16 +1021,110 @@ impl Capability<Save<Session>> for SQLite {
     type Error = String;
 
     fn perform(&self, operation: Save<Session>) -> Result<Self::Data, Self::Error> {
-        Ok(operation.0)
+        let session = operation.0;
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        db.execute(
+            "insert into sessions (token_digest, owner, created_at) values (?1, ?2, ?3)",
+            &[&session.token_digest(), &session.owner.0, &session.created_at])
+            .map(|_| session)
+            .map_err(|err| err.to_string())
     }
 }
 
 impl Capability<FindAll<PresentationsForPresenter>> for SQLite {
+    type Data = Vec<PresentationWithRole>;
+    type Error = String;
+
+    /// Presentations are found newest first.
+    fn perform(&self, operation: FindAll<PresentationsForPresenter>) -> Result<Self::Data, Self::Error> {
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        let mut statement = db.prepare(
+            &format!("select {}, {} from presentations {}
+                      where (creator = ?1 or memberships.member is not null)
+                          and (?2 is null or instr(tags, ?2) > 0)
+                      order by creation_date desc",
+                     PRESENTATION_COLUMNS, MEMBER_ROLE, MEMBER_JOIN))
+            .map_err(|err| err.to_string())?;
+        let query = operation.0;
+        let presentations = statement
+            .query_map(&[&query.presenter_id.0, &tag_pattern(&query.tag)], |row| PresentationWithRole {
+                presentation: presentation_from_row(row, 0),
+                role: role_from_row(row, PRESENTATION_COLUMN_COUNT),
+            })
+            .map_err(|err| err.to_string())?
+            .collect::<Result<Vec<PresentationWithRole>, _>>()
+            .map_err(|err| err.to_string());
+        presentations
+    }
+}
+
+impl Capability<FindAll<PresentationsForOrganization>> for SQLite {
     type Data = Vec<Presentation>;
     type Error = String;
 
-    fn perform(&self, _operation: FindAll<PresentationsForPresenter>) -> Result<Self::Data, Self::Error> {
-        Ok(vec![])
+    /// Presentations are found newest first.
+    fn perform(&self, operation: FindAll<PresentationsForOrganization>) -> Result<Self::Data, Self::Error> {
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        let mut statement = db.prepare(
+            &format!("select {} from presentations where organization = ?1 order by creation_date desc",
+                     PRESENTATION_COLUMNS))
+            .map_err(|err| err.to_string())?;
+        let presentations = statement
+            .query_map(&[&(operation.0).organization_id.0], |row| presentation_from_row(row, 0))
+            .map_err(|err| err.to_string())?
+            .collect::<Result<Vec<Presentation>, _>>()
+            .map_err(|err| err.to_string());
+        presentations
+    }
+}
+
+impl Capability<Aggregate<PresenterDashboard>> for SQLite {
+    type Data = Vec<PresentationActivity>;
+    type Error = String;
+
+    /// A presentation's last activity is the latest of when it was created, when a question was
+    /// last asked during it, and when one of those questions was last answered.
+    fn perform(&self, operation: Aggregate<PresenterDashboard>) -> Result<Self::Data, Self::Error> {
+        let dashboard = operation.0;
+        let order = match dashboard.order {
+            DashboardOrder::LastActivity => "last_activity desc",
+            DashboardOrder::Created      => "creation_date desc",
+        };
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        let mut statement = db.prepare(&format!(
+            "select {}, {}, questions, unanswered, max(creation_date, coalesce(last_asked, creation_date),
+                                                       coalesce(last_answered, creation_date)) as last_activity
+             from presentations {} left join (
+                 select presentation,
+                        count(*) as questions,
+                        sum(case when answered = 0 and status != ?5 then 1 else 0 end) as unanswered,
+                        max(ask_date) as last_asked
+                 from questions where merged_into is null group by presentation
+             ) as asked on asked.presentation = presentations.id
+             left join (
+                 select questions.presentation, max(answers.written_date) as last_answered
+                 from answers join questions on questions.id = answers.question group by questions.presentation
+             ) as answered on answered.presentation = presentations.id
+             where (creator = ?1 or memberships.member is not null)
+                 and (?2 is null or creation_date >= ?2) and (?3 is null or creation_date < ?3)
+                 and (?4 is null or is_open_to_questions = ?4) and (?6 is null or instr(tags, ?6) > 0)
+             order by {}",
+            PRESENTATION_COLUMNS, MEMBER_ROLE, MEMBER_JOIN, order))
+            .map_err(|err| err.to_string())?;
+        let activity = statement
+            .query_map(&[&dashboard.presenter_id.0, &dashboard.created_after, &dashboard.created_before,
+                         &dashboard.open, &QuestionStatus::Rejected.as_str(), &tag_pattern(&dashboard.tag)],
+                       |row| PresentationActivity {
+                           presentation: presentation_from_row(row, 0),
+                           role: role_from_row(row, PRESENTATION_COLUMN_COUNT),
+                           questions: row.get::<_, Option<u32>>(PRESENTATION_COLUMN_COUNT + 1).unwrap_or(0),
+                           unanswered: row.get::<_, Option<u32>>(PRESENTATION_COLUMN_COUNT + 2).unwrap_or(0),
+                           last_activity: row.get(PRESENTATION_COLUMN_COUNT + 3),
+                       })
+            .map_err(|err| err.to_string())?
+            .collect::<Result<Vec<PresentationActivity>, _>>()
+            .map_err(|err| err.to_string());
+        activity
     }
 }
 
@@ -129,7 +1133,52 @@ impl Capability<Search<Session>> for SQLite {
     type Error = String;
 
     fn perform(&self, operation: Search<Session>) -> Result<Self::Data, Self::Error> {
-        Ok(operation.0)
+        let digest = operation.0.token_digest();
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        let (stored_digest, owner, created_at): (Vec<u8>, String, _) = db.query_row(
+            "select token_digest, owner, created_at from sessions where token_digest = ?1",
+            &[&digest],
+            |row| (row.get(0), row.get(1), row.get(2)))
+            .map_err(|_| "No such session.".to_string())?;
+        verify_slices_are_equal(&stored_digest, &digest)
+            .map_err(|_| "No such session.".to_string())?;
+        Ok(Session {
+            token: operation.0.token,
+            owner: Id(owner),
+            created_at: created_at,
+        })
+    }
+}
+
+impl Capability<Delete<SessionsForPresenter>> for SQLite {
+    type Data = usize;
+    type Error = String;
+
+    /// Every session the presenter has is ended, and the number of sessions ended is returned.
+    fn perform(&self, operation: Delete<SessionsForPresenter>) -> Result<Self::Data, Self::Error> {
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        db.execute("delete from sessions where owner = ?1", &[&(operation.0).presenter_id.0])
+            .map(|deleted| deleted as usize)
+            .map_err(|err| err.to_string())
+    }
+}
+
+impl Capability<CreateTable<Answer>> for SQLite {
+    type Data = ();
+    type Error = String;
+
+    fn perform(&self, _operation: CreateTable<Answer>) -> Result<Self::Data, Self::Error> {
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        db.execute_batch(
+            "create table if not exists answers (
+                id              text primary key,
+                author          text not null,
+                question        text not null,
+                written_date    text not null,
+                text            text not null
+            );
+            create index if not exists answers_question on answers (question);")
+            .map_err(|err| err.to_string())
     }
 }
 
@@ -137,7 +1186,1372 @@ impl Capability<Save<Answer>> for SQLite {
     type Data = Answer;
     type Error = String;
 
+    /// Saving an answer also marks the question it answers as answered.
     fn perform(&self, operation: Save<Answer>) -> Result<Self::Data, Self::Error> {
-        Ok(operation.0)
+        let answer = operation.0;
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        insert_answer(&db, &answer)
+            .map(|_| answer)
+            .map_err(|err| err.to_string())
+    }
+}
+
+/// Insert an answer, mark the question it answers as answered, and index its text for searches.
+fn insert_answer(db: &Connection, answer: &Answer) -> SQLiteResult<c_int> {
+    db.execute(
+        "insert into answers (id, author, question, written_date, text) values (?1, ?2, ?3, ?4, ?5)",
+        &[&answer.id.0, &answer.author.0, &answer.question.0, &answer.written_date, &answer.text])
+        .and_then(|_| db.execute("update questions set answered = 1 where id = ?1", &[&answer.question.0]))
+        .and_then(|_| db.execute(
+            "insert into search_index (kind, id, question, presentation, text)
+             select 'answer', ?1, id, presentation, ?2 from questions where id = ?3",
+            &[&answer.id.0, &answer.text, &answer.question.0]))
+}
+
+impl Capability<FindAll<AnswersForQuestion>> for SQLite {
+    type Data = Vec<Answer>;
+    type Error = String;
+
+    fn perform(&self, operation: FindAll<AnswersForQuestion>) -> Result<Self::Data, Self::Error> {
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        let mut statement = db.prepare(
+            "select id, author, question, written_date, text from answers where question = ?1 order by written_date")
+            .map_err(|err| err.to_string())?;
+        let answers = statement
+            .query_map(&[&(operation.0).question_id.0], |row| Answer {
+                id: Id(row.get(0)),
+                author: Id(row.get(1)),
+                question: Id(row.get(2)),
+                written_date: row.get(3),
+                text: row.get(4),
+            })
+            .map_err(|err| err.to_string())?
+            .collect::<Result<Vec<Answer>, _>>()
+            .map_err(|err| err.to_string());
+        answers
+    }
+}
+
+impl Capability<Update<Answer>> for SQLite {
+    type Data = ();
+    type Error = String;
+
+    fn perform(&self, operation: Update<Answer>) -> Result<Self::Data, Self::Error> {
+        let answer = operation.0;
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        let updated = db.execute("update answers set text = ?1 where id = ?2", &[&answer.text, &answer.id.0])
+            .and_then(|updated| db.execute(
+                "update search_index set text = ?1 where kind = 'answer' and id = ?2",
+                &[&answer.text, &answer.id.0]).map(|_| updated))
+            .map_err(|err| err.to_string())?;
+        if updated == 1 {
+            Ok(())
+        } else {
+            Err("No such answer.".to_string())
+        }
+    }
+}
+
+impl Capability<Delete<Answer>> for SQLite {
+    type Data = ();
+    type Error = String;
+
+    fn perform(&self, operation: Delete<Answer>) -> Result<Self::Data, Self::Error> {
+        let id = &(operation.0).id.0;
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        let deleted = db.execute("delete from answers where id = ?1", &[id])
+            .and_then(|deleted| db.execute(
+                "delete from search_index where kind = 'answer' and id = ?1", &[id]).map(|_| deleted))
+            .map_err(|err| err.to_string())?;
+        if deleted == 1 {
+            Ok(())
+        } else {
+            Err("No such answer.".to_string())
+        }
+    }
+}
+
+impl Capability<CreateTable<SearchResult>> for SQLite {
+    type Data = ();
+    type Error = String;
+
+    /// The index is filled from the questions and answers already stored when it is first created.
+    fn perform(&self, _operation: CreateTable<SearchResult>) -> Result<Self::Data, Self::Error> {
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        let exists: i64 = db.query_row(
+            "select count(*) from sqlite_master where type = 'table' and name = 'search_index'",
+            &[],
+            |row| row.get(0))
+            .map_err(|err| err.to_string())?;
+        if exists > 0 {
+            return Ok(());
+        }
+        db.execute_batch(
+            "create virtual table search_index using fts5(
+                kind unindexed,
+                id unindexed,
+                question unindexed,
+                presentation unindexed,
+                text
+            );
+            insert into search_index (kind, id, question, presentation, text)
+                select 'question', id, id, presentation, text from questions;
+            insert into search_index (kind, id, question, presentation, text)
+                select 'answer', answers.id, answers.question, questions.presentation, answers.text
+                from answers join questions on questions.id = answers.question;")
+            .map_err(|err| err.to_string())
+    }
+}
+
+impl Capability<FindAll<TextSearch>> for SQLite {
+    type Data = Vec<SearchResult>;
+    type Error = String;
+
+    /// Results are ordered from best to worst match. Only questions the audience can see, and their
+    /// answers, are found unless `include_hidden` is set.
+    fn perform(&self, operation: FindAll<TextSearch>) -> Result<Self::Data, Self::Error> {
+        let search = operation.0;
+        let query = SearchResult::query(&search.text).ok_or("Enter something to search for.".to_string())?;
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        let mut statement = db.prepare(
+            "select search_index.kind, search_index.id, search_index.question,
+                    snippet(search_index, 4, '<mark>', '</mark>', '…', 16), search_index.rank
+             from search_index join questions on questions.id = search_index.question
+             where search_index match ?1 and search_index.presentation = ?2
+               and (?3 or (questions.status = 'approved' and questions.merged_into is null))
+             order by search_index.rank
+             limit ?4")
+            .map_err(|err| err.to_string())?;
+        let results = statement
+            .query_map(&[&query, &search.presentation_id.0, &search.include_hidden, &search.limit], |row| {
+                SearchResult {
+                    kind: row.get(0),
+                    id: Id(row.get(1)),
+                    question: Id(row.get(2)),
+                    snippet: row.get(3),
+                    rank: row.get(4),
+                }
+            })
+            .map_err(|err| err.to_string())?
+            .collect::<Result<Vec<SearchResult>, _>>()
+            .map_err(|err| err.to_string());
+        results
+    }
+}
+
+impl Capability<Update<Presenter>> for SQLite {
+    type Data = ();
+    type Error = String;
+
+    fn perform(&self, operation: Update<Presenter>) -> Result<Self::Data, Self::Error> {
+        let presenter = operation.0;
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        let updated = db.execute(
+            "update presenters
+             set password_hash = ?1, email_verified = ?2, totp_secret = ?3, totp_enabled = ?4,
+                 totp_last_step = ?5, recovery_codes = ?6
+             where email_address = ?7",
+            &[&presenter.password_hash, &presenter.email_verified, &presenter.totp_secret,
+              &presenter.totp_enabled, &presenter.totp_last_step,
+              &recovery_codes_to_json(&presenter.recovery_codes), &presenter.email_address.0])
+            .map_err(|err| err.to_string())?;
+        if updated == 1 {
+            Ok(())
+        } else {
+            Err("No such presenter.".to_string())
+        }
+    }
+}
+
+impl Capability<CreateTable<AccountToken>> for SQLite {
+    type Data = ();
+    type Error = String;
+
+    /// Tokens used to be stored as they were sent rather than as digests. Those can't be converted,
+    /// so they are discarded, and presenters still waiting on one will need to ask for another.
+    fn perform(&self, _operation: CreateTable<AccountToken>) -> Result<Self::Data, Self::Error> {
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        let legacy = table_columns(&db, "account_tokens")
+            .map_err(|err| err.to_string())?
+            .iter()
+            .any(|column| column == "token");
+        if legacy {
+            db.execute("drop table account_tokens", &[]).map_err(|err| err.to_string())?;
+        }
+        db.execute(
+            "create table if not exists account_tokens (
+                token_digest    blob primary key,
+                owner           text not null,
+                purpose         text not null,
+                expires_at      text not null
+            )",
+            &[])
+            .map(|_| ())
+            .map_err(|err| err.to_string())
+    }
+}
+
+impl Capability<Save<AccountToken>> for SQLite {
+    type Data = AccountToken;
+    type Error = String;
+
+    fn perform(&self, operation: Save<AccountToken>) -> Result<Self::Data, Self::Error> {
+        let token = operation.0;
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        db.execute(
+            "insert into account_tokens (token_digest, owner, purpose, expires_at) values (?1, ?2, ?3, ?4)",
+            &[&token.token_digest(), &token.owner.0, &token.purpose.as_str(), &token.expires_at])
+            .map(|_| token)
+            .map_err(|err| err.to_string())
+    }
+}
+
+impl Capability<Search<AccountToken>> for SQLite {
+    type Data = AccountToken;
+    type Error = String;
+
+    fn perform(&self, operation: Search<AccountToken>) -> Result<Self::Data, Self::Error> {
+        let digest = operation.0.token_digest();
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        let (stored_digest, owner, purpose, expires_at): (Vec<u8>, String, String, _) = db.query_row(
+            "select token_digest, owner, purpose, expires_at from account_tokens where token_digest = ?1",
+            &[&digest],
+            |row| (row.get(0), row.get(1), row.get(2), row.get(3)))
+            .map_err(|_| "No such token.".to_string())?;
+        verify_slices_are_equal(&stored_digest, &digest)
+            .map_err(|_| "No such token.".to_string())?;
+        let purpose = TokenPurpose::from_str(&purpose).ok_or("Unknown token purpose.".to_string())?;
+        Ok(AccountToken {
+            token: operation.0.token,
+            owner: Id(owner),
+            purpose: purpose,
+            expires_at: expires_at,
+        })
+    }
+}
+
+impl Capability<Delete<AccountToken>> for SQLite {
+    type Data = ();
+    type Error = String;
+
+    fn perform(&self, operation: Delete<AccountToken>) -> Result<Self::Data, Self::Error> {
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        let deleted = db.execute(
+            "delete from account_tokens where token_digest = ?1",
+            &[&operation.0.token_digest()])
+            .map_err(|err| err.to_string())?;
+        if deleted == 1 {
+            Ok(())
+        } else {
+            Err("No such token.".to_string())
+        }
+    }
+}
+
+impl Capability<CreateTable<ApiToken>> for SQLite {
+    type Data = ();
+    type Error = String;
+
+    fn perform(&self, _operation: CreateTable<ApiToken>) -> Result<Self::Data, Self::Error> {
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        db.execute(
+            "create table if not exists api_tokens (
+                id              text primary key,
+                token_digest    blob unique not null,
+                owner           text not null,
+                name            text not null,
+                scopes          text not null,
+                created_at      text not null,
+                last_used       text,
+                revoked         integer not null default 0
+            )",
+            &[])
+            .map(|_| ())
+            .map_err(|err| err.to_string())
+    }
+}
+
+impl Capability<Save<ApiToken>> for SQLite {
+    type Data = ApiToken;
+    type Error = String;
+
+    fn perform(&self, operation: Save<ApiToken>) -> Result<Self::Data, Self::Error> {
+        let token = operation.0;
+        let scopes = token.scopes.iter().map(Scope::as_str).collect::<Vec<&str>>().join(",");
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        db.execute(
+            "insert into api_tokens (id, token_digest, owner, name, scopes, created_at, last_used, revoked)
+             values (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            &[&token.id.0, &token.token_digest(), &token.owner.0, &token.name, &scopes,
+              &token.created_at, &token.last_used, &token.revoked])
+            .map(|_| token)
+            .map_err(|err| err.to_string())
+    }
+}
+
+impl Capability<Search<ApiToken>> for SQLite {
+    type Data = ApiToken;
+    type Error = String;
+
+    fn perform(&self, operation: Search<ApiToken>) -> Result<Self::Data, Self::Error> {
+        let digest = operation.0.token_digest();
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        let (stored_digest, mut token) = db.query_row(
+            "select token_digest, id, owner, name, scopes, created_at, last_used, revoked
+             from api_tokens where token_digest = ?1",
+            &[&digest],
+            |row| (row.get::<_, Vec<u8>>(0), api_token_from_row(row, 1)))
+            .map_err(|_| "No such token.".to_string())?;
+        verify_slices_are_equal(&stored_digest, &digest)
+            .map_err(|_| "No such token.".to_string())?;
+        token.token = operation.0.token;
+        Ok(token)
+    }
+}
+
+impl Capability<Update<ApiToken>> for SQLite {
+    type Data = ();
+    type Error = String;
+
+    fn perform(&self, operation: Update<ApiToken>) -> Result<Self::Data, Self::Error> {
+        let token = operation.0;
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        db.execute(
+            "update api_tokens set name = ?1, last_used = ?2, revoked = ?3 where id = ?4",
+            &[&token.name, &token.last_used, &token.revoked, &token.id.0])
+            .map(|_| ())
+            .map_err(|err| err.to_string())
+    }
+}
+
+impl Capability<Update<ApiTokenUse>> for SQLite {
+    type Data = usize;
+    type Error = String;
+
+    /// Produces the number of tokens updated, which is zero if the token has been revoked.
+    fn perform(&self, operation: Update<ApiTokenUse>) -> Result<Self::Data, Self::Error> {
+        let token_use = operation.0;
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        db.execute(
+            "update api_tokens set last_used = ?1 where id = ?2 and revoked = 0",
+            &[&token_use.used_at, &token_use.token_id.0])
+            .map(|updated| updated as usize)
+            .map_err(|err| err.to_string())
+    }
+}
+
+impl Capability<FindAll<TokensForPresenter>> for SQLite {
+    type Data = Vec<ApiToken>;
+    type Error = String;
+
+    fn perform(&self, operation: FindAll<TokensForPresenter>) -> Result<Self::Data, Self::Error> {
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        let mut statement = db.prepare(
+            "select id, owner, name, scopes, created_at, last_used, revoked
+             from api_tokens where owner = ?1 order by created_at")
+            .map_err(|err| err.to_string())?;
+        let tokens = statement
+            .query_map(&[&(operation.0).presenter_id.0], |row| api_token_from_row(row, 0))
+            .map_err(|err| err.to_string())?
+            .collect::<Result<Vec<ApiToken>, _>>()
+            .map_err(|err| err.to_string());
+        tokens
+    }
+}
+
+/// Read an `ApiToken` out of a row containing its `id`, `owner`, `name`, `scopes`, `created_at`,
+/// `last_used` and `revoked` columns, in that order, starting at column `first`.
+fn api_token_from_row(row: &::sqlite::Row, first: i32) -> ApiToken {
+    let scopes: String = row.get(first + 3);
+    ApiToken {
+        id: Id(row.get(first)),
+        token: Id(String::new()),
+        owner: Id(row.get(first + 1)),
+        name: row.get(first + 2),
+        scopes: scopes.split(',').filter_map(Scope::from_str).collect(),
+        created_at: row.get(first + 4),
+        last_used: row.get(first + 5),
+        revoked: row.get(first + 6),
+    }
+}
+
+impl Capability<CreateTable<OidcLoginAttempt>> for SQLite {
+    type Data = ();
+    type Error = String;
+
+    fn perform(&self, _operation: CreateTable<OidcLoginAttempt>) -> Result<Self::Data, Self::Error> {
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        db.execute(
+            "create table if not exists oidc_login_attempts (
+                state           text primary key,
+                nonce           text not null,
+                code_verifier   text not null,
+                expires_at      text not null
+            )",
+            &[])
+            .map(|_| ())
+            .map_err(|err| err.to_string())
+    }
+}
+
+impl Capability<Save<OidcLoginAttempt>> for SQLite {
+    type Data = OidcLoginAttempt;
+    type Error = String;
+
+    /// Saving an attempt also forgets those that expired, which are left behind whenever a
+    /// presenter never comes back from their identity provider.
+    fn perform(&self, operation: Save<OidcLoginAttempt>) -> Result<Self::Data, Self::Error> {
+        let attempt = operation.0;
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        db.execute("delete from oidc_login_attempts where expires_at <= ?1", &[&Utc::now()])
+            .and_then(|_| db.execute(
+                "insert into oidc_login_attempts (state, nonce, code_verifier, expires_at) values (?1, ?2, ?3, ?4)",
+                &[&attempt.state.0, &attempt.nonce, &attempt.code_verifier, &attempt.expires_at]))
+            .map(|_| attempt)
+            .map_err(|err| err.to_string())
+    }
+}
+
+impl Capability<Search<OidcLoginAttempt>> for SQLite {
+    type Data = OidcLoginAttempt;
+    type Error = String;
+
+    fn perform(&self, operation: Search<OidcLoginAttempt>) -> Result<Self::Data, Self::Error> {
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        db.query_row(
+            "select nonce, code_verifier, expires_at from oidc_login_attempts where state = ?1",
+            &[&(operation.0).state.0],
+            |row| OidcLoginAttempt {
+                state: Id((operation.0).state.0.clone()),
+                nonce: row.get(0),
+                code_verifier: row.get(1),
+                expires_at: row.get(2),
+            })
+            .map_err(|err| err.to_string())
+    }
+}
+
+impl Capability<Delete<OidcLoginAttempt>> for SQLite {
+    type Data = ();
+    type Error = String;
+
+    fn perform(&self, operation: Delete<OidcLoginAttempt>) -> Result<Self::Data, Self::Error> {
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        let deleted = db.execute(
+            "delete from oidc_login_attempts where state = ?1",
+            &[&(operation.0).state.0])
+            .map_err(|err| err.to_string())?;
+        if deleted == 1 {
+            Ok(())
+        } else {
+            Err("No such login attempt.".to_string())
+        }
+    }
+}
+
+impl Capability<CreateTable<ExternalIdentity>> for SQLite {
+    type Data = ();
+    type Error = String;
+
+    fn perform(&self, _operation: CreateTable<ExternalIdentity>) -> Result<Self::Data, Self::Error> {
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        db.execute(
+            "create table if not exists external_identities (
+                issuer      text not null,
+                subject     text not null,
+                presenter   text not null,
+                linked_at   text not null,
+                primary key (issuer, subject)
+            )",
+            &[])
+            .map(|_| ())
+            .map_err(|err| err.to_string())
+    }
+}
+
+impl Capability<Save<ExternalIdentity>> for SQLite {
+    type Data = ExternalIdentity;
+    type Error = String;
+
+    fn perform(&self, operation: Save<ExternalIdentity>) -> Result<Self::Data, Self::Error> {
+        let identity = operation.0;
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        db.execute(
+            "insert into external_identities (issuer, subject, presenter, linked_at) values (?1, ?2, ?3, ?4)",
+            &[&identity.issuer, &identity.subject, &identity.presenter.0, &identity.linked_at])
+            .map(|_| identity)
+            .map_err(|err| err.to_string())
+    }
+}
+
+impl Capability<Search<ExternalIdentity>> for SQLite {
+    type Data = ExternalIdentity;
+    type Error = String;
+
+    fn perform(&self, operation: Search<ExternalIdentity>) -> Result<Self::Data, Self::Error> {
+        let identity = operation.0;
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        let (presenter, linked_at) = db.query_row(
+            "select presenter, linked_at from external_identities where issuer = ?1 and subject = ?2",
+            &[&identity.issuer, &identity.subject],
+            |row| (row.get(0), row.get(1)))
+            .map_err(|err| err.to_string())?;
+        Ok(ExternalIdentity {
+            issuer: identity.issuer,
+            subject: identity.subject,
+            presenter: Id(presenter),
+            linked_at: linked_at,
+        })
+    }
+}
+
+impl Capability<Update<MergePlatformPresenter>> for SQLite {
+    type Data = ();
+    type Error = String;
+
+    /// The platform account signs in as `to` from then on, and `to` takes over the presentations
+    /// `from` created and its memberships, except in presentations `to` already takes part in.
+    fn perform(&self, operation: Update<MergePlatformPresenter>) -> Result<Self::Data, Self::Error> {
+        let merge = operation.0;
+        let mut db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        let transaction = db.transaction().map_err(|err| err.to_string())?;
+        let relinked = transaction.execute(
+            "update external_identities set presenter = ?1 where presenter = ?2",
+            &[&merge.to.0, &merge.from.0])
+            .map_err(|err| err.to_string())?;
+        if relinked == 0 {
+            return Err("No such platform account.".to_string());
+        }
+        transaction.execute(
+            "update presentations set creator = ?1 where creator = ?2",
+            &[&merge.to.0, &merge.from.0])
+            .and_then(|_| transaction.execute(
+                "update or ignore memberships set member = ?1 where member = ?2",
+                &[&merge.to.0, &merge.from.0]))
+            .and_then(|_| transaction.execute("delete from memberships where member = ?1", &[&merge.from.0]))
+            .and_then(|_| transaction.execute("delete from sessions where owner = ?1", &[&merge.from.0]))
+            .and_then(|_| transaction.commit())
+            .map_err(|err| err.to_string())
+    }
+}
+
+impl Capability<CreateTable<Audience>> for SQLite {
+    type Data = ();
+    type Error = String;
+
+    fn perform(&self, _operation: CreateTable<Audience>) -> Result<Self::Data, Self::Error> {
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        db.execute(
+            "create table if not exists audiences (
+                id                  text primary key,
+                submission_token    text not null
+            )",
+            &[])
+            .map(|_| ())
+            .map_err(|err| err.to_string())
+    }
+}
+
+impl Capability<Save<Audience>> for SQLite {
+    type Data = Audience;
+    type Error = String;
+
+    /// Saving an audience member who already exists issues them a new submission token.
+    fn perform(&self, operation: Save<Audience>) -> Result<Self::Data, Self::Error> {
+        let audience = operation.0;
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        db.execute(
+            "insert or replace into audiences (id, submission_token) values (?1, ?2)",
+            &[&audience.id.0, &audience.submission_token])
+            .map(|_| audience)
+            .map_err(|err| err.to_string())
+    }
+}
+
+impl Capability<Search<Audience>> for SQLite {
+    type Data = Audience;
+    type Error = String;
+
+    fn perform(&self, operation: Search<Audience>) -> Result<Self::Data, Self::Error> {
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        db.query_row(
+            "select submission_token from audiences where id = ?1",
+            &[&(operation.0).id.0],
+            |row| row.get(0))
+            .map(|submission_token| Audience {
+                id: operation.0.id,
+                submission_token: submission_token,
+            })
+            .map_err(|_| "No such audience member.".to_string())
+    }
+}
+
+impl Capability<CreateTable<LtiResourceLink>> for SQLite {
+    type Data = ();
+    type Error = String;
+
+    fn perform(&self, _operation: CreateTable<LtiResourceLink>) -> Result<Self::Data, Self::Error> {
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        db.execute(
+            "create table if not exists lti_resource_links (
+                issuer              text not null,
+                deployment_id       text not null,
+                resource_link_id    text not null,
+                presentation        text not null,
+                created_at          text not null,
+                primary key (issuer, deployment_id, resource_link_id)
+            )",
+            &[])
+            .map(|_| ())
+            .map_err(|err| err.to_string())
+    }
+}
+
+impl Capability<Save<LtiResourceLink>> for SQLite {
+    type Data = LtiResourceLink;
+    type Error = String;
+
+    fn perform(&self, operation: Save<LtiResourceLink>) -> Result<Self::Data, Self::Error> {
+        let link = operation.0;
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        db.execute(
+            "insert into lti_resource_links (issuer, deployment_id, resource_link_id, presentation, created_at)
+             values (?1, ?2, ?3, ?4, ?5)",
+            &[&link.issuer, &link.deployment_id, &link.resource_link_id, &link.presentation.0, &link.created_at])
+            .map(|_| link)
+            .map_err(|err| err.to_string())
+    }
+}
+
+impl Capability<Search<LtiResourceLink>> for SQLite {
+    type Data = LtiResourceLink;
+    type Error = String;
+
+    fn perform(&self, operation: Search<LtiResourceLink>) -> Result<Self::Data, Self::Error> {
+        let link = operation.0;
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        let (presentation, created_at) = db.query_row(
+            "select presentation, created_at from lti_resource_links
+             where issuer = ?1 and deployment_id = ?2 and resource_link_id = ?3",
+            &[&link.issuer, &link.deployment_id, &link.resource_link_id],
+            |row| (row.get(0), row.get(1)))
+            .map_err(|err| err.to_string())?;
+        Ok(LtiResourceLink {
+            presentation: Id(presentation),
+            created_at: created_at,
+            ..link
+        })
+    }
+}
+
+impl Capability<CreateTable<AccessGrant>> for SQLite {
+    type Data = ();
+    type Error = String;
+
+    /// Grants used to be stored as they were sent rather than as digests. Those are discarded, so
+    /// audience members holding one will need to enter the passcode again.
+    fn perform(&self, _operation: CreateTable<AccessGrant>) -> Result<Self::Data, Self::Error> {
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        let legacy = table_columns(&db, "access_grants")
+            .map_err(|err| err.to_string())?
+            .iter()
+            .any(|column| column == "token");
+        if legacy {
+            db.execute("drop table access_grants", &[]).map_err(|err| err.to_string())?;
+        }
+        db.execute(
+            "create table if not exists access_grants (
+                token_digest    blob primary key,
+                presentation    text not null,
+                expires_at      text not null
+            )",
+            &[])
+            .map(|_| ())
+            .map_err(|err| err.to_string())
+    }
+}
+
+impl Capability<Save<AccessGrant>> for SQLite {
+    type Data = AccessGrant;
+    type Error = String;
+
+    fn perform(&self, operation: Save<AccessGrant>) -> Result<Self::Data, Self::Error> {
+        let grant = operation.0;
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        db.execute(
+            "insert into access_grants (token_digest, presentation, expires_at) values (?1, ?2, ?3)",
+            &[&grant.token_digest(), &grant.presentation.0, &grant.expires_at])
+            .map(|_| grant)
+            .map_err(|err| err.to_string())
+    }
+}
+
+impl Capability<Search<AccessGrant>> for SQLite {
+    type Data = AccessGrant;
+    type Error = String;
+
+    fn perform(&self, operation: Search<AccessGrant>) -> Result<Self::Data, Self::Error> {
+        let digest = operation.0.token_digest();
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        let (stored_digest, presentation, expires_at): (Vec<u8>, String, _) = db.query_row(
+            "select token_digest, presentation, expires_at from access_grants where token_digest = ?1",
+            &[&digest],
+            |row| (row.get(0), row.get(1), row.get(2)))
+            .map_err(|_| "No such grant.".to_string())?;
+        verify_slices_are_equal(&stored_digest, &digest)
+            .map_err(|_| "No such grant.".to_string())?;
+        Ok(AccessGrant {
+            token: operation.0.token,
+            presentation: Id(presentation),
+            expires_at: expires_at,
+        })
+    }
+}
+
+impl Capability<CreateTable<TokenBucket>> for SQLite {
+    type Data = ();
+    type Error = String;
+
+    fn perform(&self, _operation: CreateTable<TokenBucket>) -> Result<Self::Data, Self::Error> {
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        db.execute(
+            "create table if not exists token_buckets (
+                key         text primary key,
+                tokens      real not null,
+                updated_at  text not null
+            )",
+            &[])
+            .map(|_| ())
+            .map_err(|err| err.to_string())
+    }
+}
+
+impl Capability<Save<TokenBucket>> for SQLite {
+    type Data = TokenBucket;
+    type Error = String;
+
+    /// Saving a bucket replaces any earlier state stored under the same key.
+    fn perform(&self, operation: Save<TokenBucket>) -> Result<Self::Data, Self::Error> {
+        let bucket = operation.0;
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        db.execute(
+            "insert or replace into token_buckets (key, tokens, updated_at) values (?1, ?2, ?3)",
+            &[&bucket.key, &bucket.tokens, &bucket.updated_at])
+            .map(|_| bucket)
+            .map_err(|err| err.to_string())
+    }
+}
+
+impl Capability<Search<TokenBucket>> for SQLite {
+    type Data = TokenBucket;
+    type Error = String;
+
+    fn perform(&self, operation: Search<TokenBucket>) -> Result<Self::Data, Self::Error> {
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        db.query_row(
+            "select tokens, updated_at from token_buckets where key = ?1",
+            &[&operation.0.key],
+            |row| (row.get(0), row.get(1)))
+            .map(|(tokens, updated_at)| TokenBucket {
+                key: operation.0.key,
+                tokens: tokens,
+                updated_at: updated_at,
+            })
+            .map_err(|err| err.to_string())
+    }
+}
+
+impl Capability<CreateTable<Flag>> for SQLite {
+    type Data = ();
+    type Error = String;
+
+    fn perform(&self, _operation: CreateTable<Flag>) -> Result<Self::Data, Self::Error> {
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        db.execute(
+            "create table if not exists flags (
+                question    text not null,
+                audience    text not null,
+                reason      text not null,
+                flagged_at  text not null,
+                source      text not null,
+                primary key (question, audience)
+            )",
+            &[])
+            .map(|_| ())
+            .map_err(|err| err.to_string())
+    }
+}
+
+impl Capability<Save<Flag>> for SQLite {
+    type Data = Flag;
+    type Error = String;
+
+    /// Saving a second flag from the same audience member against the same question fails.
+    fn perform(&self, operation: Save<Flag>) -> Result<Self::Data, Self::Error> {
+        let flag = operation.0;
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        db.execute(
+            "insert into flags (question, audience, reason, flagged_at, source) values (?1, ?2, ?3, ?4, ?5)",
+            &[&flag.question.0, &flag.audience.0, &flag.reason, &flag.flagged_at, &flag.source])
+            .map(|_| flag)
+            .map_err(|err| match err {
+                SQLiteError::SqliteFailure(ref failure, _) if failure.code == ErrorCode::ConstraintViolation =>
+                    "You have already flagged this question.".to_string(),
+                err => err.to_string(),
+            })
+    }
+}
+
+impl Capability<FindAll<FlagsForQuestion>> for SQLite {
+    type Data = Vec<Flag>;
+    type Error = String;
+
+    fn perform(&self, operation: FindAll<FlagsForQuestion>) -> Result<Self::Data, Self::Error> {
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        let mut statement = db.prepare(
+            "select question, audience, reason, flagged_at, source from flags where question = ?1 order by flagged_at")
+            .map_err(|err| err.to_string())?;
+        let flags = statement
+            .query_map(&[&(operation.0).question_id.0], |row| flag_from_row(row, 0))
+            .map_err(|err| err.to_string())?
+            .collect::<Result<Vec<Flag>, _>>()
+            .map_err(|err| err.to_string());
+        flags
+    }
+}
+
+impl Capability<FindAll<FlagsForPresentation>> for SQLite {
+    type Data = Vec<Flag>;
+    type Error = String;
+
+    fn perform(&self, operation: FindAll<FlagsForPresentation>) -> Result<Self::Data, Self::Error> {
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        let mut statement = db.prepare(
+            "select flags.question, flags.audience, flags.reason, flags.flagged_at, flags.source
+             from flags join questions on questions.id = flags.question
+             where questions.presentation = ?1
+             order by flags.flagged_at")
+            .map_err(|err| err.to_string())?;
+        let flags = statement
+            .query_map(&[&(operation.0).presentation_id.0], |row| flag_from_row(row, 0))
+            .map_err(|err| err.to_string())?
+            .collect::<Result<Vec<Flag>, _>>()
+            .map_err(|err| err.to_string());
+        flags
+    }
+}
+
+/// Read a `Flag` out of a row containing its `question`, `audience`, `reason` and `flagged_at`
+/// columns, in that order, starting at column `first`.
+fn flag_from_row(row: &::sqlite::Row, first: i32) -> Flag {
+    Flag {
+        question: Id(row.get(first)),
+        audience: Id(row.get(first + 1)),
+        reason: row.get(first + 2),
+        flagged_at: row.get(first + 3),
+        source: row.get(first + 4),
+    }
+}
+
+impl Capability<CreateTable<Membership>> for SQLite {
+    type Data = ();
+    type Error = String;
+
+    fn perform(&self, _operation: CreateTable<Membership>) -> Result<Self::Data, Self::Error> {
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        db.execute(
+            "create table if not exists memberships (
+                presentation    text not null,
+                member          text not null,
+                role            text not null,
+                joined_at       text not null,
+                primary key (presentation, member)
+            )",
+            &[])
+            .map(|_| ())
+            .map_err(|err| err.to_string())
+    }
+}
+
+impl Capability<Save<Membership>> for SQLite {
+    type Data = Membership;
+    type Error = String;
+
+    /// Saving a membership for a presenter who is already a member changes their role.
+    fn perform(&self, operation: Save<Membership>) -> Result<Self::Data, Self::Error> {
+        let membership = operation.0;
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        db.execute(
+            "insert or replace into memberships (presentation, member, role, joined_at) values (?1, ?2, ?3, ?4)",
+            &[&membership.presentation.0, &membership.member.0, &membership.role.as_str(), &membership.joined_at])
+            .map(|_| membership)
+            .map_err(|err| err.to_string())
+    }
+}
+
+impl Capability<Search<Membership>> for SQLite {
+    type Data = Membership;
+    type Error = String;
+
+    fn perform(&self, operation: Search<Membership>) -> Result<Self::Data, Self::Error> {
+        let membership = operation.0;
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        db.query_row(
+            "select presentation, member, role, joined_at from memberships where presentation = ?1 and member = ?2",
+            &[&membership.presentation.0, &membership.member.0],
+            |row| membership_from_row(row, 0))
+            .map_err(|err| err.to_string())
+    }
+}
+
+impl Capability<Delete<Membership>> for SQLite {
+    type Data = ();
+    type Error = String;
+
+    fn perform(&self, operation: Delete<Membership>) -> Result<Self::Data, Self::Error> {
+        let membership = operation.0;
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        let deleted = db.execute(
+            "delete from memberships where presentation = ?1 and member = ?2",
+            &[&membership.presentation.0, &membership.member.0])
+            .map_err(|err| err.to_string())?;
+        if deleted == 1 {
+            Ok(())
+        } else {
+            Err("No such member.".to_string())
+        }
+    }
+}
+
+impl Capability<FindAll<MembersOfPresentation>> for SQLite {
+    type Data = Vec<Membership>;
+    type Error = String;
+
+    fn perform(&self, operation: FindAll<MembersOfPresentation>) -> Result<Self::Data, Self::Error> {
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        let mut statement = db.prepare(
+            "select presentation, member, role, joined_at from memberships where presentation = ?1 order by joined_at")
+            .map_err(|err| err.to_string())?;
+        let members = statement
+            .query_map(&[&(operation.0).presentation_id.0], |row| membership_from_row(row, 0))
+            .map_err(|err| err.to_string())?
+            .collect::<Result<Vec<Membership>, _>>()
+            .map_err(|err| err.to_string());
+        members
+    }
+}
+
+/// Read a `Membership` out of a row containing its `presentation`, `member`, `role` and
+/// `joined_at` columns, in that order, starting at column `first`.
+fn membership_from_row(row: &::sqlite::Row, first: i32) -> Membership {
+    let role: String = row.get(first + 2);
+    Membership {
+        presentation: Id(row.get(first)),
+        member: Id(row.get(first + 1)),
+        role: Role::from_str(&role).unwrap_or(Role::Moderator),
+        joined_at: row.get(first + 3),
+    }
+}
+
+impl Capability<CreateTable<Invitation>> for SQLite {
+    type Data = ();
+    type Error = String;
+
+    /// Invitations used to be stored as they were sent rather than as digests. Those are
+    /// discarded, so presenters still holding one will need to be invited again.
+    fn perform(&self, _operation: CreateTable<Invitation>) -> Result<Self::Data, Self::Error> {
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        let legacy = table_columns(&db, "invitations")
+            .map_err(|err| err.to_string())?
+            .iter()
+            .any(|column| column == "token");
+        if legacy {
+            db.execute("drop table invitations", &[]).map_err(|err| err.to_string())?;
+        }
+        db.execute(
+            "create table if not exists invitations (
+                token_digest    blob primary key,
+                presentation    text not null,
+                email           text not null,
+                role            text not null,
+                invited_by      text not null,
+                expires_at      text not null
+            )",
+            &[])
+            .map(|_| ())
+            .map_err(|err| err.to_string())
+    }
+}
+
+impl Capability<Save<Invitation>> for SQLite {
+    type Data = Invitation;
+    type Error = String;
+
+    fn perform(&self, operation: Save<Invitation>) -> Result<Self::Data, Self::Error> {
+        let invitation = operation.0;
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        db.execute(
+            "insert into invitations (token_digest, presentation, email, role, invited_by, expires_at)
+             values (?1, ?2, ?3, ?4, ?5, ?6)",
+            &[&invitation.token_digest(), &invitation.presentation.0, &invitation.email.0, &invitation.role.as_str(),
+              &invitation.invited_by.0, &invitation.expires_at])
+            .map(|_| invitation)
+            .map_err(|err| err.to_string())
+    }
+}
+
+impl Capability<Search<Invitation>> for SQLite {
+    type Data = Invitation;
+    type Error = String;
+
+    fn perform(&self, operation: Search<Invitation>) -> Result<Self::Data, Self::Error> {
+        let digest = operation.0.token_digest();
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        let (stored_digest, invitation): (Vec<u8>, Invitation) = db.query_row(
+            "select token_digest, presentation, email, role, invited_by, expires_at
+             from invitations where token_digest = ?1",
+            &[&digest],
+            |row| {
+                let role: String = row.get(3);
+                (row.get(0), Invitation {
+                    token: Id((operation.0).token.0.clone()),
+                    presentation: Id(row.get(1)),
+                    email: Id(row.get(2)),
+                    role: Role::from_str(&role).unwrap_or(Role::Moderator),
+                    invited_by: Id(row.get(4)),
+                    expires_at: row.get(5),
+                })
+            })
+            .map_err(|_| "No such invitation.".to_string())?;
+        verify_slices_are_equal(&stored_digest, &digest)
+            .map(|_| invitation)
+            .map_err(|_| "No such invitation.".to_string())
+    }
+}
+
+impl Capability<Delete<Invitation>> for SQLite {
+    type Data = ();
+    type Error = String;
+
+    fn perform(&self, operation: Delete<Invitation>) -> Result<Self::Data, Self::Error> {
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        db.execute("delete from invitations where token_digest = ?1", &[&operation.0.token_digest()])
+            .map(|_| ())
+            .map_err(|err| err.to_string())
+    }
+}
+
+impl Capability<CreateTable<Organization>> for SQLite {
+    type Data = ();
+    type Error = String;
+
+    fn perform(&self, _operation: CreateTable<Organization>) -> Result<Self::Data, Self::Error> {
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        db.execute(
+            "create table if not exists organizations (
+                id                  text primary key,
+                name                text not null,
+                creation_date       text not null,
+                ask_capacity        integer not null,
+                ask_per_minute      integer not null,
+                nod_capacity        integer not null,
+                nod_per_minute      integer not null,
+                filters             text not null,
+                retention_days      integer
+            )",
+            &[])
+            .map(|_| ())
+            .map_err(|err| err.to_string())
+    }
+}
+
+impl Capability<Save<Organization>> for SQLite {
+    type Data = Organization;
+    type Error = String;
+
+    fn perform(&self, operation: Save<Organization>) -> Result<Self::Data, Self::Error> {
+        let organization = operation.0;
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        let defaults = &organization.defaults;
+        db.execute(
+            "insert into organizations
+                (id, name, creation_date, ask_capacity, ask_per_minute, nod_capacity, nod_per_minute, filters,
+                 retention_days)
+             values (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            &[&organization.id.0, &organization.name, &organization.creation_date,
+              &defaults.rate_limits.ask.capacity, &defaults.rate_limits.ask.per_minute,
+              &defaults.rate_limits.nod.capacity, &defaults.rate_limits.nod.per_minute,
+              &filters_to_json(&defaults.filters), &defaults.retention_days])
+            .map(|_| ())
+            .map_err(|err| err.to_string())?;
+        Ok(organization)
+    }
+}
+
+impl Capability<Update<Organization>> for SQLite {
+    type Data = Organization;
+    type Error = String;
+
+    fn perform(&self, operation: Update<Organization>) -> Result<Self::Data, Self::Error> {
+        let organization = operation.0;
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        let updated = {
+            let defaults = &organization.defaults;
+            db.execute(
+                "update organizations
+                 set name = ?1, ask_capacity = ?2, ask_per_minute = ?3, nod_capacity = ?4, nod_per_minute = ?5,
+                     filters = ?6, retention_days = ?7
+                 where id = ?8",
+                &[&organization.name, &defaults.rate_limits.ask.capacity, &defaults.rate_limits.ask.per_minute,
+                  &defaults.rate_limits.nod.capacity, &defaults.rate_limits.nod.per_minute,
+                  &filters_to_json(&defaults.filters), &defaults.retention_days, &organization.id.0])
+                .map_err(|err| err.to_string())?
+        };
+        if updated == 1 {
+            Ok(organization)
+        } else {
+            Err("No such organization.".to_string())
+        }
+    }
+}
+
+impl Capability<Search<Organization>> for SQLite {
+    type Data = Organization;
+    type Error = String;
+
+    fn perform(&self, operation: Search<Organization>) -> Result<Self::Data, Self::Error> {
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        db.query_row(
+            "select id, name, creation_date, ask_capacity, ask_per_minute, nod_capacity, nod_per_minute, filters,
+                    retention_days
+             from organizations where id = ?1",
+            &[&(operation.0).id.0],
+            |row| {
+                let filters: String = row.get(7);
+                Organization {
+                    id: Id(row.get(0)),
+                    name: row.get(1),
+                    creation_date: row.get(2),
+                    defaults: OrganizationDefaults {
+                        rate_limits: RateLimits {
+                            ask: Budget::new(row.get(3), row.get(4)),
+                            nod: Budget::new(row.get(5), row.get(6)),
+                        },
+                        filters: serde_json::from_str(&filters).unwrap_or(vec![]),
+                        retention_days: row.get(8),
+                    },
+                }
+            })
+            .map_err(|_| "No such organization.".to_string())
+    }
+}
+
+impl Capability<CreateTable<OrganizationMember>> for SQLite {
+    type Data = ();
+    type Error = String;
+
+    fn perform(&self, _operation: CreateTable<OrganizationMember>) -> Result<Self::Data, Self::Error> {
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        db.execute(
+            "create table if not exists organization_members (
+                organization    text not null,
+                member          text not null,
+                role            text not null,
+                joined_at       text not null,
+                primary key (organization, member)
+            )",
+            &[])
+            .map(|_| ())
+            .map_err(|err| err.to_string())
+    }
+}
+
+impl Capability<Save<OrganizationMember>> for SQLite {
+    type Data = OrganizationMember;
+    type Error = String;
+
+    /// Saving a presenter who is already in the organization changes their role.
+    fn perform(&self, operation: Save<OrganizationMember>) -> Result<Self::Data, Self::Error> {
+        let member = operation.0;
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        db.execute(
+            "insert or replace into organization_members (organization, member, role, joined_at)
+             values (?1, ?2, ?3, ?4)",
+            &[&member.organization.0, &member.member.0, &member.role.as_str(), &member.joined_at])
+            .map(|_| member)
+            .map_err(|err| err.to_string())
+    }
+}
+
+impl Capability<Search<OrganizationMember>> for SQLite {
+    type Data = OrganizationMember;
+    type Error = String;
+
+    fn perform(&self, operation: Search<OrganizationMember>) -> Result<Self::Data, Self::Error> {
+        let member = operation.0;
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        db.query_row(
+            "select organization, member, role, joined_at from organization_members
+             where organization = ?1 and member = ?2",
+            &[&member.organization.0, &member.member.0],
+            |row| organization_member_from_row(row, 0))
+            .map_err(|_| "No such member.".to_string())
+    }
+}
+
+impl Capability<Delete<OrganizationMember>> for SQLite {
+    type Data = ();
+    type Error = String;
+
+    fn perform(&self, operation: Delete<OrganizationMember>) -> Result<Self::Data, Self::Error> {
+        let member = operation.0;
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        let deleted = db.execute(
+            "delete from organization_members where organization = ?1 and member = ?2",
+            &[&member.organization.0, &member.member.0])
+            .map_err(|err| err.to_string())?;
+        if deleted == 1 {
+            Ok(())
+        } else {
+            Err("No such member.".to_string())
+        }
+    }
+}
+
+impl Capability<FindAll<MembersOfOrganization>> for SQLite {
+    type Data = Vec<OrganizationMember>;
+    type Error = String;
+
+    fn perform(&self, operation: FindAll<MembersOfOrganization>) -> Result<Self::Data, Self::Error> {
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        let mut statement = db.prepare(
+            "select organization, member, role, joined_at from organization_members
+             where organization = ?1 order by joined_at")
+            .map_err(|err| err.to_string())?;
+        let members = statement
+            .query_map(&[&(operation.0).organization_id.0], |row| organization_member_from_row(row, 0))
+            .map_err(|err| err.to_string())?
+            .collect::<Result<Vec<OrganizationMember>, _>>()
+            .map_err(|err| err.to_string());
+        members
+    }
+}
+
+/// Read an `OrganizationMember` out of a row containing its `organization`, `member`, `role` and
+/// `joined_at` columns, in that order, starting at column `first`.
+fn organization_member_from_row(row: &::sqlite::Row, first: i32) -> OrganizationMember {
+    let role: String = row.get(first + 2);
+    OrganizationMember {
+        organization: Id(row.get(first)),
+        member: Id(row.get(first + 1)),
+        role: OrganizationRole::from_str(&role).unwrap_or(OrganizationRole::Member),
+        joined_at: row.get(first + 3),
+    }
+}
+
+impl Capability<CreateTable<OwnershipTransfer>> for SQLite {
+    type Data = ();
+    type Error = String;
+
+    fn perform(&self, _operation: CreateTable<OwnershipTransfer>) -> Result<Self::Data, Self::Error> {
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        db.execute(
+            "create table if not exists ownership_transfers (
+                presentation    text primary key,
+                from_presenter  text not null,
+                to_presenter    text not null,
+                expires_at      text not null
+            )",
+            &[])
+            .map(|_| ())
+            .map_err(|err| err.to_string())
+    }
+}
+
+impl Capability<Save<OwnershipTransfer>> for SQLite {
+    type Data = OwnershipTransfer;
+    type Error = String;
+
+    /// Offering a presentation replaces any offer of it that is still open.
+    fn perform(&self, operation: Save<OwnershipTransfer>) -> Result<Self::Data, Self::Error> {
+        let transfer = operation.0;
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        db.execute(
+            "insert or replace into ownership_transfers (presentation, from_presenter, to_presenter, expires_at)
+             values (?1, ?2, ?3, ?4)",
+            &[&transfer.presentation.0, &transfer.from.0, &transfer.to.0, &transfer.expires_at])
+            .map(|_| transfer)
+            .map_err(|err| err.to_string())
+    }
+}
+
+impl Capability<Search<OwnershipTransfer>> for SQLite {
+    type Data = OwnershipTransfer;
+    type Error = String;
+
+    fn perform(&self, operation: Search<OwnershipTransfer>) -> Result<Self::Data, Self::Error> {
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        db.query_row(
+            "select presentation, from_presenter, to_presenter, expires_at from ownership_transfers
+             where presentation = ?1",
+            &[&(operation.0).presentation.0],
+            |row| OwnershipTransfer {
+                presentation: Id(row.get(0)),
+                from: Id(row.get(1)),
+                to: Id(row.get(2)),
+                expires_at: row.get(3),
+            })
+            .map_err(|_| "No such transfer.".to_string())
+    }
+}
+
+impl Capability<Delete<OwnershipTransfer>> for SQLite {
+    type Data = ();
+    type Error = String;
+
+    fn perform(&self, operation: Delete<OwnershipTransfer>) -> Result<Self::Data, Self::Error> {
+        let db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        let deleted = db.execute(
+            "delete from ownership_transfers where presentation = ?1",
+            &[&(operation.0).presentation.0])
+            .map_err(|err| err.to_string())?;
+        if deleted == 1 {
+            Ok(())
+        } else {
+            Err("No such transfer.".to_string())
+        }
+    }
+}
+
+impl Capability<Update<TransferOwnership>> for SQLite {
+    type Data = ();
+    type Error = String;
+
+    /// The open offer of the presentation is closed, and the new creator's membership in it, which
+    /// their ownership makes redundant, is removed. The old creator is kept on as a presenter, so
+    /// that they don't lose access to the presentation they handed over. Nothing changes if `from`
+    /// no longer created the presentation.
+    fn perform(&self, operation: Update<TransferOwnership>) -> Result<Self::Data, Self::Error> {
+        let transfer = operation.0;
+        let mut db = self.database.lock().map_err(|_| "Database unavailable.".to_string())?;
+        let transaction = db.transaction().map_err(|err| err.to_string())?;
+        let updated = transaction.execute(
+            "update presentations set creator = ?1 where id = ?2 and creator = ?3",
+            &[&transfer.to.0, &transfer.presentation_id.0, &transfer.from.0])
+            .map_err(|err| err.to_string())?;
+        if updated != 1 {
+            return Err("The presentation has changed hands since it was offered.".to_string());
+        }
+        transaction.execute(
+            "delete from ownership_transfers where presentation = ?1",
+            &[&transfer.presentation_id.0])
+            .and_then(|_| transaction.execute(
+                "delete from memberships where presentation = ?1 and member = ?2",
+                &[&transfer.presentation_id.0, &transfer.to.0]))
+            .and_then(|_| transaction.execute(
+                "insert or replace into memberships (presentation, member, role, joined_at) values (?1, ?2, ?3, ?4)",
+                &[&transfer.presentation_id.0, &transfer.from.0, &Role::Presenter.as_str(), &Utc::now()]))
+            .and_then(|_| transaction.commit())
+            .map_err(|err| err.to_string())
     }
 }
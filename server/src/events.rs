@@ -0,0 +1,103 @@
+//! Announcements of changes to presentations, which clients poll for so that they can update
+//! without reloading everything.
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use chrono::prelude::*;
+
+use models::Id;
+
+
+/// The number of events kept in memory. Clients that fall further behind than this must reload.
+const MAX_EVENTS: usize = 1000;
+
+
+/// The kinds of change that clients are told about.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub enum EventKind {
+    /// The presentation was opened to questions.
+    #[serde(rename = "opened")]
+    Opened,
+    /// The presentation was closed to questions.
+    #[serde(rename = "closed")]
+    Closed,
+}
+
+/// A change to a presentation. Events are numbered in the order they happened, starting from one.
+#[derive(Clone, Debug, Serialize)]
+pub struct Event {
+    pub sequence: u64,
+    pub presentation: Id,
+    pub kind: EventKind,
+    #[serde(rename = "occurredAt")]
+    pub occurred_at: DateTime<Utc>,
+}
+
+/// Represents "the ability to announce changes to presentations."
+pub trait EventSink {
+    fn emit(&self, presentation: Id, kind: EventKind, occurred_at: DateTime<Utc>);
+}
+
+impl<S> EventSink for Arc<S>
+    where S: EventSink + ?Sized
+{
+    fn emit(&self, presentation: Id, kind: EventKind, occurred_at: DateTime<Utc>) {
+        (**self).emit(presentation, kind, occurred_at)
+    }
+}
+
+/// Keeps the most recent events in memory for clients to poll.
+pub struct EventLog {
+    state: Mutex<LogState>,
+}
+
+struct LogState {
+    last_sequence: u64,
+    events: VecDeque<Event>,
+}
+
+impl EventLog {
+    pub fn new() -> Self {
+        EventLog {
+            state: Mutex::new(LogState {
+                last_sequence: 0,
+                events: VecDeque::new(),
+            }),
+        }
+    }
+
+    /// Find the events concerning `presentation` that happened after the event numbered `after`.
+    /// The number of the latest event of all is returned along with them, for the client to poll
+    /// after next time.
+    pub fn since(&self, presentation: &Id, after: u64) -> Result<(Vec<Event>, u64), String> {
+        let state = self.state.lock().map_err(|_| "Events unavailable.".to_string())?;
+        let events = state.events
+            .iter()
+            .filter(|event| event.sequence > after && event.presentation == *presentation)
+            .cloned()
+            .collect();
+        Ok((events, state.last_sequence))
+    }
+}
+
+impl EventSink for EventLog {
+    fn emit(&self, presentation: Id, kind: EventKind, occurred_at: DateTime<Utc>) {
+        // A poisoned log only means another thread panicked while emitting; the log itself is intact.
+        let mut state = match self.state.lock() {
+            Ok(state) => state,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        state.last_sequence += 1;
+        let event = Event {
+            sequence: state.last_sequence,
+            presentation: presentation,
+            kind: kind,
+            occurred_at: occurred_at,
+        };
+        if state.events.len() >= MAX_EVENTS {
+            state.events.pop_front();
+        }
+        state.events.push_back(event);
+    }
+}
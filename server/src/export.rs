@@ -0,0 +1,169 @@
+//! Writing the questions asked during a presentation, and their answers, out as CSV, JSON or
+//! Markdown documents. Documents are written one question at a time, so that exports of any size
+//! can be streamed.
+
+use std::io::{self, Write};
+
+use serde_json;
+
+use capabilities::{Capability, FindAll};
+use capabilities::sqlite::{AnswersForQuestion, QuestionsPage};
+use models::{Answer, Id, Presentation, Question};
+
+
+/// How many questions are read from the database at a time.
+const PAGE_SIZE: u32 = 100;
+
+
+/// The document formats that a presentation can be exported in.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Format {
+    Csv,
+    Json,
+    Markdown,
+}
+
+/// A question together with every answer posted to it.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportedQuestion {
+    pub question: Question,
+    pub answers: Vec<Answer>,
+}
+
+impl Format {
+    /// Parse a format from the name it is requested by.
+    pub fn from_str(name: &str) -> Option<Self> {
+        match name {
+            "csv"  => Some(Format::Csv),
+            "json" => Some(Format::Json),
+            "md"   => Some(Format::Markdown),
+            _      => None,
+        }
+    }
+
+    /// The extension that files in the format are conventionally given.
+    pub fn extension(&self) -> &'static str {
+        match *self {
+            Format::Csv      => "csv",
+            Format::Json     => "json",
+            Format::Markdown => "md",
+        }
+    }
+}
+
+/// Read every question asked during the presentation identified by `presentation`, in the order
+/// they were asked, with their answers. Questions are read a page at a time and handed to `f` one
+/// by one, so that they never all need to be held in memory.
+pub fn for_each_question<DB, F>(db: &DB, presentation: &Id, mut f: F) -> Result<(), String>
+    where DB: Capability<FindAll<QuestionsPage>, Data = Vec<Question>, Error = String>
+            + Capability<FindAll<AnswersForQuestion>, Data = Vec<Answer>, Error = String>,
+          F: FnMut(ExportedQuestion) -> Result<(), String>
+{
+    let mut offset = 0;
+    loop {
+        let questions = db.perform(FindAll(QuestionsPage {
+            presentation_id: presentation.clone(),
+            offset: offset,
+            limit: PAGE_SIZE,
+        }))?;
+        let count = questions.len() as u32;
+        for question in questions {
+            let answers = db.perform(FindAll(AnswersForQuestion {
+                question_id: question.id.clone(),
+            }))?;
+            f(ExportedQuestion {
+                question: question,
+                answers: answers,
+            })?;
+        }
+        if count < PAGE_SIZE {
+            return Ok(());
+        }
+        offset += PAGE_SIZE;
+    }
+}
+
+/// Write whatever precedes the first question of a document.
+pub fn write_header<W>(format: Format, presentation: &Presentation, out: &mut W) -> io::Result<()>
+    where W: Write + ?Sized
+{
+    match format {
+        Format::Csv => writeln!(out, "id,text,nods,answered,timeAsked,answers,status,reviewReason,mergedInto"),
+        Format::Json => {
+            let presentation = serde_json::to_string(presentation).map_err(to_io_error)?;
+            write!(out, "{{\"presentation\":{},\"questions\":[", presentation)
+        },
+        Format::Markdown => writeln!(out, "# {}\n", presentation.title.trim()),
+    }
+}
+
+/// Write a single question and its answers. The first question written to a document must be
+/// marked `first`.
+pub fn write_question<W>(format: Format, first: bool, entry: &ExportedQuestion, out: &mut W) -> io::Result<()>
+    where W: Write + ?Sized
+{
+    let question = &entry.question;
+    match format {
+        Format::Csv => {
+            let answers: Vec<&str> = entry.answers.iter().map(|answer| answer.text.as_str()).collect();
+            let review_reason = question.review_reason.as_ref().map(|reason| reason.as_str()).unwrap_or("");
+            let merged_into = question.merged_into.as_ref().map(|id| id.0.as_str()).unwrap_or("");
+            writeln!(out, "{},{},{},{},{},{},{},{},{}",
+                     csv_field(&question.id.0), csv_field(&question.text), question.nods, question.answered,
+                     question.ask_date.to_rfc3339(), csv_field(&answers.join("\n")), question.status.as_str(),
+                     csv_field(review_reason), csv_field(merged_into))
+        },
+        Format::Json => {
+            let entry = serde_json::to_string(entry).map_err(to_io_error)?;
+            write!(out, "{}{}", if first { "" } else { "," }, entry)
+        },
+        Format::Markdown => {
+            writeln!(out, "## {}\n", single_line(&question.text))?;
+            writeln!(out, "*Asked {} · {} nod{} · {}*\n",
+                     question.ask_date.format("%Y-%m-%d %H:%M UTC"), question.nods,
+                     if question.nods == 1 { "" } else { "s" },
+                     if question.answered { "answered" } else { "unanswered" })?;
+            for answer in entry.answers.iter() {
+                for line in answer.text.lines() {
+                    writeln!(out, "> {}", line)?;
+                }
+                writeln!(out)?;
+            }
+            Ok(())
+        },
+    }
+}
+
+/// Write whatever follows the last question of a document.
+pub fn write_footer<W>(format: Format, out: &mut W) -> io::Result<()>
+    where W: Write + ?Sized
+{
+    match format {
+        Format::Json => write!(out, "]}}"),
+        _            => Ok(()),
+    }
+}
+
+/// Quote a CSV field if it contains anything that would otherwise break the row apart. Fields that
+/// spreadsheets would mistake for formulas are prefixed with an apostrophe.
+fn csv_field(value: &str) -> String {
+    let value = if value.starts_with(|c| c == '=' || c == '+' || c == '-' || c == '@') {
+        format!("'{}", value)
+    } else {
+        value.to_string()
+    };
+    if value.contains(|c| c == ',' || c == '"' || c == '\n' || c == '\r') {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+/// Collapse text onto a single line, for use as a Markdown heading.
+fn single_line(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+fn to_io_error(err: serde_json::Error) -> io::Error {
+    io::Error::new(io::ErrorKind::Other, err)
+}
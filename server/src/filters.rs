@@ -0,0 +1,179 @@
+//! Filters that questions are run through before they are saved, so that presenters can keep
+//! abuse and spam away from their audiences.
+
+use models::{FilterAction, FilterSetting};
+
+
+/// Top-level domains common enough in spam that bare domain names ending in them count as links.
+const LINK_DOMAINS: &'static [&'static str] = &[
+    "biz", "co", "com", "info", "io", "ly", "me", "net", "org", "ru", "tk", "xyz",
+];
+
+
+/// The outcome of running a question through a filter.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Verdict {
+    Accept,
+    /// The question should be held back for a presenter to review, for the given reason.
+    Review(String),
+    /// The question should be turned away, for the given reason.
+    Reject(String),
+}
+
+/// Represents "the ability to judge whether a question is fit to be asked."
+pub trait QuestionFilter {
+    fn check(&self, text: &str) -> Verdict;
+}
+
+/// Objects to questions containing any word from a list, including when it is disguised by
+/// substituting look-alike digits and symbols for letters.
+pub struct WordListFilter {
+    words: Vec<String>,
+    action: FilterAction,
+}
+
+/// Objects to questions containing too many links or email addresses.
+pub struct LinkFilter {
+    max_links: usize,
+    action: FilterAction,
+}
+
+/// Objects to questions repeating a single character too many times in a row.
+pub struct RepeatedCharacterFilter {
+    max_repeats: usize,
+    action: FilterAction,
+}
+
+impl WordListFilter {
+    pub fn new(words: &[String], action: FilterAction) -> Self {
+        WordListFilter {
+            words: words.iter().map(|word| normalize(word)).filter(|word| !word.is_empty()).collect(),
+            action: action,
+        }
+    }
+}
+
+impl QuestionFilter for WordListFilter {
+    fn check(&self, text: &str) -> Verdict {
+        let text = normalize(text);
+        let blocked = text
+            .split(|c: char| !c.is_alphanumeric())
+            .any(|word| self.words.iter().any(|blocked| *blocked == word));
+        if blocked {
+            verdict(self.action, "The question contains a blocked word.")
+        } else {
+            Verdict::Accept
+        }
+    }
+}
+
+impl LinkFilter {
+    pub fn new(max_links: usize, action: FilterAction) -> Self {
+        LinkFilter {
+            max_links: max_links,
+            action: action,
+        }
+    }
+}
+
+impl QuestionFilter for LinkFilter {
+    fn check(&self, text: &str) -> Verdict {
+        if text.split_whitespace().filter(|word| is_link(word)).count() > self.max_links {
+            verdict(self.action, "The question contains too many links.")
+        } else {
+            Verdict::Accept
+        }
+    }
+}
+
+impl RepeatedCharacterFilter {
+    pub fn new(max_repeats: usize, action: FilterAction) -> Self {
+        RepeatedCharacterFilter {
+            max_repeats: max_repeats,
+            action: action,
+        }
+    }
+}
+
+impl QuestionFilter for RepeatedCharacterFilter {
+    fn check(&self, text: &str) -> Verdict {
+        let mut longest_run = 0;
+        let mut run = 0;
+        let mut previous = None;
+        for c in text.chars().flat_map(|c| c.to_lowercase()) {
+            run = if previous == Some(c) { run + 1 } else { 1 };
+            longest_run = longest_run.max(run);
+            previous = Some(c);
+        }
+        if longest_run > self.max_repeats {
+            verdict(self.action, "The question repeats a character too many times.")
+        } else {
+            Verdict::Accept
+        }
+    }
+}
+
+/// Construct the filter that a presenter configured.
+pub fn from_setting(setting: &FilterSetting) -> Box<QuestionFilter> {
+    match *setting {
+        FilterSetting::WordList { ref words, action } =>
+            Box::new(WordListFilter::new(words, action)),
+        FilterSetting::Links { max_links, action } =>
+            Box::new(LinkFilter::new(max_links, action)),
+        FilterSetting::RepeatedCharacters { max_repeats, action } =>
+            Box::new(RepeatedCharacterFilter::new(max_repeats, action)),
+    }
+}
+
+/// Run a question through each of the filters a presenter configured. Any rejection takes
+/// precedence over the question being held for review.
+pub fn check_all(settings: &[FilterSetting], text: &str) -> Verdict {
+    let mut outcome = Verdict::Accept;
+    for setting in settings {
+        match from_setting(setting).check(text) {
+            Verdict::Reject(reason) => return Verdict::Reject(reason),
+            Verdict::Review(reason) => if outcome == Verdict::Accept {
+                outcome = Verdict::Review(reason);
+            },
+            Verdict::Accept => (),
+        }
+    }
+    outcome
+}
+
+fn verdict(action: FilterAction, reason: &str) -> Verdict {
+    match action {
+        FilterAction::Reject => Verdict::Reject(reason.to_string()),
+        FilterAction::Review => Verdict::Review(reason.to_string()),
+    }
+}
+
+/// Lowercase text and undo common substitutions of digits and symbols for letters.
+fn normalize(text: &str) -> String {
+    text.chars()
+        .flat_map(|c| c.to_lowercase())
+        .map(|c| match c {
+            '0'       => 'o',
+            '1'       => 'i',
+            '3'       => 'e',
+            '4' | '@' => 'a',
+            '5' | '$' => 's',
+            '7'       => 't',
+            c         => c,
+        })
+        .collect()
+}
+
+/// Determine whether a word looks like a URL, an email address or a bare domain name.
+fn is_link(word: &str) -> bool {
+    let word = word.trim_matches(|c: char| !c.is_alphanumeric() && c != '/').to_lowercase();
+    if word.contains("://") || word.starts_with("www.") {
+        return true;
+    }
+    let host = word.splitn(2, '/').next().unwrap_or("");
+    let host = host.rsplitn(2, '@').next().unwrap_or("");
+    let labels: Vec<&str> = host.split('.').collect();
+    labels.len() >= 2
+        && labels.iter().all(|label| !label.is_empty() && label.chars().all(|c| c.is_alphanumeric() || c == '-'))
+        && LINK_DOMAINS.contains(labels.last().unwrap())
+}
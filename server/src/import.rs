@@ -0,0 +1,370 @@
+//! Reading questions, and their answers, in from the CSV and JSON documents that presentations are
+//! exported as, so that questions can be prepared ahead of a talk or moved between servers.
+//!
+//! Every question in a document is checked before anything is created, and every problem found is
+//! reported along with the row it was found in. Rows are numbered from one, not counting the header
+//! of a CSV document; problems with the document as a whole are reported against row zero.
+//!
+//! Questions keep the review status they were exported with, so that questions that were held back,
+//! rejected or merged into others aren't shown to the audience once they are imported.
+
+use std::collections::HashMap;
+
+use chrono::prelude::*;
+use serde_json;
+
+use export::Format;
+use models::{Answer, Id, Presentation, Question, QuestionStatus};
+
+
+/// The most characters a question or answer may contain.
+pub const MAX_TEXT_LENGTH: usize = 2000;
+
+
+/// A problem found in a document, which prevents it from being imported.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct RowError {
+    pub row: usize,
+    pub message: String,
+}
+
+/// A question read from a document, before it belongs to any presentation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImportedQuestion {
+    /// The ID the question had where it was exported from, which other questions in the document
+    /// may refer to.
+    pub id: Option<String>,
+    pub text: String,
+    pub nods: u32,
+    pub answered: bool,
+    pub asked_at: Option<DateTime<Utc>>,
+    pub answers: Vec<String>,
+    pub status: QuestionStatus,
+    pub review_reason: Option<String>,
+    /// The ID, where it was exported from, of the question this one was merged into.
+    pub merged_into: Option<String>,
+}
+
+/// Everything that could be read from a document.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Document {
+    /// The title of the presentation the document was exported from, if it says.
+    pub title: Option<String>,
+    pub questions: Vec<ImportedQuestion>,
+}
+
+#[derive(Deserialize)]
+struct JsonDocument {
+    #[serde(default)]
+    presentation: Option<JsonPresentation>,
+    questions: Vec<serde_json::Value>,
+}
+
+#[derive(Deserialize)]
+struct JsonPresentation {
+    title: String,
+}
+
+#[derive(Deserialize)]
+struct JsonEntry {
+    question: JsonQuestion,
+    #[serde(default)]
+    answers: Vec<JsonAnswer>,
+}
+
+#[derive(Deserialize)]
+struct JsonQuestion {
+    #[serde(default)]
+    id: Option<String>,
+    text: String,
+    #[serde(default)]
+    nods: u32,
+    #[serde(default)]
+    answered: bool,
+    #[serde(rename = "timeAsked", default)]
+    time_asked: Option<DateTime<Utc>>,
+    #[serde(default)]
+    status: Option<QuestionStatus>,
+    #[serde(rename = "reviewReason", default)]
+    review_reason: Option<String>,
+    #[serde(rename = "mergedInto", default)]
+    merged_into: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct JsonAnswer {
+    text: String,
+}
+
+impl RowError {
+    fn new(row: usize, message: String) -> Self {
+        RowError {
+            row: row,
+            message: message,
+        }
+    }
+}
+
+impl ImportedQuestion {
+    /// Check that the question and its answers could have been asked and written in the application,
+    /// and that no earlier question in the document has its ID. `ids` maps the IDs seen so far to
+    /// the rows they were seen in.
+    fn validate(self, row: usize, ids: &mut HashMap<String, usize>, errors: &mut Vec<RowError>) -> Option<Self> {
+        let mut valid = true;
+        if let Some(ref id) = self.id {
+            if let Some(first) = ids.get(id) {
+                errors.push(RowError::new(row, format!("The ID {} is already used in row {}.", id, first)));
+                valid = false;
+            }
+            ids.entry(id.clone()).or_insert(row);
+        }
+        if self.text.trim().is_empty() {
+            errors.push(RowError::new(row, "The question has no text.".to_string()));
+            valid = false;
+        }
+        if self.text.chars().count() > MAX_TEXT_LENGTH {
+            errors.push(RowError::new(row, format!("The question is longer than {} characters.", MAX_TEXT_LENGTH)));
+            valid = false;
+        }
+        if self.answers.iter().any(|answer| answer.chars().count() > MAX_TEXT_LENGTH) {
+            errors.push(RowError::new(row, format!("An answer is longer than {} characters.", MAX_TEXT_LENGTH)));
+            valid = false;
+        }
+        if valid { Some(self) } else { None }
+    }
+}
+
+/// Read every question out of a document, or report every problem with it.
+pub fn parse(format: Format, document: &str) -> Result<Document, Vec<RowError>> {
+    match format {
+        Format::Csv      => parse_csv(document),
+        Format::Json     => parse_json(document),
+        Format::Markdown => Err(vec![RowError::new(0, "Markdown documents cannot be imported.".to_string())]),
+    }
+}
+
+/// Turn the questions read from a document into records belonging to `presentation`. Answers are
+/// attributed to the presentation's creator.
+///
+/// Questions are given new IDs, and merged questions are pointed at the new IDs of the questions
+/// they were merged into. Merged questions whose originals aren't in the document are left out,
+/// since they would otherwise reappear as questions of their own.
+pub fn records(document: Document, presentation: &Presentation) -> Vec<(Question, Vec<Answer>)> {
+    let ids: HashMap<String, Id> = document.questions.iter()
+        .filter_map(|imported| imported.id.clone())
+        .map(|id| (id, Id::generate()))
+        .collect();
+    document.questions.into_iter().filter_map(|imported| {
+        let merged_into = match imported.merged_into {
+            Some(ref original) => Some(ids.get(original)?.clone()),
+            None               => None,
+        };
+        let mut question = Question::new(presentation.id.clone(), imported.text.trim().to_string());
+        if let Some(id) = imported.id.as_ref().and_then(|id| ids.get(id)) {
+            question.id = id.clone();
+        }
+        question.nods = imported.nods;
+        question.answered = imported.answered || !imported.answers.is_empty();
+        if let Some(asked_at) = imported.asked_at {
+            question.ask_date = asked_at;
+        }
+        question.status = imported.status;
+        question.review_reason = imported.review_reason;
+        question.merged_into = merged_into;
+        let answers = imported.answers.into_iter()
+            .map(|text| Answer::new(presentation.creator.clone(), question.id.clone(), text))
+            .collect();
+        Some((question, answers))
+    }).collect()
+}
+
+fn parse_json(document: &str) -> Result<Document, Vec<RowError>> {
+    let document: JsonDocument = serde_json::from_str(document)
+        .map_err(|err| vec![RowError::new(0, format!("The document is not valid: {}", err))])?;
+    let mut errors = vec![];
+    let mut questions = vec![];
+    let mut ids = HashMap::new();
+    for (index, entry) in document.questions.into_iter().enumerate() {
+        let row = index + 1;
+        let entry: JsonEntry = match serde_json::from_value(entry) {
+            Ok(entry) => entry,
+            Err(err) => {
+                errors.push(RowError::new(row, format!("The question is not valid: {}", err)));
+                continue;
+            },
+        };
+        let question = ImportedQuestion {
+            id: entry.question.id,
+            text: entry.question.text,
+            nods: entry.question.nods,
+            answered: entry.question.answered,
+            asked_at: entry.question.time_asked,
+            answers: entry.answers.into_iter()
+                .map(|answer| answer.text)
+                .filter(|text| !text.trim().is_empty())
+                .collect(),
+            status: entry.question.status.unwrap_or(QuestionStatus::Approved),
+            review_reason: entry.question.review_reason,
+            merged_into: entry.question.merged_into,
+        };
+        if let Some(question) = question.validate(row, &mut ids, &mut errors) {
+            questions.push(question);
+        }
+    }
+    if errors.is_empty() {
+        Ok(Document {
+            title: document.presentation.map(|presentation| presentation.title),
+            questions: questions,
+        })
+    } else {
+        Err(errors)
+    }
+}
+
+/// CSV documents need a header naming their columns, of which only `text` is required. The `id`,
+/// `nods`, `answered`, `timeAsked`, `answers`, `status`, `reviewReason` and `mergedInto` columns
+/// are read if they are present, and any others are ignored. Since a CSV export puts all of a
+/// question's answers in one field, each field is read back as a single answer.
+fn parse_csv(document: &str) -> Result<Document, Vec<RowError>> {
+    let mut records = csv_records(document).map_err(|err| vec![err])?.into_iter();
+    let header = records.next().unwrap_or(vec![]);
+    let column = |name: &str| header.iter().position(|field| field.trim() == name);
+    let text_column = column("text")
+        .ok_or(vec![RowError::new(0, "The header has no text column.".to_string())])?;
+    let (nods_column, answered_column) = (column("nods"), column("answered"));
+    let (asked_column, answers_column) = (column("timeAsked"), column("answers"));
+    let (id_column, status_column) = (column("id"), column("status"));
+    let (reason_column, merged_column) = (column("reviewReason"), column("mergedInto"));
+
+    let mut errors = vec![];
+    let mut questions = vec![];
+    let mut ids = HashMap::new();
+    for (index, record) in records.enumerate() {
+        let row = index + 1;
+        if record.len() != header.len() {
+            errors.push(RowError::new(row, format!("Expected {} fields but found {}.", header.len(), record.len())));
+            continue;
+        }
+        let field = |column: Option<usize>| column
+            .map(|column| record[column].trim())
+            .filter(|value| !value.is_empty());
+        let mut valid = true;
+        let nods = match field(nods_column).map(|nods| nods.parse::<u32>()) {
+            Some(Ok(nods)) => nods,
+            Some(Err(_)) => {
+                errors.push(RowError::new(row, "Nods must be a whole number.".to_string()));
+                valid = false;
+                0
+            },
+            None => 0,
+        };
+        let answered = match field(answered_column) {
+            Some("true") => true,
+            Some("false") | None => false,
+            Some(_) => {
+                errors.push(RowError::new(row, "Answered must be true or false.".to_string()));
+                valid = false;
+                false
+            },
+        };
+        let asked_at = match field(asked_column).map(DateTime::parse_from_rfc3339) {
+            Some(Ok(asked_at)) => Some(asked_at.with_timezone(&Utc)),
+            Some(Err(_)) => {
+                errors.push(RowError::new(row, "The time asked must be an RFC 3339 date.".to_string()));
+                valid = false;
+                None
+            },
+            None => None,
+        };
+        let status = match field(status_column).map(QuestionStatus::from_str) {
+            Some(Some(status)) => status,
+            Some(None) => {
+                errors.push(RowError::new(row, "The status must be approved, pending or rejected.".to_string()));
+                valid = false;
+                QuestionStatus::Approved
+            },
+            None => QuestionStatus::Approved,
+        };
+        let answers = field(answers_column).map(|answer| vec![unescape_formula(answer)]).unwrap_or(vec![]);
+        let question = ImportedQuestion {
+            id: field(id_column).map(unescape_formula),
+            text: unescape_formula(&record[text_column]),
+            nods: nods,
+            answered: answered,
+            asked_at: asked_at,
+            answers: answers,
+            status: status,
+            review_reason: field(reason_column).map(unescape_formula),
+            merged_into: field(merged_column).map(unescape_formula),
+        };
+        if let Some(question) = question.validate(row, &mut ids, &mut errors) {
+            if valid {
+                questions.push(question);
+            }
+        }
+    }
+    if errors.is_empty() {
+        Ok(Document {
+            title: None,
+            questions: questions,
+        })
+    } else {
+        Err(errors)
+    }
+}
+
+/// Split a CSV document into records of fields. Quoted fields may contain commas, line breaks and
+/// doubled quotes. Blank lines are skipped.
+fn csv_records(document: &str) -> Result<Vec<Vec<String>>, RowError> {
+    let mut records = vec![];
+    let mut record = vec![];
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = document.chars().peekable();
+    while let Some(c) = chars.next() {
+        if quoted {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                },
+                '"' => quoted = false,
+                _   => field.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' if field.is_empty() => quoted = true,
+            ','  => record.push(field.split_off(0)),
+            '\r' => {},
+            '\n' => {
+                record.push(field.split_off(0));
+                if record.len() > 1 || !record[0].is_empty() {
+                    records.push(record.split_off(0));
+                } else {
+                    record.clear();
+                }
+            },
+            _    => field.push(c),
+        }
+    }
+    if quoted {
+        // Rows are numbered without the header, which is the first record.
+        return Err(RowError::new(records.len(), "A quoted field is never closed.".to_string()));
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    Ok(records)
+}
+
+/// Remove the apostrophe that exports put in front of fields that spreadsheets would otherwise
+/// mistake for formulas.
+fn unescape_formula(value: &str) -> String {
+    let mut chars = value.chars();
+    match (chars.next(), chars.next()) {
+        (Some('\''), Some(c)) if c == '=' || c == '+' || c == '-' || c == '@' => value[1..].to_string(),
+        _                                                                      => value.to_string(),
+    }
+}
@@ -1,4 +1,7 @@
+extern crate bodyparser;
 extern crate chrono;
+extern crate iron;
+extern crate ring;
 extern crate ring_pwhash as password_hash;
 extern crate rusqlite as sqlite;
 extern crate serde;
@@ -6,6 +9,29 @@ extern crate serde;
 extern crate serde_json;
 extern crate rand;
 extern crate base64;
+extern crate base32;
+extern crate url;
+extern crate urlencoded;
+extern crate router;
+extern crate untrusted;
+extern crate reqwest;
+extern crate png;
+extern crate qrcode;
+extern crate lettre;
+extern crate lettre_email;
+extern crate printpdf;
 
 pub mod models;
+pub mod api;
 #[macro_use] pub mod capabilities;
+pub mod auth;
+pub mod events;
+pub mod export;
+pub mod filters;
+pub mod import;
+pub mod mailer;
+pub mod qr;
+pub mod ratelimit;
+pub mod report;
+pub mod scheduler;
+pub mod similarity;
@@ -0,0 +1,37 @@
+use std::sync::Arc;
+
+
+pub mod outbox;
+pub mod smtp;
+
+
+/// A plain-text email addressed to a single recipient.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Message {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+/// Represents "the ability to deliver an email," independent of how it actually gets delivered.
+pub trait Mailer {
+    fn send(&self, message: Message) -> Result<(), String>;
+}
+
+impl<M> Mailer for Arc<M>
+    where M: Mailer + ?Sized
+{
+    fn send(&self, message: Message) -> Result<(), String> {
+        (**self).send(message)
+    }
+}
+
+impl Message {
+    pub fn new(to: String, subject: String, body: String) -> Self {
+        Message {
+            to: to,
+            subject: subject,
+            body: body,
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::PathBuf;
+
+use chrono::prelude::*;
+use serde_json;
+
+use mailer::{Mailer, Message};
+
+
+/// A `Mailer` that writes each message to a file in a directory instead of sending it.
+/// Intended for development and tests, where no SMTP server is available.
+#[derive(Clone)]
+pub struct OutboxMailer {
+    directory: PathBuf,
+}
+
+impl OutboxMailer {
+    /// Create a new outbox writing messages into `directory`, which is created when the first
+    /// message is sent if it is missing.
+    pub fn new<P: Into<PathBuf>>(directory: P) -> Self {
+        OutboxMailer {
+            directory: directory.into(),
+        }
+    }
+
+    /// Read back every message currently sitting in the outbox, oldest first.
+    pub fn messages(&self) -> Result<Vec<Message>, String> {
+        let mut paths = fs::read_dir(&self.directory)
+            .map_err(|err| err.to_string())?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.extension().map(|ext| ext == "json").unwrap_or(false))
+            .collect::<Vec<PathBuf>>();
+        paths.sort();
+        paths
+            .into_iter()
+            .map(|path| {
+                let file = File::open(path).map_err(|err| err.to_string())?;
+                serde_json::from_reader(file).map_err(|err| err.to_string())
+            })
+            .collect()
+    }
+}
+
+impl Mailer for OutboxMailer {
+    fn send(&self, message: Message) -> Result<(), String> {
+        fs::create_dir_all(&self.directory).map_err(|err| err.to_string())?;
+        let now = Utc::now();
+        let file_name = format!("{}-{:09}.json", now.timestamp(), now.timestamp_subsec_nanos());
+        let mut file = File::create(self.directory.join(file_name)).map_err(|err| err.to_string())?;
+        let encoded = serde_json::to_string_pretty(&message).map_err(|err| err.to_string())?;
+        file.write_all(encoded.as_bytes()).map_err(|err| err.to_string())
+    }
+}
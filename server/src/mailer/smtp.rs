@@ -0,0 +1,53 @@
+use lettre::{EmailTransport, SmtpTransport};
+use lettre::smtp::authentication::Credentials;
+use lettre_email::EmailBuilder;
+
+use mailer::{Mailer, Message};
+
+
+/// A `Mailer` that delivers messages through an SMTP relay.
+#[derive(Clone)]
+pub struct SmtpMailer {
+    server: String,
+    sender: String,
+    credentials: Option<(String, String)>,
+}
+
+impl SmtpMailer {
+    /// Create a mailer that relays through `server` and sends messages from the address `sender`.
+    pub fn new(server: String, sender: String) -> Self {
+        SmtpMailer {
+            server: server,
+            sender: sender,
+            credentials: None,
+        }
+    }
+
+    /// Authenticate against the relay with a username and password.
+    pub fn with_credentials(mut self, username: String, password: String) -> Self {
+        self.credentials = Some((username, password));
+        self
+    }
+}
+
+impl Mailer for SmtpMailer {
+    fn send(&self, message: Message) -> Result<(), String> {
+        let email = EmailBuilder::new()
+            .to(message.to.as_str())
+            .from(self.sender.as_str())
+            .subject(message.subject.as_str())
+            .text(message.body.as_str())
+            .build()
+            .map_err(|err| err.to_string())?;
+        let mut builder = SmtpTransport::simple_builder(self.server.clone())
+            .map_err(|err| err.to_string())?;
+        if let Some((ref username, ref password)) = self.credentials {
+            builder = builder.credentials(Credentials::new(username.clone(), password.clone()));
+        }
+        let mut transport = builder.build();
+        transport
+            .send(&email)
+            .map(|_| ())
+            .map_err(|err| err.to_string())
+    }
+}
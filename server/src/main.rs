@@ -2,6 +2,7 @@ extern crate bodyparser;
 extern crate chrono;
 extern crate iron;
 extern crate persistent;
+extern crate ring;
 extern crate ring_pwhash as password_hash;
 extern crate router;
 extern crate rusqlite as sqlite;
@@ -13,13 +14,36 @@ extern crate staticfile;
 extern crate mount;
 extern crate rand;
 extern crate base64;
+extern crate base32;
+extern crate url;
+extern crate untrusted;
+extern crate reqwest;
+extern crate png;
+extern crate qrcode;
+extern crate lettre;
+extern crate lettre_email;
+extern crate printpdf;
 
 pub mod models;
 mod api;
 #[macro_use] mod capabilities;
+mod auth;
+mod events;
+mod export;
+mod filters;
+mod import;
+mod mailer;
+mod qr;
+mod ratelimit;
+mod report;
+mod scheduler;
+mod similarity;
 
+use std::env;
+use std::fs::File;
 use std::sync::{Arc, Mutex};
 use std::path::Path;
+use std::thread;
 
 use iron::prelude::*;
 use persistent::Read;
@@ -30,6 +54,9 @@ use staticfile::Static;
 
 const MAX_BODY_LENGTH: usize = 10 * 1024 * 1024;
 const DATABASE_FILE: &'static str = "asq.db";
+const MAIL_OUTBOX_DIRECTORY: &'static str = "outbox";
+const MAIL_SENDER: &'static str = "asq@localhost";
+const DEFAULT_PUBLIC_BASE_URL: &'static str = "http://127.0.0.1:9001";
 
 
 fn main() {
@@ -37,6 +64,19 @@ fn main() {
         sqlite::Connection::open(DATABASE_FILE).expect("Could not connect to database.")
     ));
     let db_authority = capabilities::sqlite::SQLite::new(db_connection);
+    capabilities::initializers::init_sqlite_tables(&db_authority).expect("Could not create tables.");
+
+    // Mail is relayed over SMTP when a server is configured, and written to a local outbox otherwise.
+    let mailer: Arc<mailer::Mailer + Sync + Send> = match env::var("ASQ_SMTP_SERVER") {
+        Ok(server) => {
+            let smtp = mailer::smtp::SmtpMailer::new(server, MAIL_SENDER.to_string());
+            match (env::var("ASQ_SMTP_USERNAME"), env::var("ASQ_SMTP_PASSWORD")) {
+                (Ok(username), Ok(password)) => Arc::new(smtp.with_credentials(username, password)),
+                _                            => Arc::new(smtp),
+            }
+        },
+        Err(_) => Arc::new(mailer::outbox::OutboxMailer::new(MAIL_OUTBOX_DIRECTORY)),
+    };
 
     /*
     let register_presenter = api::presenters::RegistrationHandler::new(db_authority);
@@ -45,22 +85,182 @@ fn main() {
     router.post("/api/presenters", register_presenter, "register_presenter");
     */
 
-    let ask_question = api::questions::ask::AskHandler::new(db_authority.clone());
-    let nod_to_question = api::questions::nod::NodHandler::new(db_authority.clone());
+    // Rate limits are forgotten on restart unless they are configured to be kept in the database.
+    let buckets: Arc<ratelimit::BucketStore + Sync + Send> = match env::var("ASQ_RATE_LIMIT_STORE") {
+        Ok(ref store) if store == "sqlite" => Arc::new(ratelimit::PersistentBuckets::new(db_authority.clone())),
+        _                                  => Arc::new(ratelimit::MemoryBuckets::new()),
+    };
+
+    // Presentations are opened and closed on schedule in the background, and clients poll for the changes.
+    let event_log = Arc::new(events::EventLog::new());
+    let scheduler = scheduler::Scheduler::new(db_authority.clone(), scheduler::SystemClock, event_log.clone());
+    thread::spawn(move || scheduler.run());
+
+    let mut ask_question = Chain::new(api::questions::ask::AskHandler::new(db_authority.clone()));
+    ask_question.link_before(api::rate_limit::RateLimiter::new(
+        db_authority.clone(), buckets.clone(), api::rate_limit::Action::Ask));
+    let mut nod_to_question = Chain::new(api::questions::nod::NodHandler::new(db_authority.clone()));
+    nod_to_question.link_before(api::rate_limit::RateLimiter::new(
+        db_authority.clone(), buckets.clone(), api::rate_limit::Action::Nod));
     let list_questions = api::questions::list::ListHandler::new(db_authority.clone());
     let answer_question = api::questions::answer::AnswerHandler::new(db_authority.clone());
-    let register_presenter = api::presenters::register::RegistrationHandler::new(db_authority.clone());
+    let approve_question = api::questions::review::ReviewHandler::new(
+        db_authority.clone(), models::QuestionStatus::Approved);
+    let reject_question = api::questions::review::ReviewHandler::new(
+        db_authority.clone(), models::QuestionStatus::Rejected);
+    let flag_question = api::questions::flag::FlagHandler::new(db_authority.clone(), buckets.clone());
+    let list_flagged = api::questions::flag::FlaggedHandler::new(db_authority.clone());
+    let merge_questions = api::questions::merge::MergeHandler::new(db_authority.clone());
+    let search_questions = api::questions::search::SearchHandler::new(db_authority.clone());
+    let register_presenter = api::presenters::register::RegistrationHandler::new(db_authority.clone(), mailer.clone());
     let login_presenter = api::presenters::login::LoginHandler::new(db_authority.clone());
+    let request_reset = api::presenters::reset::RequestResetHandler::new(db_authority.clone(), mailer.clone());
+    let reset_password = api::presenters::reset::ResetPasswordHandler::new(db_authority.clone());
+    let verify_email = api::presenters::verify::VerifyEmailHandler::new(db_authority.clone());
+    let enroll_totp = api::presenters::two_factor::EnrollHandler::new(db_authority.clone());
+    let confirm_totp = api::presenters::two_factor::ConfirmHandler::new(db_authority.clone());
+    let login_challenge = api::presenters::two_factor::ChallengeHandler::new(db_authority.clone());
+    let create_token = api::presenters::tokens::CreateTokenHandler::new(db_authority.clone());
+    let list_tokens = api::presenters::tokens::ListTokensHandler::new(db_authority.clone());
+    let revoke_token = api::presenters::tokens::RevokeTokenHandler::new(db_authority.clone());
     let list_presentations = api::presentations::list::ListHandler::new(db_authority.clone());
+    let create_presentation = api::presentations::details::CreatePresentationHandler::new(db_authority.clone());
+    let update_details = api::presentations::details::UpdateDetailsHandler::new(db_authority.clone());
+    let presenter_dashboard = api::presentations::dashboard::DashboardHandler::new(db_authority.clone());
+    let join_presentation = api::presentations::join::JoinHandler::new(db_authority.clone());
+    let join_audience = api::presentations::audience::JoinAudienceHandler::new(db_authority.clone(), buckets.clone());
+    let regenerate_join_code = api::presentations::join_code::RegenerateJoinCodeHandler::new(db_authority.clone());
+    let request_access = api::presentations::access::RequestAccessHandler::new(db_authority.clone(), buckets);
+    let set_passcode = api::presentations::access::SetPasscodeHandler::new(db_authority.clone());
+    let set_rate_limits = api::presentations::rate_limits::SetRateLimitsHandler::new(db_authority.clone());
+    let set_filters = api::presentations::filters::SetFiltersHandler::new(db_authority.clone());
+    let set_moderation = api::presentations::moderation::SetModerationHandler::new(db_authority.clone());
+    let set_schedule = api::presentations::schedule::SetScheduleHandler::new(db_authority.clone());
+    let set_retention = api::presentations::retention::SetRetentionHandler::new(db_authority.clone());
+    let invite_member = api::presentations::members::InviteMemberHandler::new(db_authority.clone(), mailer.clone());
+    let accept_invitation = api::presentations::members::AcceptInvitationHandler::new(db_authority.clone());
+    let list_members = api::presentations::members::ListMembersHandler::new(db_authority.clone());
+    let remove_member = api::presentations::members::RemoveMemberHandler::new(db_authority.clone());
+    let offer_transfer = api::presentations::transfer::OfferTransferHandler::new(db_authority.clone(), mailer.clone());
+    let accept_transfer = api::presentations::transfer::AcceptTransferHandler::new(db_authority.clone());
+    let cancel_transfer = api::presentations::transfer::CancelTransferHandler::new(db_authority.clone());
+    let clone_presentation = api::presentations::clone::ClonePresentationHandler::new(db_authority.clone());
+    let create_organization = api::organizations::create::CreateOrganizationHandler::new(db_authority.clone());
+    let set_organization_defaults = api::organizations::defaults::SetDefaultsHandler::new(db_authority.clone());
+    let add_organization_member = api::organizations::members::AddMemberHandler::new(db_authority.clone());
+    let remove_organization_member = api::organizations::members::RemoveMemberHandler::new(db_authority.clone());
+    let list_organization_members = api::organizations::members::ListMembersHandler::new(db_authority.clone());
+    let list_organization_presentations =
+        api::organizations::presentations::ListPresentationsHandler::new(db_authority.clone());
+    let move_presentation = api::organizations::presentations::MovePresentationHandler::new(db_authority.clone());
+    let presentation_events = api::presentations::events::EventsHandler::new(event_log);
+    let import_presentation = api::presentations::import::ImportHandler::new(db_authority.clone());
+    let export_presentation = api::presentations::export::ExportHandler::new(db_authority.clone());
+    let presentation_stats = api::presentations::stats::StatsHandler::new(db_authority.clone());
+    let presentation_report = api::presentations::report::ReportHandler::new(db_authority.clone());
+
+    // The address audience members reach the application at, which links in QR codes point to.
+    let public_base_url = env::var("ASQ_PUBLIC_BASE_URL").unwrap_or(DEFAULT_PUBLIC_BASE_URL.to_string());
+    let qr_code_svg = api::presentations::qr_code::QrCodeHandler::new(
+        db_authority.clone(), public_base_url.clone(), api::presentations::qr_code::QrFormat::Svg);
+    let qr_code_png = api::presentations::qr_code::QrCodeHandler::new(
+        db_authority.clone(), public_base_url.clone(), api::presentations::qr_code::QrFormat::Png);
 
     let mut router = Router::new();
     router.get("/questions", list_questions, "list_questions");
     router.post("/questions/ask", ask_question, "ask_question");
     router.put("/questions/nod", nod_to_question, "nod_to_question");
     router.post("/questions/answer", answer_question, "answer_question");
+    router.post("/questions/approve", approve_question, "approve_question");
+    router.post("/questions/reject", reject_question, "reject_question");
+    router.post("/questions/flag", flag_question, "flag_question");
+    router.get("/questions/flagged", list_flagged, "list_flagged");
+    router.post("/questions/merge", merge_questions, "merge_questions");
+    router.get("/questions/search", search_questions, "search_questions");
     router.post("/presenters/register", register_presenter, "register_presenter");
     router.post("/presenters/login", login_presenter, "login_presenter");
+    router.post("/presenters/password/forgot", request_reset, "request_password_reset");
+    router.post("/presenters/password/reset", reset_password, "reset_password");
+    router.post("/presenters/verify", verify_email, "verify_email");
+    router.post("/presenters/2fa/enroll", enroll_totp, "enroll_totp");
+    router.post("/presenters/2fa/confirm", confirm_totp, "confirm_totp");
+    router.post("/presenters/login/challenge", login_challenge, "login_challenge");
+    router.get("/presenters/tokens", list_tokens, "list_tokens");
+    router.post("/presenters/tokens", create_token, "create_token");
+    router.post("/presenters/tokens/revoke", revoke_token, "revoke_token");
     router.get("/presentations", list_presentations, "list_presentations");
+    router.post("/presentations", create_presentation, "create_presentation");
+    router.post("/presentations/details", update_details, "update_presentation_details");
+    router.get("/presentations/dashboard", presenter_dashboard, "presenter_dashboard");
+    router.post("/presentations/join-code", regenerate_join_code, "regenerate_join_code");
+    router.post("/presentations/access", request_access, "request_access");
+    router.post("/presentations/passcode", set_passcode, "set_passcode");
+    router.post("/presentations/rate-limits", set_rate_limits, "set_rate_limits");
+    router.post("/presentations/filters", set_filters, "set_filters");
+    router.post("/presentations/moderation", set_moderation, "set_moderation");
+    router.post("/presentations/schedule", set_schedule, "set_schedule");
+    router.post("/presentations/retention", set_retention, "set_retention");
+    router.post("/presentations/members/invite", invite_member, "invite_member");
+    router.post("/presentations/members/accept", accept_invitation, "accept_invitation");
+    router.post("/presentations/members/remove", remove_member, "remove_member");
+    router.get("/presentations/:id/members", list_members, "list_members");
+    router.post("/presentations/transfer", offer_transfer, "offer_transfer");
+    router.post("/presentations/transfer/accept", accept_transfer, "accept_transfer");
+    router.post("/presentations/transfer/cancel", cancel_transfer, "cancel_transfer");
+    router.post("/presentations/clone", clone_presentation, "clone_presentation");
+    router.post("/organizations", create_organization, "create_organization");
+    router.post("/organizations/defaults", set_organization_defaults, "set_organization_defaults");
+    router.post("/organizations/members", add_organization_member, "add_organization_member");
+    router.post("/organizations/members/remove", remove_organization_member, "remove_organization_member");
+    router.get("/organizations/:id/members", list_organization_members, "list_organization_members");
+    router.get("/organizations/:id/presentations", list_organization_presentations, "list_organization_presentations");
+    router.post("/organizations/presentations", move_presentation, "move_presentation");
+    router.get("/presentations/:id/qr.svg", qr_code_svg, "qr_code_svg");
+    router.get("/presentations/:id/qr.png", qr_code_png, "qr_code_png");
+    router.post("/presentations/import", import_presentation, "import_presentation");
+    router.get("/presentations/:id/export", export_presentation, "export_presentation");
+    router.get("/presentations/:id/report.pdf", presentation_report, "presentation_report");
+    router.get("/presentations/:id/stats", presentation_stats, "presentation_stats");
+    router.get("/presentations/:id/events", presentation_events, "presentation_events");
+    router.get("/join/:code", join_presentation, "join_presentation");
+    router.post("/presentations/audience", join_audience, "join_audience");
+
+    // Single sign-on is only offered when an OpenID Connect provider has been configured.
+    if let Ok(issuer) = env::var("ASQ_OIDC_ISSUER") {
+        let config = auth::oidc::OidcConfig {
+            issuer: issuer,
+            client_id: env::var("ASQ_OIDC_CLIENT_ID").expect("ASQ_OIDC_CLIENT_ID must be set."),
+            client_secret: env::var("ASQ_OIDC_CLIENT_SECRET").expect("ASQ_OIDC_CLIENT_SECRET must be set."),
+            redirect_uri: env::var("ASQ_OIDC_REDIRECT_URI").expect("ASQ_OIDC_REDIRECT_URI must be set."),
+            post_login_redirect: env::var("ASQ_OIDC_POST_LOGIN_REDIRECT").unwrap_or("/".to_string()),
+        };
+        let provider = Arc::new(auth::oidc::OidcProvider::discover(config)
+            .expect("Could not discover OpenID Connect provider."));
+        let oidc_login = api::oidc::login::LoginHandler::new(db_authority.clone(), provider.clone());
+        let oidc_callback = api::oidc::callback::CallbackHandler::new(db_authority.clone(), provider);
+        let oidc_link = api::oidc::link::LinkHandler::new(db_authority.clone());
+        router.get("/oidc/login", oidc_login, "oidc_login");
+        router.get("/oidc/callback", oidc_callback, "oidc_callback");
+        router.post("/oidc/link", oidc_link, "oidc_link");
+    }
+
+    // Learning management systems can only launch AsQ once they have been registered as platforms.
+    if let Ok(platforms_file) = env::var("ASQ_LTI_PLATFORMS") {
+        let platforms: Vec<auth::lti::LtiPlatform> = serde_json::from_reader(
+            File::open(platforms_file).expect("Could not open LTI platforms file."))
+            .expect("Could not parse LTI platforms file.");
+        let platforms = Arc::new(platforms);
+        let launch_url = env::var("ASQ_LTI_LAUNCH_URL").expect("ASQ_LTI_LAUNCH_URL must be set.");
+        let app_url = env::var("ASQ_LTI_APP_URL").unwrap_or("/".to_string());
+        let lti_login_get = api::lti::login::LoginHandler::new(db_authority.clone(), platforms.clone(), launch_url.clone());
+        let lti_login_post = api::lti::login::LoginHandler::new(db_authority.clone(), platforms.clone(), launch_url);
+        let lti_launch = api::lti::launch::LaunchHandler::new(db_authority.clone(), platforms, app_url);
+        let lti_link = api::lti::link::LinkHandler::new(db_authority.clone());
+        router.get("/lti/login", lti_login_get, "lti_login_get");
+        router.post("/lti/login", lti_login_post, "lti_login_post");
+        router.post("/lti/launch", lti_launch, "lti_launch");
+        router.post("/lti/link", lti_link, "lti_link");
+    }
 
     let mut mount = Mount::new();
     mount.mount("/", Static::new(Path::new("../index.html")));
@@ -0,0 +1,55 @@
+use base64;
+use chrono::Duration;
+use chrono::prelude::*;
+use rand;
+use rand::Rng;
+use ring::digest::{digest, SHA256};
+
+use models::Id;
+
+
+/// How long an audience member can take part in a private presentation after entering its passcode.
+const ACCESS_GRANT_LIFETIME_HOURS: i64 = 4;
+
+
+/// Proof that an audience member entered the passcode of a private presentation.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AccessGrant {
+    pub token: Id,
+    pub presentation: Id,
+    #[serde(rename = "expiresAt")]
+    pub expires_at: DateTime<Utc>,
+}
+
+impl AccessGrant {
+    /// Grant access to the presentation identified by `presentation`.
+    pub fn new(presentation: Id) -> Self {
+        let mut rng = rand::thread_rng();
+        let mut bytes = [0u8; 32];
+        rng.fill_bytes(&mut bytes);
+        AccessGrant {
+            token: Id(base64::encode_config(&bytes, base64::URL_SAFE_NO_PAD)),
+            presentation: presentation,
+            expires_at: Utc::now() + Duration::hours(ACCESS_GRANT_LIFETIME_HOURS),
+        }
+    }
+
+    /// Construct a grant with only the token supplied for the sake of searching the database.
+    pub fn search_parameter(token: Id) -> Self {
+        AccessGrant {
+            token: token,
+            presentation: Id(String::new()),
+            expires_at: Utc::now(),
+        }
+    }
+
+    /// The digest of the token, which is what gets stored in place of the token itself.
+    pub fn token_digest(&self) -> Vec<u8> {
+        digest(&SHA256, (self.token.0).as_bytes()).as_ref().to_vec()
+    }
+
+    /// Determine whether the grant lets its holder into `presentation` at time `now`.
+    pub fn permits(&self, presentation: &Id, now: DateTime<Utc>) -> bool {
+        self.presentation == *presentation && now < self.expires_at
+    }
+}
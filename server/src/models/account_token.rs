@@ -0,0 +1,106 @@
+use base64;
+use chrono::Duration;
+use chrono::prelude::*;
+use rand;
+use rand::Rng;
+use ring::digest::{digest, SHA256};
+
+use models::Id;
+
+
+/// How long a password reset link remains valid after it is requested.
+const PASSWORD_RESET_LIFETIME_MINUTES: i64 = 60;
+
+/// How long an email verification link remains valid after registration.
+const EMAIL_VERIFICATION_LIFETIME_MINUTES: i64 = 7 * 24 * 60;
+
+/// How long a presenter has to supply their second factor after entering their password.
+const LOGIN_CHALLENGE_LIFETIME_MINUTES: i64 = 5;
+
+
+/// The action that an `AccountToken` authorizes its bearer to take.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum TokenPurpose {
+    PasswordReset,
+    EmailVerification,
+    LoginChallenge,
+}
+
+/// A single-use, expiring token proving that its bearer has completed some step of authentication,
+/// such as receiving an email or entering the correct password ahead of a second factor.
+///
+/// Like a session token, the plaintext `token` is only known to the bearer. Only its SHA-256 digest
+/// is persisted.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AccountToken {
+    pub token: Id,
+    pub owner: Id,
+    pub purpose: TokenPurpose,
+    #[serde(rename = "expiresAt")]
+    pub expires_at: DateTime<Utc>,
+}
+
+impl TokenPurpose {
+    /// The name under which the purpose is persisted.
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            TokenPurpose::PasswordReset     => "password_reset",
+            TokenPurpose::EmailVerification => "email_verification",
+            TokenPurpose::LoginChallenge    => "login_challenge",
+        }
+    }
+
+    /// Parse a purpose from the name under which it is persisted.
+    pub fn from_str(name: &str) -> Option<Self> {
+        match name {
+            "password_reset"     => Some(TokenPurpose::PasswordReset),
+            "email_verification" => Some(TokenPurpose::EmailVerification),
+            "login_challenge"    => Some(TokenPurpose::LoginChallenge),
+            _                    => None,
+        }
+    }
+
+    fn lifetime(&self) -> Duration {
+        match *self {
+            TokenPurpose::PasswordReset     => Duration::minutes(PASSWORD_RESET_LIFETIME_MINUTES),
+            TokenPurpose::EmailVerification => Duration::minutes(EMAIL_VERIFICATION_LIFETIME_MINUTES),
+            TokenPurpose::LoginChallenge    => Duration::minutes(LOGIN_CHALLENGE_LIFETIME_MINUTES),
+        }
+    }
+}
+
+impl AccountToken {
+    /// Generate a fresh token for the presenter identified by `owner`.
+    pub fn new(owner: Id, purpose: TokenPurpose) -> Self {
+        let mut rng = rand::thread_rng();
+        let mut bytes = [0u8; 32];
+        rng.fill_bytes(&mut bytes);
+        let token = base64::encode_config(&bytes, base64::URL_SAFE_NO_PAD);
+        AccountToken {
+            token: Id(token),
+            owner: owner,
+            purpose: purpose,
+            expires_at: Utc::now() + purpose.lifetime(),
+        }
+    }
+
+    /// Construct a token with only the token value supplied for the sake of searching the database.
+    pub fn search_parameter(token: Id) -> Self {
+        AccountToken {
+            token: token,
+            owner: Id(String::new()),
+            purpose: TokenPurpose::PasswordReset,
+            expires_at: Utc::now(),
+        }
+    }
+
+    /// Determine whether the token may still be redeemed for the given purpose at time `now`.
+    pub fn is_redeemable_for(&self, purpose: TokenPurpose, now: DateTime<Utc>) -> bool {
+        self.purpose == purpose && now < self.expires_at
+    }
+
+    /// The digest of the token, which is what gets stored in place of the token itself.
+    pub fn token_digest(&self) -> Vec<u8> {
+        digest(&SHA256, (self.token.0).as_bytes()).as_ref().to_vec()
+    }
+}
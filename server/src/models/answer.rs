@@ -16,7 +16,7 @@ pub struct Answer {
 impl Answer {
     pub fn new(author: Id, question: Id, text: String) -> Self {
         Answer {
-            id: Id(String::new()),
+            id: Id::generate(),
             author: author,
             question: question,
             written_date: Utc::now(),
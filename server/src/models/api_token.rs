@@ -0,0 +1,117 @@
+use base64;
+use chrono::prelude::*;
+use rand;
+use rand::Rng;
+use ring::digest::{digest, SHA256};
+
+use models::Id;
+
+
+/// Prefixed to every API token so that they can be told apart from session tokens at a glance.
+pub const API_TOKEN_PREFIX: &'static str = "asq_";
+
+
+/// A permission that an API token can be granted.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Scope {
+    #[serde(rename = "presentations:read")]
+    PresentationsRead,
+    #[serde(rename = "presentations:write")]
+    PresentationsWrite,
+    #[serde(rename = "questions:read")]
+    QuestionsRead,
+    #[serde(rename = "questions:write")]
+    QuestionsWrite,
+}
+
+/// A named, revocable credential a presenter can mint for scripts, limited to a set of scopes.
+///
+/// Like sessions, only the digest of the token is persisted.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ApiToken {
+    pub id: Id,
+    #[serde(skip_serializing)]
+    pub token: Id,
+    pub owner: Id,
+    pub name: String,
+    pub scopes: Vec<Scope>,
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+    #[serde(rename = "lastUsed")]
+    pub last_used: Option<DateTime<Utc>>,
+    pub revoked: bool,
+}
+
+impl Scope {
+    /// The name of the scope, as presented to and accepted from clients.
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            Scope::PresentationsRead  => "presentations:read",
+            Scope::PresentationsWrite => "presentations:write",
+            Scope::QuestionsRead      => "questions:read",
+            Scope::QuestionsWrite     => "questions:write",
+        }
+    }
+
+    /// Parse a scope from its name.
+    pub fn from_str(name: &str) -> Option<Self> {
+        match name {
+            "presentations:read"  => Some(Scope::PresentationsRead),
+            "presentations:write" => Some(Scope::PresentationsWrite),
+            "questions:read"      => Some(Scope::QuestionsRead),
+            "questions:write"     => Some(Scope::QuestionsWrite),
+            _                     => None,
+        }
+    }
+}
+
+impl ApiToken {
+    /// Mint a new token for a presenter. The plaintext `token` must be handed to the presenter
+    /// right away, since it cannot be recovered later.
+    pub fn new(owner: Id, name: String, scopes: Vec<Scope>) -> Self {
+        let mut rng = rand::thread_rng();
+        let mut bytes = [0u8; 32];
+        rng.fill_bytes(&mut bytes);
+        let token = format!("{}{}", API_TOKEN_PREFIX, base64::encode_config(&bytes, base64::URL_SAFE_NO_PAD));
+        ApiToken {
+            id: Id::generate(),
+            token: Id(token),
+            owner: owner,
+            name: name,
+            scopes: scopes,
+            created_at: Utc::now(),
+            last_used: None,
+            revoked: false,
+        }
+    }
+
+    /// Construct a token with only the plaintext token supplied for the sake of searching the
+    /// database.
+    pub fn search_parameter(token: Id) -> Self {
+        ApiToken {
+            id: Id(String::new()),
+            token: token,
+            owner: Id(String::new()),
+            name: String::new(),
+            scopes: vec![],
+            created_at: Utc::now(),
+            last_used: None,
+            revoked: false,
+        }
+    }
+
+    /// Determine whether a client-supplied credential looks like an API token rather than a session.
+    pub fn is_api_token(token: &Id) -> bool {
+        token.0.starts_with(API_TOKEN_PREFIX)
+    }
+
+    /// The digest of the token, which is what gets stored in place of the token itself.
+    pub fn token_digest(&self) -> Vec<u8> {
+        digest(&SHA256, (self.token.0).as_bytes()).as_ref().to_vec()
+    }
+
+    /// Determine whether the token may currently be used for an operation requiring `scope`.
+    pub fn permits(&self, scope: Scope) -> bool {
+        !self.revoked && self.scopes.contains(&scope)
+    }
+}
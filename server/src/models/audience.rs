@@ -1,9 +1,28 @@
+use base64;
+use rand;
+use rand::Rng;
+
 use models::Id;
 
 
+/// An identity for a member of a presentation's audience. Audience members never register, so the
+/// `submission_token` is the secret that proves a request comes from the holder of this identity.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Audience {
     pub id: Id,
     #[serde(rename = "submissionToken")]
     pub submission_token: String,
 }
+
+impl Audience {
+    /// Issue a fresh submission token for the audience member identified by `id`.
+    pub fn new(id: Id) -> Self {
+        let mut rng = rand::thread_rng();
+        let mut bytes = [0u8; 32];
+        rng.fill_bytes(&mut bytes);
+        Audience {
+            id: id,
+            submission_token: base64::encode_config(&bytes, base64::URL_SAFE_NO_PAD),
+        }
+    }
+}
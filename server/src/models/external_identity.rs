@@ -0,0 +1,32 @@
+use chrono::prelude::*;
+
+use models::Id;
+
+
+/// Links an account at an external identity provider, identified by its issuer and subject, to
+/// the `Presenter` it signs in as.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExternalIdentity {
+    pub issuer: String,
+    pub subject: String,
+    pub presenter: Id,
+    #[serde(rename = "linkedAt")]
+    pub linked_at: DateTime<Utc>,
+}
+
+impl ExternalIdentity {
+    pub fn new(issuer: String, subject: String, presenter: Id) -> Self {
+        ExternalIdentity {
+            issuer: issuer,
+            subject: subject,
+            presenter: presenter,
+            linked_at: Utc::now(),
+        }
+    }
+
+    /// Construct an identity with only the issuer and subject supplied for the sake of searching
+    /// the database.
+    pub fn search_parameter(issuer: String, subject: String) -> Self {
+        ExternalIdentity::new(issuer, subject, Id(String::new()))
+    }
+}
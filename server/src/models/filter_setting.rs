@@ -0,0 +1,36 @@
+/// What happens to a question that a filter objects to.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum FilterAction {
+    /// The question is turned away, and the audience member told why.
+    #[serde(rename = "reject")]
+    Reject,
+    /// The question is held back until a presenter has reviewed it.
+    #[serde(rename = "review")]
+    Review,
+}
+
+/// A filter that a presenter has chosen to run over the questions asked during their presentation.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind")]
+pub enum FilterSetting {
+    /// Objects to questions containing any of `words`.
+    #[serde(rename = "wordList")]
+    WordList {
+        words: Vec<String>,
+        action: FilterAction,
+    },
+    /// Objects to questions containing more than `max_links` links or email addresses.
+    #[serde(rename = "links")]
+    Links {
+        #[serde(rename = "maxLinks")]
+        max_links: usize,
+        action: FilterAction,
+    },
+    /// Objects to questions repeating a single character more than `max_repeats` times in a row.
+    #[serde(rename = "repeatedCharacters")]
+    RepeatedCharacters {
+        #[serde(rename = "maxRepeats")]
+        max_repeats: usize,
+        action: FilterAction,
+    },
+}
@@ -0,0 +1,41 @@
+use std::net::IpAddr;
+
+use chrono::prelude::*;
+
+use auth::lti;
+use models::Id;
+
+
+/// A report by an audience member that a question is inappropriate. Each audience member may flag
+/// a question only once.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Flag {
+    pub question: Id,
+    pub audience: Id,
+    pub reason: String,
+    #[serde(rename = "flaggedAt")]
+    pub flagged_at: DateTime<Utc>,
+    /// Who the flag is counted as coming from. Anyone can join the audience again as a new member,
+    /// so anonymous audience members are told apart by their address, and only learners launched
+    /// from a learning management system by their identity.
+    #[serde(skip_serializing, default)]
+    pub source: String,
+}
+
+impl Flag {
+    /// Construct a flag raised by the audience member `audience` from the client IP `address`.
+    pub fn new(question: Id, audience: Id, address: IpAddr, reason: String) -> Self {
+        let source = if lti::is_launch_audience(&audience) {
+            audience.0.clone()
+        } else {
+            format!("address:{}", address)
+        };
+        Flag {
+            question: question,
+            audience: audience,
+            reason: reason,
+            flagged_at: Utc::now(),
+            source: source,
+        }
+    }
+}
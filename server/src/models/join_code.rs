@@ -0,0 +1,42 @@
+use rand;
+use rand::Rng;
+
+
+/// Characters that join codes are made of. Characters that are easily mistaken for one another on
+/// a projector, such as 0 and O or 1, I and l, are left out.
+const ALPHABET: &'static [u8] = b"23456789ABCDEFGHJKMNPQRSTUVWXYZ";
+
+/// The number of characters in a join code.
+pub const JOIN_CODE_LENGTH: usize = 6;
+
+
+/// A short code that audience members type in to join a presentation that is open to questions.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct JoinCode(pub String);
+
+impl JoinCode {
+    /// Generate a random join code.
+    pub fn generate() -> Self {
+        let mut rng = rand::thread_rng();
+        let code = (0..JOIN_CODE_LENGTH)
+            .map(|_| ALPHABET[rng.gen_range(0, ALPHABET.len())] as char)
+            .collect();
+        JoinCode(code)
+    }
+
+    /// Tidy up a code typed in by an audience member, who may have used lowercase letters or
+    /// included spaces and dashes.
+    pub fn normalize(typed: &str) -> Self {
+        let code = typed
+            .chars()
+            .filter(|c| !c.is_whitespace() && *c != '-')
+            .flat_map(|c| c.to_uppercase())
+            .collect();
+        JoinCode(code)
+    }
+
+    /// Determine whether the code could have been generated by `JoinCode::generate`.
+    pub fn is_well_formed(&self) -> bool {
+        self.0.len() == JOIN_CODE_LENGTH && self.0.bytes().all(|c| ALPHABET.contains(&c))
+    }
+}
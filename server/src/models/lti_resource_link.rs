@@ -0,0 +1,36 @@
+use chrono::prelude::*;
+
+use models::Id;
+
+
+/// Ties a resource link placed in a course on a learning management system to the presentation
+/// that launches from it open.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LtiResourceLink {
+    pub issuer: String,
+    #[serde(rename = "deploymentId")]
+    pub deployment_id: String,
+    #[serde(rename = "resourceLinkId")]
+    pub resource_link_id: String,
+    pub presentation: Id,
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+}
+
+impl LtiResourceLink {
+    pub fn new(issuer: String, deployment_id: String, resource_link_id: String, presentation: Id) -> Self {
+        LtiResourceLink {
+            issuer: issuer,
+            deployment_id: deployment_id,
+            resource_link_id: resource_link_id,
+            presentation: presentation,
+            created_at: Utc::now(),
+        }
+    }
+
+    /// Construct a link with only the identifying fields supplied for the sake of searching the
+    /// database.
+    pub fn search_parameter(issuer: String, deployment_id: String, resource_link_id: String) -> Self {
+        LtiResourceLink::new(issuer, deployment_id, resource_link_id, Id(String::new()))
+    }
+}
@@ -0,0 +1,163 @@
+use base64;
+use chrono::Duration;
+use chrono::prelude::*;
+use rand;
+use rand::Rng;
+use ring::digest::{digest, SHA256};
+
+use models::{Id, Presentation};
+
+
+/// How long an invitation to join a presentation remains valid after it is sent.
+const INVITATION_LIFETIME_DAYS: i64 = 14;
+
+
+/// The part a presenter plays in a presentation. The presenter who created a presentation is
+/// always one of its owners.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Role {
+    /// May do anything, including changing settings and deciding who else takes part.
+    #[serde(rename = "owner")]
+    Owner,
+    /// May answer and moderate questions.
+    #[serde(rename = "presenter")]
+    Presenter,
+    /// May moderate questions, but not answer them.
+    #[serde(rename = "moderator")]
+    Moderator,
+}
+
+/// The things that members of a presentation may be allowed to do with it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Action {
+    /// See every question, including those hidden from the audience, and summaries of them.
+    ViewQuestions,
+    AnswerQuestions,
+    /// Approve, reject, merge and review flagged questions.
+    ModerateQuestions,
+    ChangeSettings,
+    ManageMembers,
+}
+
+/// A presenter's role in a presentation that someone else created.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Membership {
+    pub presentation: Id,
+    pub member: Id,
+    pub role: Role,
+    #[serde(rename = "joinedAt")]
+    pub joined_at: DateTime<Utc>,
+}
+
+/// An offer, sent by email, for a presenter to take on a role in a presentation.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Invitation {
+    #[serde(skip_serializing)]
+    pub token: Id,
+    pub presentation: Id,
+    /// The email address of the presenter invited, who is the only one who may accept.
+    pub email: Id,
+    pub role: Role,
+    #[serde(rename = "invitedBy")]
+    pub invited_by: Id,
+    #[serde(rename = "expiresAt")]
+    pub expires_at: DateTime<Utc>,
+}
+
+/// A presentation that a presenter takes part in, along with the role they have in it.
+#[derive(Debug, Serialize)]
+pub struct PresentationWithRole {
+    #[serde(flatten)]
+    pub presentation: Presentation,
+    pub role: Role,
+}
+
+impl Role {
+    /// The name under which the role is persisted.
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            Role::Owner     => "owner",
+            Role::Presenter => "presenter",
+            Role::Moderator => "moderator",
+        }
+    }
+
+    /// Parse a role from the name under which it is persisted.
+    pub fn from_str(name: &str) -> Option<Self> {
+        match name {
+            "owner"     => Some(Role::Owner),
+            "presenter" => Some(Role::Presenter),
+            "moderator" => Some(Role::Moderator),
+            _           => None,
+        }
+    }
+
+    /// Determine whether members with the role may take `action`.
+    pub fn permits(&self, action: Action) -> bool {
+        match (*self, action) {
+            (Role::Owner, _)                              => true,
+            (Role::Presenter, Action::ViewQuestions)      => true,
+            (Role::Presenter, Action::AnswerQuestions)    => true,
+            (Role::Presenter, Action::ModerateQuestions)  => true,
+            (Role::Moderator, Action::ViewQuestions)      => true,
+            (Role::Moderator, Action::ModerateQuestions)  => true,
+            _                                             => false,
+        }
+    }
+}
+
+impl Membership {
+    pub fn new(presentation: Id, member: Id, role: Role) -> Self {
+        Membership {
+            presentation: presentation,
+            member: member,
+            role: role,
+            joined_at: Utc::now(),
+        }
+    }
+
+    /// Construct a membership identifying a presenter's part in a presentation, to pass to a search
+    /// or delete operation.
+    pub fn search_parameter(presentation: Id, member: Id) -> Self {
+        Membership::new(presentation, member, Role::Moderator)
+    }
+}
+
+impl Invitation {
+    /// Invite the presenter with the email address `email` to take on `role` in `presentation`.
+    pub fn new(presentation: Id, email: Id, role: Role, invited_by: Id) -> Self {
+        let mut rng = rand::thread_rng();
+        let mut bytes = [0u8; 32];
+        rng.fill_bytes(&mut bytes);
+        Invitation {
+            token: Id(base64::encode_config(&bytes, base64::URL_SAFE_NO_PAD)),
+            presentation: presentation,
+            email: email,
+            role: role,
+            invited_by: invited_by,
+            expires_at: Utc::now() + Duration::days(INVITATION_LIFETIME_DAYS),
+        }
+    }
+
+    /// Construct an invitation with only its token, for the sake of searching the database.
+    pub fn search_parameter(token: Id) -> Self {
+        Invitation {
+            token: token,
+            presentation: Id(String::new()),
+            email: Id(String::new()),
+            role: Role::Moderator,
+            invited_by: Id(String::new()),
+            expires_at: Utc::now(),
+        }
+    }
+
+    /// The digest of the token, which is what gets stored in place of the token itself.
+    pub fn token_digest(&self) -> Vec<u8> {
+        digest(&SHA256, (self.token.0).as_bytes()).as_ref().to_vec()
+    }
+
+    /// Determine whether the presenter `presenter` may accept the invitation at time `now`.
+    pub fn may_be_accepted_by(&self, presenter: &Id, now: DateTime<Utc>) -> bool {
+        now < self.expires_at && self.email.0.to_lowercase() == presenter.0.to_lowercase()
+    }
+}
@@ -1,24 +1,68 @@
+mod access_grant;
+mod account_token;
 mod answer;
+mod api_token;
 mod audience;
+mod external_identity;
+mod filter_setting;
+mod flag;
+mod join_code;
+mod lti_resource_link;
+mod membership;
+mod oidc_login_attempt;
+mod organization;
 mod presentation;
 mod presenter;
 mod question;
+mod rate_limit;
+mod search_result;
 mod session;
+mod stats;
+mod transfer;
 
 use std::cmp::PartialEq;
 
+use base64;
+use rand;
+use rand::Rng;
+
+pub use models::access_grant::AccessGrant;
+pub use models::account_token::{AccountToken, TokenPurpose};
 pub use models::answer::Answer;
+pub use models::api_token::{ApiToken, Scope};
 pub use models::audience::Audience;
-pub use models::presentation::Presentation;
+pub use models::external_identity::ExternalIdentity;
+pub use models::filter_setting::{FilterAction, FilterSetting};
+pub use models::flag::Flag;
+pub use models::join_code::JoinCode;
+pub use models::lti_resource_link::LtiResourceLink;
+pub use models::membership::{Action, Invitation, Membership, PresentationWithRole, Role};
+pub use models::oidc_login_attempt::{LOGIN_ATTEMPT_LIFETIME_MINUTES, OidcLoginAttempt};
+pub use models::organization::{Organization, OrganizationDefaults, OrganizationMember, OrganizationRole};
+pub use models::presentation::{Presentation, PresentationDetails, PublicPresentation};
 pub use models::presenter::Presenter;
-pub use models::question::Question;
+pub use models::question::{Question, QuestionStatus};
+pub use models::rate_limit::{Budget, RateLimits, TokenBucket};
+pub use models::search_result::SearchResult;
 pub use models::session::Session;
+pub use models::stats::{NodCount, PresentationActivity, QuestionCounts, TimeBucket};
+pub use models::transfer::OwnershipTransfer;
 
 
 /// A simple type used for identifiers, so we can more clearly demark relations in our models.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Id(pub String);
 
+impl Id {
+    /// Generate a new random identifier for a record.
+    pub fn generate() -> Self {
+        let mut rng = rand::thread_rng();
+        let mut bytes = [0u8; 12];
+        rng.fill_bytes(&mut bytes);
+        Id(base64::encode_config(&bytes, base64::URL_SAFE_NO_PAD))
+    }
+}
+
 impl PartialEq for Id {
     fn eq(&self, other: &Self) -> bool {
         self.0 == other.0 && self.0.len() > 0
@@ -0,0 +1,47 @@
+use chrono::Duration;
+use chrono::prelude::*;
+
+use models::Id;
+
+
+/// How long a presenter has to complete signing in at their identity provider.
+pub const LOGIN_ATTEMPT_LIFETIME_MINUTES: i64 = 10;
+
+
+/// The secrets generated when a presenter starts signing in through OpenID Connect, which are
+/// needed again when the provider redirects them back to us.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OidcLoginAttempt {
+    pub state: Id,
+    pub nonce: String,
+    #[serde(rename = "codeVerifier")]
+    pub code_verifier: String,
+    #[serde(rename = "expiresAt")]
+    pub expires_at: DateTime<Utc>,
+}
+
+impl OidcLoginAttempt {
+    pub fn new(state: Id, nonce: String, code_verifier: String) -> Self {
+        OidcLoginAttempt {
+            state: state,
+            nonce: nonce,
+            code_verifier: code_verifier,
+            expires_at: Utc::now() + Duration::minutes(LOGIN_ATTEMPT_LIFETIME_MINUTES),
+        }
+    }
+
+    /// Construct an attempt with only the state supplied for the sake of searching the database.
+    pub fn search_parameter(state: Id) -> Self {
+        OidcLoginAttempt {
+            state: state,
+            nonce: String::new(),
+            code_verifier: String::new(),
+            expires_at: Utc::now(),
+        }
+    }
+
+    /// Determine whether the presenter took too long to come back from their identity provider.
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        now >= self.expires_at
+    }
+}
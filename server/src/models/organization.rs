@@ -0,0 +1,144 @@
+use chrono::prelude::*;
+
+use models::{Id, FilterSetting, RateLimits};
+use models::presentation::validate_retention;
+
+
+/// The most characters an organization's name may contain.
+pub const MAX_NAME_LENGTH: usize = 200;
+
+
+/// A team of presenters, such as a department, that presentations can belong to.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Organization {
+    pub id: Id,
+    pub name: String,
+    #[serde(rename = "creationDate")]
+    pub creation_date: DateTime<Utc>,
+    /// The settings that presentations created in the organization start out with.
+    pub defaults: OrganizationDefaults,
+}
+
+/// Settings that an organization's admins choose for every presentation created in it. Presenters
+/// remain free to change them in their own presentations afterwards.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct OrganizationDefaults {
+    #[serde(rename = "rateLimits", default)]
+    pub rate_limits: RateLimits,
+    #[serde(default)]
+    pub filters: Vec<FilterSetting>,
+    /// How many days questions are kept for after they are asked, or forever if there is no limit.
+    #[serde(rename = "retentionDays", default)]
+    pub retention_days: Option<u32>,
+}
+
+/// The part a presenter plays in an organization.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum OrganizationRole {
+    /// May see and manage every presentation in the organization, as well as the organization
+    /// itself.
+    #[serde(rename = "admin")]
+    Admin,
+    /// May create presentations in the organization.
+    #[serde(rename = "member")]
+    Member,
+}
+
+/// A presenter's place in an organization.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct OrganizationMember {
+    pub organization: Id,
+    pub member: Id,
+    pub role: OrganizationRole,
+    #[serde(rename = "joinedAt")]
+    pub joined_at: DateTime<Utc>,
+}
+
+impl Organization {
+    /// Construct a new organization with the settings that presentations have by default.
+    pub fn new(name: String) -> Self {
+        Organization {
+            id: Id::generate(),
+            name: name,
+            creation_date: Utc::now(),
+            defaults: OrganizationDefaults::default(),
+        }
+    }
+
+    /// Create an instance of `Organization` to pass to a search operation.
+    pub fn search_parameter(id: Id) -> Self {
+        Organization {
+            id: id,
+            name: String::new(),
+            creation_date: Utc::now(),
+            defaults: OrganizationDefaults::default(),
+        }
+    }
+
+    /// Check that a name could be given to an organization, returning it trimmed.
+    pub fn validate_name(name: &str) -> Result<String, String> {
+        let name = name.trim();
+        if name.is_empty() || name.chars().count() > MAX_NAME_LENGTH {
+            Err(format!("Names must have between 1 and {} characters.", MAX_NAME_LENGTH))
+        } else {
+            Ok(name.to_string())
+        }
+    }
+}
+
+impl Default for OrganizationDefaults {
+    fn default() -> Self {
+        OrganizationDefaults {
+            rate_limits: RateLimits::default(),
+            filters: vec![],
+            retention_days: None,
+        }
+    }
+}
+
+impl OrganizationDefaults {
+    /// Check that presentations could be given these settings.
+    pub fn validate(self) -> Result<Self, String> {
+        if !self.rate_limits.is_valid() {
+            return Err("Budgets must allow at least one request per minute.".to_string());
+        }
+        validate_retention(self.retention_days)?;
+        Ok(self)
+    }
+}
+
+impl OrganizationRole {
+    /// The name under which the role is persisted.
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            OrganizationRole::Admin  => "admin",
+            OrganizationRole::Member => "member",
+        }
+    }
+
+    /// Parse a role from the name under which it is persisted.
+    pub fn from_str(name: &str) -> Option<Self> {
+        match name {
+            "admin"  => Some(OrganizationRole::Admin),
+            "member" => Some(OrganizationRole::Member),
+            _        => None,
+        }
+    }
+}
+
+impl OrganizationMember {
+    pub fn new(organization: Id, member: Id, role: OrganizationRole) -> Self {
+        OrganizationMember {
+            organization: organization,
+            member: member,
+            role: role,
+            joined_at: Utc::now(),
+        }
+    }
+
+    /// Construct a member identifying a presenter's place in an organization, to pass to a search
+    /// or delete operation.
+    pub fn search_parameter(organization: Id, member: Id) -> Self {
+        OrganizationMember::new(organization, member, OrganizationRole::Member)
+    }
+}
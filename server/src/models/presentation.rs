@@ -1,6 +1,29 @@
 use chrono::prelude::*;
+use url::Url;
 
-use models::Id;
+use auth::password;
+use models::{Id, FilterSetting, JoinCode, OrganizationDefaults, RateLimits};
+
+
+/// How many audience members must flag a question before it is hidden, unless the presenter says
+/// otherwise.
+pub const DEFAULT_FLAG_THRESHOLD: u32 = 3;
+
+/// The most characters a presentation's title may contain.
+pub const MAX_TITLE_LENGTH: usize = 200;
+
+/// The most characters a presentation's description may contain.
+pub const MAX_DESCRIPTION_LENGTH: usize = 5000;
+
+/// The most tags a presentation may have.
+pub const MAX_TAGS: usize = 20;
+
+/// The most characters a tag may contain.
+pub const MAX_TAG_LENGTH: usize = 32;
+
+/// The longest that questions may be kept for, in days, when a presentation keeps them for a
+/// limited time.
+pub const MAX_RETENTION_DAYS: u32 = 3650;
 
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -12,9 +35,126 @@ pub struct Presentation {
     pub is_open_to_questions: bool,
     #[serde(rename = "creationDate")]
     pub creation_date: DateTime<Utc>,
+    /// The code audience members join with, which only exists while the presentation is open.
+    #[serde(rename = "joinCode")]
+    pub join_code: Option<JoinCode>,
+    /// Whether audience members must enter a passcode to take part.
+    #[serde(rename = "isPrivate")]
+    pub is_private: bool,
+    /// The hash of the passcode of a private presentation.
+    #[serde(skip_serializing, default)]
+    pub passcode_hash: Option<String>,
+    /// How often each audience member may ask and nod to questions.
+    #[serde(rename = "rateLimits", default)]
+    pub rate_limits: RateLimits,
+    /// The filters that questions asked during the presentation are run through, in order.
+    #[serde(default)]
+    pub filters: Vec<FilterSetting>,
+    /// Whether new questions are held back until a presenter approves them.
+    #[serde(rename = "requiresApproval", default)]
+    pub requires_approval: bool,
+    /// How many audience members must flag a question before it is hidden for review.
+    #[serde(rename = "flagThreshold", default = "default_flag_threshold")]
+    pub flag_threshold: u32,
+    #[serde(default)]
+    pub description: String,
+    /// When the presentation is planned to start, as shown to its audience. This is only
+    /// advertised, and unlike `opens_at` never opens the presentation to questions.
+    #[serde(rename = "scheduledStart", default)]
+    pub scheduled_start: Option<DateTime<Utc>>,
+    /// When the presentation is planned to end, as shown to its audience. This is only advertised,
+    /// and unlike `closes_at` never closes the presentation to questions.
+    #[serde(rename = "scheduledEnd", default)]
+    pub scheduled_end: Option<DateTime<Utc>>,
+    /// The language the presentation is given in, as a BCP 47 tag such as "en" or "pt-BR".
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Where slides or other material for the presentation can be found.
+    #[serde(rename = "externalLink", default)]
+    pub external_link: Option<String>,
+    /// When the presentation will next be opened to questions automatically, if ever. The
+    /// scheduler acts on this, whatever the advertised `scheduled_start` says.
+    #[serde(rename = "opensAt", default)]
+    pub opens_at: Option<DateTime<Utc>>,
+    /// When the presentation will next be closed to questions automatically, if ever.
+    #[serde(rename = "closesAt", default)]
+    pub closes_at: Option<DateTime<Utc>>,
+    /// The organization the presentation belongs to, whose admins may manage it.
+    #[serde(default)]
+    pub organization: Option<Id>,
+    /// How many days questions are kept for after they are asked, or forever if there is no limit.
+    #[serde(rename = "retentionDays", default)]
+    pub retention_days: Option<u32>,
+}
+
+/// What anyone may see of a presentation, which leaves out how it is run and who runs it.
+#[derive(Debug, Serialize)]
+pub struct PublicPresentation {
+    pub id: Id,
+    pub title: String,
+    pub description: String,
+    #[serde(rename = "scheduledStart")]
+    pub scheduled_start: Option<DateTime<Utc>>,
+    #[serde(rename = "scheduledEnd")]
+    pub scheduled_end: Option<DateTime<Utc>>,
+    pub language: Option<String>,
+    pub tags: Vec<String>,
+    #[serde(rename = "externalLink")]
+    pub external_link: Option<String>,
+    #[serde(rename = "isPrivate")]
+    pub is_private: bool,
+}
+
+/// The details of a presentation that describe it to its audience, which its presenter may change
+/// at any time.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct PresentationDetails {
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(rename = "scheduledStart", default)]
+    pub scheduled_start: Option<DateTime<Utc>>,
+    #[serde(rename = "scheduledEnd", default)]
+    pub scheduled_end: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(rename = "externalLink", default)]
+    pub external_link: Option<String>,
 }
 
 impl Presentation {
+    /// Construct a new presentation, open to questions, created by the presenter `creator`.
+    pub fn new(creator: Id, title: String) -> Self {
+        Presentation {
+            id: Id::generate(),
+            creator: creator,
+            title: title,
+            is_open_to_questions: true,
+            creation_date: Utc::now(),
+            join_code: Some(JoinCode::generate()),
+            is_private: false,
+            passcode_hash: None,
+            rate_limits: RateLimits::default(),
+            filters: vec![],
+            requires_approval: false,
+            flag_threshold: DEFAULT_FLAG_THRESHOLD,
+            description: String::new(),
+            scheduled_start: None,
+            scheduled_end: None,
+            language: None,
+            tags: vec![],
+            external_link: None,
+            opens_at: None,
+            closes_at: None,
+            organization: None,
+            retention_days: None,
+        }
+    }
+
     /// Create an instance of `Presentation` to pass to a search operation.
     pub fn search_parameter(id: Id) -> Self {
         Presentation {
@@ -23,6 +163,260 @@ impl Presentation {
             title: String::new(),
             is_open_to_questions: true,
             creation_date: Utc::now(),
+            join_code: None,
+            is_private: false,
+            passcode_hash: None,
+            rate_limits: RateLimits::default(),
+            filters: vec![],
+            requires_approval: false,
+            flag_threshold: DEFAULT_FLAG_THRESHOLD,
+            description: String::new(),
+            scheduled_start: None,
+            scheduled_end: None,
+            language: None,
+            tags: vec![],
+            external_link: None,
+            opens_at: None,
+            closes_at: None,
+            organization: None,
+            retention_days: None,
+        }
+    }
+
+    /// Construct a new presentation, created by `creator`, with the same details and settings as
+    /// this one. The copy is open to questions under a join code of its own, but is not scheduled
+    /// to open or close, and belongs to no organization.
+    pub fn copy_for(&self, creator: Id) -> Self {
+        let mut copy = Presentation::new(creator, self.title.clone());
+        copy.is_private = self.is_private;
+        copy.passcode_hash = self.passcode_hash.clone();
+        copy.rate_limits = self.rate_limits;
+        copy.filters = self.filters.clone();
+        copy.requires_approval = self.requires_approval;
+        copy.flag_threshold = self.flag_threshold;
+        copy.description = self.description.clone();
+        copy.language = self.language.clone();
+        copy.tags = self.tags.clone();
+        copy.external_link = self.external_link.clone();
+        copy.retention_days = self.retention_days;
+        copy
+    }
+
+    /// Produce what anyone, including audience members who haven't been let in yet, may see of
+    /// the presentation.
+    pub fn public_view(&self) -> PublicPresentation {
+        PublicPresentation {
+            id: self.id.clone(),
+            title: self.title.clone(),
+            description: self.description.clone(),
+            scheduled_start: self.scheduled_start,
+            scheduled_end: self.scheduled_end,
+            language: self.language.clone(),
+            tags: self.tags.clone(),
+            external_link: self.external_link.clone(),
+            is_private: self.is_private,
+        }
+    }
+
+    /// Open the presentation to questions, giving it a new join code.
+    pub fn open(&mut self) {
+        self.is_open_to_questions = true;
+        self.join_code = Some(JoinCode::generate());
+    }
+
+    /// Require audience members to enter `passcode` to take part, or make the presentation public
+    /// again if there is none.
+    pub fn set_passcode(&mut self, passcode: Option<&str>) {
+        self.passcode_hash = passcode.map(password::hash);
+        self.is_private = self.passcode_hash.is_some();
+    }
+
+    /// Check a passcode entered by an audience member. Public presentations accept any passcode.
+    pub fn passcode_matches(&self, passcode: &str) -> bool {
+        self.passcode_hash
+            .as_ref()
+            .map(|hashed| password::matches(passcode, hashed))
+            .unwrap_or(true)
+    }
+
+    /// The presentation's current details, such as to change some of them and validate the rest.
+    pub fn details(&self) -> PresentationDetails {
+        PresentationDetails {
+            title: self.title.clone(),
+            description: self.description.clone(),
+            scheduled_start: self.scheduled_start,
+            scheduled_end: self.scheduled_end,
+            language: self.language.clone(),
+            tags: self.tags.clone(),
+            external_link: self.external_link.clone(),
+        }
+    }
+
+    /// Replace the presentation's details with a validated set.
+    pub fn set_details(&mut self, details: PresentationDetails) {
+        self.title = details.title;
+        self.description = details.description;
+        self.scheduled_start = details.scheduled_start;
+        self.scheduled_end = details.scheduled_end;
+        self.language = details.language;
+        self.tags = details.tags;
+        self.external_link = details.external_link;
+    }
+
+    /// Place the presentation in an organization, taking on the organization's default settings
+    /// wherever its own settings are still at their defaults.
+    pub fn join_organization(&mut self, organization: Id, defaults: OrganizationDefaults) {
+        self.organization = Some(organization);
+        if self.rate_limits == RateLimits::default() {
+            self.rate_limits = defaults.rate_limits;
+        }
+        if self.filters.is_empty() {
+            self.filters = defaults.filters;
         }
+        if self.retention_days.is_none() {
+            self.retention_days = defaults.retention_days;
+        }
+    }
+
+    /// Keep questions for `retention_days` days after they are asked, or forever if there is no limit.
+    pub fn set_retention(&mut self, retention_days: Option<u32>) -> Result<(), String> {
+        validate_retention(retention_days)?;
+        self.retention_days = retention_days;
+        Ok(())
+    }
+
+    /// Determine whether flags from `sources` distinct sources are enough to hide a question until
+    /// it is reviewed.
+    pub fn flags_warrant_review(&self, sources: usize) -> bool {
+        sources >= self.flag_threshold.max(1) as usize
+    }
+
+    /// Close the presentation to questions. Its join code expires, and may be reused by another
+    /// presentation.
+    pub fn close(&mut self) {
+        self.is_open_to_questions = false;
+        self.join_code = None;
     }
+
+    /// Arrange for the presentation to be opened and closed to questions at the given times.
+    pub fn set_schedule(&mut self, opens_at: Option<DateTime<Utc>>, closes_at: Option<DateTime<Utc>>)
+        -> Result<(), String>
+    {
+        if let (Some(opens_at), Some(closes_at)) = (opens_at, closes_at) {
+            if closes_at <= opens_at {
+                return Err("Presentations must be scheduled to close after they open.".to_string());
+            }
+        }
+        self.opens_at = opens_at;
+        self.closes_at = closes_at;
+        Ok(())
+    }
+
+    /// Carry out any scheduled opening or closing that is due by `now`, and forget about it, so
+    /// that the presenter remains free to open or close the presentation by hand afterwards. When
+    /// both are due, whichever was scheduled later decides. Returns whether the presentation went
+    /// from open to closed or back.
+    pub fn apply_schedule(&mut self, now: DateTime<Utc>) -> bool {
+        let was_open = self.is_open_to_questions;
+        let opens_at = self.opens_at.filter(|&opens_at| opens_at <= now);
+        let closes_at = self.closes_at.filter(|&closes_at| closes_at <= now);
+        let open = match (opens_at, closes_at) {
+            (Some(opens_at), Some(closes_at)) => Some(opens_at > closes_at),
+            (Some(_), None)                   => Some(true),
+            (None, Some(_))                   => Some(false),
+            (None, None)                      => None,
+        };
+        match open {
+            Some(true) if !was_open => self.open(),
+            Some(false) if was_open => self.close(),
+            _                       => {},
+        }
+        if opens_at.is_some() {
+            self.opens_at = None;
+        }
+        if closes_at.is_some() {
+            self.closes_at = None;
+        }
+        self.is_open_to_questions != was_open
+    }
+}
+
+impl PresentationDetails {
+    /// Check that the details are acceptable, tidying them up along the way. Text is trimmed,
+    /// blank optional fields are dropped, and tags are lowercased with duplicates removed.
+    pub fn validate(self) -> Result<Self, String> {
+        let title = self.title.trim().to_string();
+        if title.is_empty() || title.chars().count() > MAX_TITLE_LENGTH {
+            return Err(format!("Titles must have between 1 and {} characters.", MAX_TITLE_LENGTH));
+        }
+        let description = self.description.trim().to_string();
+        if description.chars().count() > MAX_DESCRIPTION_LENGTH {
+            return Err(format!("Descriptions may have at most {} characters.", MAX_DESCRIPTION_LENGTH));
+        }
+        if let (Some(start), Some(end)) = (self.scheduled_start, self.scheduled_end) {
+            if end <= start {
+                return Err("Presentations must be scheduled to end after they start.".to_string());
+            }
+        }
+        let language = non_blank(self.language);
+        if !language.as_ref().map(|language| is_language_tag(language)).unwrap_or(true) {
+            return Err("Languages must be given as BCP 47 tags, such as \"en\" or \"pt-BR\".".to_string());
+        }
+        let mut tags: Vec<String> = vec![];
+        for tag in self.tags.iter().map(|tag| tag.trim().to_lowercase()).filter(|tag| !tag.is_empty()) {
+            if tag.chars().count() > MAX_TAG_LENGTH || !tag.chars().all(|c| c.is_alphanumeric() || c == '-') {
+                return Err(format!("Tags may only have up to {} letters, digits and hyphens.", MAX_TAG_LENGTH));
+            }
+            if !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        }
+        if tags.len() > MAX_TAGS {
+            return Err(format!("Presentations may have at most {} tags.", MAX_TAGS));
+        }
+        let external_link = non_blank(self.external_link);
+        let is_web_link = |link: &String| Url::parse(link)
+            .map(|url| url.scheme() == "http" || url.scheme() == "https")
+            .unwrap_or(false);
+        if !external_link.as_ref().map(is_web_link).unwrap_or(true) {
+            return Err("External links must be http or https URLs.".to_string());
+        }
+        Ok(PresentationDetails {
+            title: title,
+            description: description,
+            scheduled_start: self.scheduled_start,
+            scheduled_end: self.scheduled_end,
+            language: language,
+            tags: tags,
+            external_link: external_link,
+        })
+    }
+}
+
+/// Trim an optional piece of text, treating blank text as missing.
+fn non_blank(text: Option<String>) -> Option<String> {
+    text.map(|text| text.trim().to_string()).filter(|text| !text.is_empty())
+}
+
+/// Determine whether `language` looks like a BCP 47 language tag: a two or three letter language,
+/// followed by any number of hyphenated subtags of up to eight letters and digits.
+fn is_language_tag(language: &str) -> bool {
+    let mut subtags = language.split('-');
+    let primary = subtags.next().unwrap_or("");
+    (primary.len() == 2 || primary.len() == 3) && primary.chars().all(|c| c.is_ascii_alphabetic())
+        && subtags.all(|subtag| !subtag.is_empty() && subtag.len() <= 8
+                           && subtag.chars().all(|c| c.is_ascii_alphanumeric()))
+}
+
+/// Check that questions could be kept for `retention_days` days.
+pub fn validate_retention(retention_days: Option<u32>) -> Result<(), String> {
+    match retention_days {
+        Some(days) if days == 0 || days > MAX_RETENTION_DAYS =>
+            Err(format!("Questions may be kept for between 1 and {} days.", MAX_RETENTION_DAYS)),
+        _ => Ok(()),
+    }
+}
+
+fn default_flag_threshold() -> u32 {
+    DEFAULT_FLAG_THRESHOLD
 }
@@ -1,6 +1,6 @@
 use chrono::prelude::*;
-use password_hash::scrypt::{ScryptParams, scrypt_simple, scrypt_check};
 
+use auth::{password, totp};
 use models::Id;
 
 
@@ -12,17 +12,49 @@ pub struct Presenter {
     pub password_hash: String,
     #[serde(rename = "joinDate")]
     pub join_date: DateTime<Utc>,
+    #[serde(rename = "emailVerified")]
+    pub email_verified: bool,
+    /// The base32-encoded TOTP secret, present once the presenter has begun enrolling.
+    #[serde(rename = "totpSecret")]
+    pub totp_secret: Option<String>,
+    /// Whether enrollment has been confirmed, and a second factor is required to log in.
+    #[serde(rename = "totpEnabled")]
+    pub totp_enabled: bool,
+    /// The time step of the last TOTP code accepted, which may not be used again.
+    #[serde(rename = "totpLastStep")]
+    pub totp_last_step: Option<i64>,
+    /// Digests of the recovery codes that have not yet been used.
+    #[serde(rename = "recoveryCodes")]
+    pub recovery_codes: Vec<String>,
 }
 
 impl Presenter {
     /// Construct a new `Presenter`, which is effectively a user account.
     pub fn new(email: String, password: String) -> Presenter {
-        let params = ScryptParams::new(14, 8, 1);
-        let pwd_hash = scrypt_simple(&password, &params).unwrap();
         Presenter {
             email_address: Id(email),
-            password_hash: pwd_hash,
+            password_hash: password::hash(&password),
             join_date: Utc::now(),
+            email_verified: false,
+            totp_secret: None,
+            totp_enabled: false,
+            totp_last_step: None,
+            recovery_codes: vec![],
+        }
+    }
+
+    /// Construct a presenter who signs in through an external identity provider, and so has no
+    /// password of their own. The provider is trusted to have verified the email address.
+    pub fn from_identity_provider(email: String) -> Presenter {
+        Presenter {
+            email_address: Id(email),
+            password_hash: String::new(),
+            join_date: Utc::now(),
+            email_verified: true,
+            totp_secret: None,
+            totp_enabled: false,
+            totp_last_step: None,
+            recovery_codes: vec![],
         }
     }
 
@@ -33,11 +65,63 @@ impl Presenter {
             email_address: email_address,
             password_hash: String::new(),
             join_date: Utc::now(),
+            email_verified: false,
+            totp_secret: None,
+            totp_enabled: false,
+            totp_last_step: None,
+            recovery_codes: vec![],
         }
     }
 
     /// Check if a given password matches the hashed password stored with a registered presenter.
     pub fn password_matches(&self, password: &str) -> bool {
-        scrypt_check(password, &self.password_hash).unwrap_or(false)
+        password::matches(password, &self.password_hash)
+    }
+
+    /// Replace the presenter's password, such as after they have redeemed a password reset token.
+    pub fn set_password(&mut self, password: &str) {
+        self.password_hash = password::hash(password);
+    }
+
+    /// Begin two-factor enrollment with a fresh secret, returning the plaintext recovery codes.
+    /// Two-factor authentication is not required until the enrollment is confirmed.
+    pub fn begin_totp_enrollment(&mut self) -> Vec<String> {
+        let codes = totp::generate_recovery_codes();
+        self.totp_secret = Some(totp::generate_secret());
+        self.totp_enabled = false;
+        self.totp_last_step = None;
+        self.recovery_codes = codes.iter().map(|code| totp::recovery_code_digest(code)).collect();
+        codes
+    }
+
+    /// Finish two-factor enrollment with a code from the presenter's authenticator, proving they
+    /// have stored the secret.
+    pub fn confirm_totp_enrollment(&mut self, code: &str, now: DateTime<Utc>) -> bool {
+        self.totp_enabled = self.totp_code_matches(code, now);
+        self.totp_enabled
+    }
+
+    /// Check a second factor, which is either a TOTP code or one of the presenter's recovery codes.
+    /// A recovery code that matches is removed so it cannot be used again, and a TOTP code can't be
+    /// used again either, nor can any code from before it.
+    pub fn verify_second_factor(&mut self, code: &str, now: DateTime<Utc>) -> bool {
+        if self.totp_code_matches(code, now) {
+            return true;
+        }
+        let digest = totp::recovery_code_digest(code);
+        let before = self.recovery_codes.len();
+        self.recovery_codes.retain(|stored| *stored != digest);
+        self.recovery_codes.len() < before
+    }
+
+    /// Check a TOTP code, recording its time step if it matches.
+    fn totp_code_matches(&mut self, code: &str, now: DateTime<Utc>) -> bool {
+        let step = self.totp_secret
+            .as_ref()
+            .and_then(|secret| totp::verify_step(secret, code, now, self.totp_last_step));
+        if step.is_some() {
+            self.totp_last_step = step;
+        }
+        step.is_some()
     }
 }
@@ -3,6 +3,19 @@ use chrono::prelude::*;
 use models::Id;
 
 
+/// Whether a question may be shown to the audience.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum QuestionStatus {
+    #[serde(rename = "approved")]
+    Approved,
+    /// Held back until a presenter has reviewed it.
+    #[serde(rename = "pending")]
+    Pending,
+    /// Turned down by a presenter, and never shown to the audience.
+    #[serde(rename = "rejected")]
+    Rejected,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Question {
     pub id: Id,
@@ -12,18 +25,53 @@ pub struct Question {
     pub answered: bool,
     #[serde(rename = "timeAsked")]
     pub ask_date: DateTime<Utc>,
+    pub status: QuestionStatus,
+    /// Why the question was held back for review, if it was.
+    #[serde(rename = "reviewReason")]
+    pub review_reason: Option<String>,
+    /// When a presenter approved or rejected the question, if they have.
+    #[serde(rename = "reviewedAt")]
+    pub reviewed_at: Option<DateTime<Utc>>,
+    /// The question that this one was merged into as a duplicate, if it was.
+    #[serde(rename = "mergedInto")]
+    pub merged_into: Option<Id>,
+}
+
+impl QuestionStatus {
+    /// The name under which the status is persisted.
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            QuestionStatus::Approved => "approved",
+            QuestionStatus::Pending  => "pending",
+            QuestionStatus::Rejected => "rejected",
+        }
+    }
+
+    /// Parse a status from the name under which it is persisted.
+    pub fn from_str(name: &str) -> Option<Self> {
+        match name {
+            "approved" => Some(QuestionStatus::Approved),
+            "pending"  => Some(QuestionStatus::Pending),
+            "rejected" => Some(QuestionStatus::Rejected),
+            _          => None,
+        }
+    }
 }
 
 impl Question {
     /// Construct a new question being asked during a presentation.
     pub fn new(presentation: Id, question_text: String) -> Self {
         Question {
-            id: Id(String::new()),
+            id: Id::generate(),
             presentation: presentation,
             text: question_text,
             nods: 0,
             answered: false,
             ask_date: Utc::now(),
+            status: QuestionStatus::Approved,
+            review_reason: None,
+            reviewed_at: None,
+            merged_into: None,
         }
     }
 
@@ -36,6 +84,28 @@ impl Question {
             nods: 0,
             answered: false,
             ask_date: Utc::now(),
+            status: QuestionStatus::Approved,
+            review_reason: None,
+            reviewed_at: None,
+            merged_into: None,
         }
     }
+
+    /// Hold the question back from the audience until a presenter has reviewed it.
+    pub fn hold_for_review(&mut self, reason: String) {
+        self.status = QuestionStatus::Pending;
+        self.review_reason = Some(reason);
+    }
+
+    /// Record a presenter's decision to approve or reject the question.
+    pub fn review(&mut self, decision: QuestionStatus) {
+        self.status = decision;
+        self.reviewed_at = Some(Utc::now());
+    }
+
+    /// Whether the question may be shown to the audience, which requires it to be approved and
+    /// not merged into another question.
+    pub fn is_visible(&self) -> bool {
+        self.status == QuestionStatus::Approved && self.merged_into.is_none()
+    }
 }
@@ -0,0 +1,99 @@
+use chrono::prelude::*;
+
+
+/// How many requests of one kind a client may make in a burst, and how quickly that allowance is
+/// restored.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Budget {
+    pub capacity: u32,
+    #[serde(rename = "perMinute")]
+    pub per_minute: u32,
+}
+
+/// The budgets each audience member has for taking part in a presentation.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RateLimits {
+    pub ask: Budget,
+    pub nod: Budget,
+}
+
+/// The state of a client's allowance for one kind of request, refilled continuously over time.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TokenBucket {
+    pub key: String,
+    pub tokens: f64,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Budget {
+    pub fn new(capacity: u32, per_minute: u32) -> Self {
+        Budget {
+            capacity: capacity,
+            per_minute: per_minute,
+        }
+    }
+
+    /// Produce a budget large enough to be shared by `clients` clients each spending this one.
+    pub fn shared_by(&self, clients: u32) -> Self {
+        Budget {
+            capacity: self.capacity.saturating_mul(clients),
+            per_minute: self.per_minute.saturating_mul(clients),
+        }
+    }
+
+    /// Determine whether the budget lets anyone make a request at all.
+    pub fn is_valid(&self) -> bool {
+        self.capacity > 0 && self.per_minute > 0
+    }
+}
+
+impl Default for RateLimits {
+    fn default() -> Self {
+        RateLimits {
+            ask: Budget::new(5, 2),
+            nod: Budget::new(30, 30),
+        }
+    }
+}
+
+impl RateLimits {
+    pub fn is_valid(&self) -> bool {
+        self.ask.is_valid() && self.nod.is_valid()
+    }
+}
+
+impl TokenBucket {
+    /// Construct a bucket holding the whole of `budget`, for a client that hasn't been seen before.
+    pub fn full(key: String, budget: Budget, now: DateTime<Utc>) -> Self {
+        TokenBucket {
+            key: key,
+            tokens: budget.capacity as f64,
+            updated_at: now,
+        }
+    }
+
+    /// Create an instance of `TokenBucket` to pass to a search operation.
+    pub fn search_parameter(key: String) -> Self {
+        TokenBucket {
+            key: key,
+            tokens: 0.0,
+            updated_at: Utc::now(),
+        }
+    }
+
+    /// Spend one token from the bucket at time `now`, after refilling it according to `budget`.
+    /// When the bucket is empty, the number of seconds until a token is available is returned.
+    pub fn take(&mut self, budget: Budget, now: DateTime<Utc>) -> Result<(), u64> {
+        let elapsed_millis = now.signed_duration_since(self.updated_at).num_milliseconds().max(0) as f64;
+        let refilled = elapsed_millis * budget.per_minute as f64 / 60000.0;
+        self.tokens = (self.tokens + refilled).min(budget.capacity as f64);
+        self.updated_at = now;
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            return Ok(());
+        }
+        let wait = (1.0 - self.tokens) * 60.0 / budget.per_minute.max(1) as f64;
+        Err(wait.ceil().max(1.0) as u64)
+    }
+}
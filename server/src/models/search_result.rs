@@ -0,0 +1,35 @@
+use models::Id;
+
+
+/// A question or answer matching a full-text search, with the matching words highlighted.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SearchResult {
+    /// Either "question" or "answer".
+    pub kind: String,
+    pub id: Id,
+    /// The question that matched, or that the matching answer belongs to.
+    pub question: Id,
+    /// An excerpt of the matching text, with matches wrapped in `<mark>` tags.
+    pub snippet: String,
+    /// How well the text matches, where lower is better.
+    pub rank: f64,
+}
+
+impl SearchResult {
+    /// Turn text typed into a search box into a full-text query. Words are matched individually
+    /// and quoted phrases as a whole, without exposing the rest of the query syntax.
+    pub fn query(text: &str) -> Option<String> {
+        let terms: Vec<String> = text
+            .split('"')
+            .enumerate()
+            .flat_map(|(index, part)| if index % 2 == 1 {
+                vec![part.trim().to_string()]
+            } else {
+                part.split_whitespace().map(|word| word.to_string()).collect()
+            })
+            .filter(|term| term.chars().any(|c| c.is_alphanumeric()))
+            .map(|term| format!("\"{}\"", term))
+            .collect();
+        if terms.is_empty() { None } else { Some(terms.join(" ")) }
+    }
+}
@@ -2,10 +2,15 @@ use base64;
 use chrono::prelude::*;
 use rand;
 use rand::Rng;
+use ring::digest::{digest, SHA256};
 
 use models::{Id, Presenter};
 
 
+/// A presenter's login session.
+///
+/// The plaintext `token` is only ever known when the session is first created and when a client
+/// presents it back to us. Only its SHA-256 digest is persisted.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Session {
     pub token: Id,
@@ -34,4 +39,9 @@ impl Session {
             created_at: Utc::now(),
         }
     }
+
+    /// The digest of the session token, which is what gets stored in place of the token itself.
+    pub fn token_digest(&self) -> Vec<u8> {
+        digest(&SHA256, (self.token.0).as_bytes()).as_ref().to_vec()
+    }
 }
@@ -0,0 +1,49 @@
+use chrono::prelude::*;
+
+use models::{Presentation, Role};
+
+
+/// How many questions were asked during a presentation, and how many of them were answered.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct QuestionCounts {
+    pub questions: u32,
+    pub answered: u32,
+}
+
+/// How many questions asked during a presentation received a particular number of nods.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct NodCount {
+    pub nods: u32,
+    pub questions: u32,
+}
+
+/// How many questions were asked during a span of time, starting at `start`.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TimeBucket {
+    pub start: DateTime<Utc>,
+    pub questions: u32,
+}
+
+/// A summary of what has happened in one of a presenter's presentations, for their dashboard.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PresentationActivity {
+    pub presentation: Presentation,
+    /// The role that the presenter whose dashboard it is has in the presentation.
+    pub role: Role,
+    pub questions: u32,
+    /// How many questions that have not been rejected are still waiting for an answer.
+    pub unanswered: u32,
+    #[serde(rename = "lastActivity")]
+    pub last_activity: DateTime<Utc>,
+}
+
+impl QuestionCounts {
+    /// The fraction of questions that were answered, which is zero if none were asked.
+    pub fn answered_ratio(&self) -> f64 {
+        if self.questions == 0 {
+            0.0
+        } else {
+            self.answered as f64 / self.questions as f64
+        }
+    }
+}
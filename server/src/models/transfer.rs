@@ -0,0 +1,50 @@
+use chrono::Duration;
+use chrono::prelude::*;
+
+use models::Id;
+
+
+/// How long an offer to hand over a presentation remains open after it is made.
+const TRANSFER_LIFETIME_DAYS: i64 = 14;
+
+
+/// An offer to hand a presentation over to another presenter, who becomes its creator once they
+/// accept. A presentation has at most one open offer at a time.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct OwnershipTransfer {
+    pub presentation: Id,
+    /// The presenter who created the presentation when the offer was made.
+    pub from: Id,
+    /// The email address of the presenter the presentation is offered to.
+    pub to: Id,
+    #[serde(rename = "expiresAt")]
+    pub expires_at: DateTime<Utc>,
+}
+
+impl OwnershipTransfer {
+    /// Offer `presentation`, created by `from`, to the presenter with the email address `to`.
+    pub fn new(presentation: Id, from: Id, to: Id) -> Self {
+        OwnershipTransfer {
+            presentation: presentation,
+            from: from,
+            to: to,
+            expires_at: Utc::now() + Duration::days(TRANSFER_LIFETIME_DAYS),
+        }
+    }
+
+    /// Construct a transfer identifying the open offer of a presentation, to pass to a search or
+    /// delete operation.
+    pub fn search_parameter(presentation: Id) -> Self {
+        OwnershipTransfer {
+            presentation: presentation,
+            from: Id(String::new()),
+            to: Id(String::new()),
+            expires_at: Utc::now(),
+        }
+    }
+
+    /// Determine whether the presenter `presenter` may accept the offer at time `now`.
+    pub fn may_be_accepted_by(&self, presenter: &Id, now: DateTime<Utc>) -> bool {
+        now < self.expires_at && self.to.0.to_lowercase() == presenter.0.to_lowercase()
+    }
+}
@@ -0,0 +1,63 @@
+//! Rendering of QR codes as SVG and PNG images, for audience members to scan with their phones.
+
+use png;
+use png::HasParameters;
+use qrcode::{Color, QrCode};
+
+
+/// The width, in modules, of the blank margin that scanners need around a code.
+const QUIET_ZONE: usize = 4;
+
+/// The size, in pixels, of each module in PNG renderings.
+const PNG_MODULE_SIZE: usize = 8;
+
+
+/// Render `data` as a QR code in an SVG document.
+pub fn svg(data: &str) -> Result<String, String> {
+    let (width, modules) = encode(data)?;
+    let size = width + 2 * QUIET_ZONE;
+    let mut path = String::new();
+    for (index, _) in modules.iter().enumerate().filter(|&(_, dark)| *dark) {
+        let (x, y) = (index % width + QUIET_ZONE, index / width + QUIET_ZONE);
+        path.push_str(&format!("M{},{}h1v1h-1z", x, y));
+    }
+    Ok(format!(
+        "<?xml version=\"1.0\" standalone=\"yes\"?>\
+         <svg xmlns=\"http://www.w3.org/2000/svg\" version=\"1.1\" viewBox=\"0 0 {0} {0}\" \
+         shape-rendering=\"crispEdges\">\
+         <rect width=\"{0}\" height=\"{0}\" fill=\"#fff\"/>\
+         <path d=\"{1}\" fill=\"#000\"/>\
+         </svg>",
+        size, path))
+}
+
+/// Render `data` as a QR code in a grayscale PNG image.
+pub fn png(data: &str) -> Result<Vec<u8>, String> {
+    let (width, modules) = encode(data)?;
+    let size = (width + 2 * QUIET_ZONE) * PNG_MODULE_SIZE;
+    let mut pixels = vec![255u8; size * size];
+    for (index, _) in modules.iter().enumerate().filter(|&(_, dark)| *dark) {
+        let (x, y) = (index % width + QUIET_ZONE, index / width + QUIET_ZONE);
+        for row in y * PNG_MODULE_SIZE..(y + 1) * PNG_MODULE_SIZE {
+            let start = row * size + x * PNG_MODULE_SIZE;
+            for pixel in pixels[start..start + PNG_MODULE_SIZE].iter_mut() {
+                *pixel = 0;
+            }
+        }
+    }
+    let mut image = Vec::new();
+    {
+        let mut encoder = png::Encoder::new(&mut image, size as u32, size as u32);
+        encoder.set(png::ColorType::Grayscale).set(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().map_err(|err| err.to_string())?;
+        writer.write_image_data(&pixels).map_err(|err| err.to_string())?;
+    }
+    Ok(image)
+}
+
+/// Encode `data` into a QR code, returning its width and whether each module, row by row, is dark.
+fn encode(data: &str) -> Result<(usize, Vec<bool>), String> {
+    let code = QrCode::new(data.as_bytes()).map_err(|err| err.to_string())?;
+    let modules = code.to_colors().into_iter().map(|color| color == Color::Dark).collect();
+    Ok((code.width(), modules))
+}
@@ -0,0 +1,122 @@
+//! Storage for the token buckets that limit how often audience members may ask and nod to
+//! questions.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use chrono::Duration;
+use chrono::prelude::*;
+
+use capabilities::{Capability, Save, Search};
+use models::{Budget, TokenBucket};
+
+
+/// The number of buckets kept in memory before idle ones are discarded.
+const MAX_BUCKETS_IN_MEMORY: usize = 10000;
+
+/// How long a bucket must go unused before it may be discarded. Buckets idle this long will have
+/// refilled under any reasonable budget, so forgetting them doesn't change any outcome.
+const IDLE_BUCKET_MINUTES: i64 = 60;
+
+/// How often, at most, idle buckets are looked for once there are too many in memory, so that
+/// busy servers don't scan every bucket on every request.
+const SWEEP_INTERVAL_MINUTES: i64 = 1;
+
+
+/// Represents "the ability to keep track of token buckets," independent of where they are kept.
+pub trait BucketStore {
+    /// Spend a token from the bucket stored under `key`, creating it full if it doesn't exist yet.
+    /// The inner result holds the number of seconds to wait when the bucket is empty.
+    fn take(&self, key: &str, budget: Budget, now: DateTime<Utc>) -> Result<Result<(), u64>, String>;
+}
+
+impl<S> BucketStore for Arc<S>
+    where S: BucketStore + ?Sized
+{
+    fn take(&self, key: &str, budget: Budget, now: DateTime<Utc>) -> Result<Result<(), u64>, String> {
+        (**self).take(key, budget, now)
+    }
+}
+
+/// Keeps buckets in memory, so that limits are forgotten when the server restarts.
+pub struct MemoryBuckets {
+    state: Mutex<MemoryBucketsState>,
+}
+
+struct MemoryBucketsState {
+    buckets: HashMap<String, TokenBucket>,
+    last_swept: Option<DateTime<Utc>>,
+}
+
+impl MemoryBuckets {
+    pub fn new() -> Self {
+        MemoryBuckets {
+            state: Mutex::new(MemoryBucketsState {
+                buckets: HashMap::new(),
+                last_swept: None,
+            }),
+        }
+    }
+
+    /// The number of buckets currently kept in memory.
+    pub fn bucket_count(&self) -> usize {
+        self.state.lock().map(|state| state.buckets.len()).unwrap_or(0)
+    }
+}
+
+impl MemoryBucketsState {
+    /// Discard idle buckets if there are too many and they haven't been looked through recently.
+    fn sweep(&mut self, now: DateTime<Utc>) {
+        let due = self.last_swept
+            .map(|swept| now - swept >= Duration::minutes(SWEEP_INTERVAL_MINUTES))
+            .unwrap_or(true);
+        if self.buckets.len() < MAX_BUCKETS_IN_MEMORY || !due {
+            return;
+        }
+        let cutoff = now - Duration::minutes(IDLE_BUCKET_MINUTES);
+        self.buckets.retain(|_, bucket| bucket.updated_at > cutoff);
+        self.last_swept = Some(now);
+    }
+}
+
+impl BucketStore for MemoryBuckets {
+    fn take(&self, key: &str, budget: Budget, now: DateTime<Utc>) -> Result<Result<(), u64>, String> {
+        let mut state = self.state.lock().map_err(|_| "Rate limiter unavailable.".to_string())?;
+        state.sweep(now);
+        let bucket = state.buckets
+            .entry(key.to_string())
+            .or_insert_with(|| TokenBucket::full(key.to_string(), budget, now));
+        Ok(bucket.take(budget, now))
+    }
+}
+
+/// Keeps buckets in the database, so that limits survive the server restarting.
+pub struct PersistentBuckets<DB> {
+    database: DB,
+    lock: Mutex<()>,
+}
+
+impl<DB> PersistentBuckets<DB> {
+    pub fn new(db: DB) -> Self {
+        PersistentBuckets {
+            database: db,
+            lock: Mutex::new(()),
+        }
+    }
+}
+
+impl<DB> BucketStore for PersistentBuckets<DB>
+    where DB: Capability<Search<TokenBucket>, Data = TokenBucket, Error = String>
+            + Capability<Save<TokenBucket>, Data = TokenBucket, Error = String>
+{
+    /// Reading and writing a bucket happen under a lock, so that concurrent requests can't both
+    /// spend the same token.
+    fn take(&self, key: &str, budget: Budget, now: DateTime<Utc>) -> Result<Result<(), u64>, String> {
+        let _guard = self.lock.lock().map_err(|_| "Rate limiter unavailable.".to_string())?;
+        let mut bucket = self.database
+            .perform(Search(TokenBucket::search_parameter(key.to_string())))
+            .unwrap_or_else(|_| TokenBucket::full(key.to_string(), budget, now));
+        let decision = bucket.take(budget, now);
+        self.database.perform(Save(bucket)).map(|_| decision)
+    }
+}
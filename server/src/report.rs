@@ -0,0 +1,174 @@
+//! Rendering a printable PDF record of the questions asked during a presentation. Fonts are
+//! compiled into the server, so reports can be produced without access to system fonts or the
+//! network.
+
+use std::io::{BufWriter, Cursor};
+
+use printpdf::{IndirectFontRef, Mm, PdfDocument, PdfDocumentReference, PdfLayerReference};
+
+use export::ExportedQuestion;
+use models::Presentation;
+
+
+const REGULAR_FONT: &'static [u8] = include_bytes!("../assets/fonts/DejaVuSans.ttf");
+const BOLD_FONT: &'static [u8] = include_bytes!("../assets/fonts/DejaVuSans-Bold.ttf");
+
+/// The dimensions of an A4 page, in millimetres.
+const PAGE_WIDTH: f64 = 210.0;
+const PAGE_HEIGHT: f64 = 297.0;
+const MARGIN: f64 = 20.0;
+
+/// Millimetres per typographic point.
+const MM_PER_POINT: f64 = 0.3528;
+
+/// The average width of a character as a fraction of the font size, used to decide where to wrap.
+const AVERAGE_CHARACTER_WIDTH: f64 = 0.55;
+
+const TITLE_SIZE: i64 = 20;
+const HEADING_SIZE: i64 = 12;
+const BODY_SIZE: i64 = 10;
+
+
+/// Figures summarizing the questions asked during a presentation.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Summary {
+    pub questions: usize,
+    pub answered: usize,
+    pub nods: u64,
+}
+
+/// Lays text out from the top of each page down, starting new pages as they fill up.
+struct PageWriter {
+    document: PdfDocumentReference,
+    layer: PdfLayerReference,
+    regular: IndirectFontRef,
+    bold: IndirectFontRef,
+    y: f64,
+}
+
+impl Summary {
+    pub fn of(entries: &[ExportedQuestion]) -> Self {
+        Summary {
+            questions: entries.len(),
+            answered: entries.iter().filter(|entry| entry.question.answered).count(),
+            nods: entries.iter().map(|entry| entry.question.nods as u64).sum(),
+        }
+    }
+}
+
+impl PageWriter {
+    fn new(title: &str) -> Result<Self, String> {
+        let (document, page, layer) = PdfDocument::new(title, Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Text");
+        let regular = document.add_external_font(Cursor::new(REGULAR_FONT)).map_err(|err| err.to_string())?;
+        let bold = document.add_external_font(Cursor::new(BOLD_FONT)).map_err(|err| err.to_string())?;
+        let layer = document.get_page(page).get_layer(layer);
+        Ok(PageWriter {
+            document: document,
+            layer: layer,
+            regular: regular,
+            bold: bold,
+            y: PAGE_HEIGHT - MARGIN,
+        })
+    }
+
+    /// Write `text` wrapped to the width of the page, indented from the left margin by `indent`
+    /// millimetres.
+    fn paragraph(&mut self, text: &str, size: i64, bold: bool, indent: f64) {
+        let line_height = size as f64 * MM_PER_POINT * 1.4;
+        let character_width = size as f64 * MM_PER_POINT * AVERAGE_CHARACTER_WIDTH;
+        let max_characters = ((PAGE_WIDTH - 2.0 * MARGIN - indent) / character_width) as usize;
+        for line in wrap(text, max_characters) {
+            if self.y - line_height < MARGIN {
+                self.new_page();
+            }
+            self.y -= line_height;
+            let font = if bold { &self.bold } else { &self.regular };
+            self.layer.use_text(line, size, Mm(MARGIN + indent), Mm(self.y), font);
+        }
+    }
+
+    fn gap(&mut self, height: f64) {
+        self.y -= height;
+    }
+
+    fn new_page(&mut self) {
+        let (page, layer) = self.document.add_page(Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Text");
+        self.layer = self.document.get_page(page).get_layer(layer);
+        self.y = PAGE_HEIGHT - MARGIN;
+    }
+
+    fn finish(self) -> Result<Vec<u8>, String> {
+        let mut bytes = vec![];
+        {
+            let mut writer = BufWriter::new(&mut bytes);
+            self.document.save(&mut writer).map_err(|err| err.to_string())?;
+        }
+        Ok(bytes)
+    }
+}
+
+/// Render a report of `presentation` listing the questions in `entries` from most to least nodded
+/// to, each followed by its answers.
+pub fn render(presentation: &Presentation, entries: &[ExportedQuestion]) -> Result<Vec<u8>, String> {
+    let summary = Summary::of(entries);
+    let mut sorted: Vec<&ExportedQuestion> = entries.iter().collect();
+    sorted.sort_by(|a, b| b.question.nods.cmp(&a.question.nods).then(a.question.ask_date.cmp(&b.question.ask_date)));
+
+    let mut page = PageWriter::new(&presentation.title)?;
+    page.paragraph(&presentation.title, TITLE_SIZE, true, 0.0);
+    page.paragraph(&presentation.creation_date.format("%A %e %B %Y").to_string(), BODY_SIZE, false, 0.0);
+    page.gap(4.0);
+    let answered_percent = if summary.questions == 0 { 0 } else { summary.answered * 100 / summary.questions };
+    page.paragraph(
+        &format!("{} questions · {} answered ({}%) · {} nods",
+                 summary.questions, summary.answered, answered_percent, summary.nods),
+        BODY_SIZE, true, 0.0);
+    page.gap(6.0);
+
+    for entry in sorted {
+        let nods = entry.question.nods;
+        page.paragraph(&entry.question.text, HEADING_SIZE, true, 0.0);
+        page.paragraph(
+            &format!("{} nod{} · asked {}", nods, if nods == 1 { "" } else { "s" },
+                     entry.question.ask_date.format("%H:%M")),
+            BODY_SIZE, false, 0.0);
+        for answer in entry.answers.iter() {
+            page.gap(1.5);
+            page.paragraph(&answer.text, BODY_SIZE, false, 6.0);
+        }
+        page.gap(5.0);
+    }
+    page.finish()
+}
+
+/// Break text into lines of at most `max_characters` characters, keeping existing line breaks and
+/// splitting words too long to fit on a line of their own.
+fn wrap(text: &str, max_characters: usize) -> Vec<String> {
+    let max_characters = max_characters.max(1);
+    let mut lines = vec![];
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        for word in paragraph.split_whitespace() {
+            let mut word: Vec<char> = word.chars().collect();
+            while word.len() > max_characters {
+                if !line.is_empty() {
+                    lines.push(line);
+                    line = String::new();
+                }
+                lines.push(word.drain(..max_characters).collect());
+            }
+            let word: String = word.into_iter().collect();
+            let line_length = line.chars().count();
+            if line_length > 0 && line_length + 1 + word.chars().count() > max_characters {
+                lines.push(line);
+                line = String::new();
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(&word);
+        }
+        lines.push(line);
+    }
+    lines
+}
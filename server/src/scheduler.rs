@@ -0,0 +1,123 @@
+//! Opening and closing presentations to questions at the times their presenters scheduled, and
+//! deleting questions once their presentations no longer keep them.
+//!
+//! Schedules are kept with the presentations in the database, and each is forgotten once it has
+//! been carried out, so the scheduler keeps no state of its own. After a restart, the first tick
+//! carries out everything that fell due while the server was down.
+
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use chrono::prelude::*;
+
+use capabilities::{Capability, Delete, FindAll, Update};
+use capabilities::sqlite::{DueSchedules, ExpiredQuestions};
+use events::{EventKind, EventSink};
+use models::{Id, Presentation};
+
+
+/// How often the scheduler checks for presentations that are due to be opened or closed.
+const TICK_INTERVAL_MS: u64 = 1000;
+
+/// How many ticks pass between each deletion of the questions that are no longer kept.
+const TICKS_PER_EXPIRY: u64 = 60 * 60;
+
+
+/// Represents "the ability to tell the time," so that the scheduler can be tested without waiting.
+pub trait Clock {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+impl<C> Clock for Arc<C>
+    where C: Clock + ?Sized
+{
+    fn now(&self) -> DateTime<Utc> {
+        (**self).now()
+    }
+}
+
+/// Tells the time by the system clock.
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// Opens and closes presentations to questions when they are scheduled to be, announcing each
+/// change through `events`.
+pub struct Scheduler<DB, C, E> {
+    database: DB,
+    clock: C,
+    events: E,
+}
+
+impl<DB, C, E> Scheduler<DB, C, E>
+    where DB: Capability<FindAll<DueSchedules>, Data = Vec<Presentation>, Error = String>
+            + Capability<Update<Presentation>, Data = Presentation, Error = String>
+            + Capability<Delete<ExpiredQuestions>, Data = usize, Error = String>,
+          C: Clock,
+          E: EventSink
+{
+    pub fn new(db: DB, clock: C, events: E) -> Self {
+        Scheduler {
+            database: db,
+            clock: clock,
+            events: events,
+        }
+    }
+
+    /// Carry out every scheduled opening and closing that is due, returning the presentations
+    /// that were opened or closed as a result.
+    ///
+    /// A presentation that can't be updated doesn't hold back the others that are due. Its
+    /// schedule is left as it was, so it is tried again on the next tick.
+    pub fn tick(&self) -> Result<Vec<Id>, String> {
+        let now = self.clock.now();
+        let due = self.database.perform(FindAll(DueSchedules {
+            now: now,
+        }))?;
+        let mut changed = vec![];
+        for mut presentation in due {
+            let id = presentation.id.clone();
+            let flipped = presentation.apply_schedule(now);
+            let presentation = match self.database.perform(Update(presentation)) {
+                Ok(presentation) => presentation,
+                Err(err) => {
+                    eprintln!("Failed to carry out the schedule of presentation {}: {}", id.0, err);
+                    continue;
+                },
+            };
+            if flipped {
+                let kind = if presentation.is_open_to_questions { EventKind::Opened } else { EventKind::Closed };
+                self.events.emit(presentation.id.clone(), kind, now);
+                changed.push(presentation.id);
+            }
+        }
+        Ok(changed)
+    }
+
+    /// Delete every question that has been kept for as long as its presentation keeps questions,
+    /// returning how many were deleted.
+    pub fn expire_questions(&self) -> Result<usize, String> {
+        self.database.perform(Delete(ExpiredQuestions {
+            now: self.clock.now(),
+        }))
+    }
+
+    /// Tick forever, deleting expired questions every so often. A tick that fails is simply tried
+    /// again on the next one.
+    pub fn run(self) {
+        let mut ticks: u64 = 0;
+        loop {
+            let _ = self.tick();
+            if ticks % TICKS_PER_EXPIRY == 0 {
+                let _ = self.expire_questions();
+            }
+            ticks = ticks.wrapping_add(1);
+            thread::sleep(Duration::from_millis(TICK_INTERVAL_MS));
+        }
+    }
+}
@@ -0,0 +1,71 @@
+//! Lexical similarity between questions, used to point audience members at questions that have
+//! already been asked instead of splitting nods between duplicates.
+
+use std::collections::HashSet;
+
+use models::Question;
+
+
+/// How similar two questions must be to be considered duplicates, from 0 to 1.
+pub const DUPLICATE_THRESHOLD: f64 = 0.5;
+
+/// Words too common to say anything about what a question is asking.
+const STOP_WORDS: &'static [&'static str] = &[
+    "a", "about", "an", "and", "are", "as", "at", "be", "by", "can", "could", "do", "does", "for",
+    "from", "how", "i", "if", "in", "is", "it", "of", "on", "or", "please", "should", "so", "that",
+    "the", "this", "to", "was", "we", "what", "when", "where", "which", "who", "why", "will",
+    "with", "would", "you", "your",
+];
+
+
+/// Break text into the set of normalized words that matter for comparing questions. Words are
+/// lowercased, stop words dropped and common suffixes stripped, so that "Will the slides be
+/// shared?" and "sharing slides" are made of the same tokens.
+pub fn tokens(text: &str) -> HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .map(|word| word.to_lowercase())
+        .filter(|word| !word.is_empty() && !STOP_WORDS.contains(&word.as_str()))
+        .map(|word| stem(&word))
+        .collect()
+}
+
+/// The Jaccard similarity of the tokens of two texts, from 0 for nothing in common to 1 for the
+/// same tokens.
+pub fn similarity(a: &str, b: &str) -> f64 {
+    jaccard(&tokens(a), &tokens(b))
+}
+
+/// Find up to `limit` of `questions` similar enough to `text` to be duplicates, most similar first.
+pub fn find_duplicates<'a>(text: &str, questions: &'a [Question], limit: usize) -> Vec<&'a Question> {
+    let asked = tokens(text);
+    let mut scored: Vec<(f64, &Question)> = questions
+        .iter()
+        .map(|question| (jaccard(&asked, &tokens(&question.text)), question))
+        .filter(|&(score, _)| score >= DUPLICATE_THRESHOLD)
+        .collect();
+    scored.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap().then(b.1.nods.cmp(&a.1.nods)));
+    scored.into_iter().take(limit).map(|(_, question)| question).collect()
+}
+
+fn jaccard(a: &HashSet<String>, b: &HashSet<String>) -> f64 {
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    a.intersection(b).count() as f64 / a.union(b).count() as f64
+}
+
+/// Strip common English suffixes from a word, keeping at least a three letter stem. A final "e"
+/// is dropped too, so that "share", "shared" and "sharing" all become "shar".
+fn stem(word: &str) -> String {
+    let mut stem = word;
+    for suffix in &["ing", "ed", "es", "s"] {
+        if stem.ends_with(suffix) && stem.len() - suffix.len() >= 3 {
+            stem = &stem[..stem.len() - suffix.len()];
+            break;
+        }
+    }
+    if stem.ends_with('e') && stem.len() > 3 {
+        stem = &stem[..stem.len() - 1];
+    }
+    stem.to_string()
+}
@@ -0,0 +1,127 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use chrono::prelude::*;
+use iron::Headers;
+use iron::headers::{ContentType, Location};
+use iron::status;
+use iron_test::request;
+use json;
+use url::form_urlencoded;
+
+use server::api::lti::launch::LaunchHandler;
+use server::api::lti::link::LinkHandler;
+use server::auth::{lti, role_of};
+use server::auth::jwt::JwkSet;
+use server::auth::lti::LtiPlatform;
+use server::capabilities::{Capability, Save, Search};
+use server::capabilities::sqlite::SQLite;
+use server::models::{Id, OidcLoginAttempt, Presentation, Presenter, Role, Session};
+
+use auth::mock_issuer;
+use super::super::{post, setup_db};
+
+
+const PLATFORM_ISSUER: &'static str = "https://lms.school.example";
+const CLIENT_ID: &'static str = "asq-tool";
+const DEPLOYMENT_ID: &'static str = "deployment-1";
+const NONCE: &'static str = "launch-nonce";
+const INSTRUCTOR: &'static str = "http://purl.imsglobal.org/vocab/lis/v2/membership#Instructor";
+const LEARNER: &'static str = "http://purl.imsglobal.org/vocab/lis/v2/membership#Learner";
+
+
+fn launch_handler(db: &SQLite) -> LaunchHandler<SQLite> {
+    let keys: JwkSet = json::from_str(include_str!("../../fixtures/test_rsa_key.jwks.json")).unwrap();
+    let platforms = vec![LtiPlatform {
+        issuer: PLATFORM_ISSUER.to_string(),
+        client_id: CLIENT_ID.to_string(),
+        deployment_ids: vec![DEPLOYMENT_ID.to_string()],
+        auth_login_url: format!("{}/auth", PLATFORM_ISSUER),
+        keys: keys,
+    }];
+    LaunchHandler::new(db.clone(), Arc::new(platforms), "/".to_string())
+}
+
+/// Launch from the resource link as the platform user `sub`, returning the parameters AsQ
+/// redirects the user's browser with.
+fn launch(db: &SQLite, handler: &LaunchHandler<SQLite>, sub: &str, role: &str) -> HashMap<String, String> {
+    let state = format!("state-{}", sub);
+    db.perform(Save(OidcLoginAttempt::new(Id(state.clone()), NONCE.to_string(), String::new()))).unwrap();
+    let now = Utc::now();
+    let id_token = mock_issuer::sign(&json!({
+        "iss": PLATFORM_ISSUER,
+        "sub": sub,
+        "aud": CLIENT_ID,
+        "exp": now.timestamp() + 300,
+        "iat": now.timestamp(),
+        "nonce": NONCE,
+        // Every launch claims the address of an existing presenter, which must not matter.
+        "email": "teacher@school.example",
+        "https://purl.imsglobal.org/spec/lti/claim/message_type": "LtiResourceLinkRequest",
+        "https://purl.imsglobal.org/spec/lti/claim/version": "1.3.0",
+        "https://purl.imsglobal.org/spec/lti/claim/deployment_id": DEPLOYMENT_ID,
+        "https://purl.imsglobal.org/spec/lti/claim/roles": [role],
+        "https://purl.imsglobal.org/spec/lti/claim/resource_link": { "id": "link-1", "title": "Week 3" },
+    }));
+    let body = form_urlencoded::Serializer::new(String::new())
+        .append_pair("id_token", &id_token)
+        .append_pair("state", &state)
+        .finish();
+    let mut headers = Headers::new();
+    headers.set(ContentType::form_url_encoded());
+    let response = request::post("http://127.0.0.1:9001/lti/launch", headers, &body, handler).unwrap();
+    assert_eq!(response.status, Some(status::Found));
+    let location = response.headers.get::<Location>().unwrap().to_string();
+    let fragment = location.splitn(2, '#').nth(1).unwrap().to_string();
+    form_urlencoded::parse(fragment.as_bytes()).into_owned().collect()
+}
+
+fn session_owner(db: &SQLite, token: &str) -> Id {
+    db.perform(Search(Session::search_parameter(Id(token.to_string())))).unwrap().owner
+}
+
+#[test]
+fn launches_sign_in_by_platform_account_until_it_is_linked() {
+    let db = setup_db();
+    let handler = launch_handler(&db);
+    let teacher = db.perform(Save(Presenter::new("teacher@school.example".to_string(), "hunter2".to_string()))).unwrap();
+    let teacher_id = teacher.email_address.clone();
+    let teacher_session = db.perform(Save(Session::new(teacher))).unwrap();
+
+    let first = launch(&db, &handler, "user-7", INSTRUCTOR);
+    let first_presenter = session_owner(&db, &first["sessionToken"]);
+    assert!(lti::is_platform_presenter(&first_presenter));
+    let presentation_id = Id(first["presentation"].clone());
+    let presentation = db.perform(Search(Presentation::search_parameter(presentation_id.clone()))).unwrap();
+    assert_eq!(presentation.creator, first_presenter);
+
+    let second = launch(&db, &handler, "user-8", INSTRUCTOR);
+    let second_presenter = session_owner(&db, &second["sessionToken"]);
+    assert_eq!(second["presentation"], presentation_id.0);
+    assert!(second_presenter != first_presenter && second_presenter != teacher_id);
+    assert_eq!(role_of(&db, &second_presenter, &presentation), Some(Role::Presenter));
+    assert_eq!(role_of(&db, &teacher_id, &presentation), None);
+
+    let learner = launch(&db, &handler, "user-9", LEARNER);
+    assert_eq!(learner["presentation"], presentation_id.0);
+    assert!(learner.contains_key("submissionToken"));
+    assert!(!learner.contains_key("sessionToken"));
+
+    // Linking takes a session of both accounts.
+    let link = LinkHandler::new(db.clone());
+    let (status, _) = post(&link, json!({
+        "sessionToken": second["sessionToken"],
+        "launchSessionToken": first["sessionToken"],
+    }));
+    assert_eq!(status, status::BadRequest);
+    let (status, _) = post(&link, json!({
+        "sessionToken": teacher_session.token.0,
+        "launchSessionToken": first["sessionToken"],
+    }));
+    assert_eq!(status, status::Ok);
+
+    let relaunch = launch(&db, &handler, "user-7", INSTRUCTOR);
+    assert_eq!(session_owner(&db, &relaunch["sessionToken"]), teacher_id);
+    let presentation = db.perform(Search(Presentation::search_parameter(presentation_id))).unwrap();
+    assert_eq!(presentation.creator, teacher_id);
+}
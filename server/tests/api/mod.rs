@@ -1 +1,44 @@
+mod lti;
+mod oidc;
+mod organizations;
+mod presentations;
+mod presenters;
 mod questions;
+
+use std::sync::{Arc, Mutex};
+
+use iron::Handler;
+use iron::Headers;
+use iron::headers::ContentType;
+use iron::status::Status;
+use iron_test::{request, response};
+use json;
+use sqlite::Connection;
+
+use server::capabilities::initializers::init_sqlite_tables;
+use server::capabilities::sqlite::SQLite;
+
+
+pub fn setup_db() -> SQLite {
+    let db = SQLite::new(Arc::new(Mutex::new(Connection::open_in_memory().unwrap())));
+    init_sqlite_tables(&db).unwrap();
+    db
+}
+
+/// Post a JSON body to a handler, returning the status and the decoded body of its response.
+pub fn post<H: Handler>(handler: &H, body: json::Value) -> (Status, json::Value) {
+    let mut headers = Headers::new();
+    headers.set(ContentType::json());
+    let response = request::post("http://127.0.0.1:9001", headers, &body.to_string(), handler).unwrap();
+    let status = response.status.unwrap();
+    let body = json::from_str(&response::extract_body_to_string(response)).unwrap();
+    (status, body)
+}
+
+/// Get a URL from a handler, returning the status and the decoded body of its response.
+pub fn get<H: Handler>(handler: &H, url: &str) -> (Status, json::Value) {
+    let response = request::get(url, Headers::new(), handler).unwrap();
+    let status = response.status.unwrap();
+    let body = json::from_str(&response::extract_body_to_string(response)).unwrap();
+    (status, body)
+}
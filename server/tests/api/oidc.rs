@@ -0,0 +1,110 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use chrono::prelude::*;
+use iron::Headers;
+use iron::headers::{Cookie, Location};
+use iron::status;
+use iron_test::request;
+use url::form_urlencoded;
+
+use server::api::oidc::callback::CallbackHandler;
+use server::api::oidc::link::LinkHandler;
+use server::auth::oidc::{self, OidcConfig, OidcProvider};
+use server::capabilities::{Capability, Save, Search};
+use server::capabilities::sqlite::SQLite;
+use server::models::{Id, OidcLoginAttempt, Presenter, Session};
+
+use auth::mock_issuer::{self, AUTHORIZATION_CODE, CLIENT_ID, CLIENT_SECRET};
+use super::{post, setup_db};
+
+
+const PORT: u16 = 47302;
+const NONCE: &'static str = "login-nonce";
+const CODE_VERIFIER: &'static str = "login-code-verifier";
+
+
+fn provider() -> Arc<OidcProvider> {
+    let config = OidcConfig {
+        issuer: format!("http://127.0.0.1:{}", PORT),
+        client_id: CLIENT_ID.to_string(),
+        client_secret: CLIENT_SECRET.to_string(),
+        redirect_uri: "http://127.0.0.1:9001/api/oidc/callback".to_string(),
+        post_login_redirect: "/".to_string(),
+    };
+    Arc::new(OidcProvider::discover(config).unwrap())
+}
+
+/// Come back from the provider for a login attempt with the given `state`, sending `cookie` if
+/// there is one, returning the status and the parameters AsQ redirects the browser with.
+fn callback(db: &SQLite, handler: &CallbackHandler<SQLite>, state: &str, cookie: Option<&str>)
+    -> (status::Status, HashMap<String, String>)
+{
+    db.perform(Save(OidcLoginAttempt::new(Id(state.to_string()), NONCE.to_string(), CODE_VERIFIER.to_string()))).unwrap();
+    let mut headers = Headers::new();
+    if let Some(cookie) = cookie {
+        headers.set(Cookie(vec![cookie.to_string()]));
+    }
+    let url = format!("http://127.0.0.1:9001/api/oidc/callback?state={}&code={}", state, AUTHORIZATION_CODE);
+    let response = request::get(&url, headers, handler).unwrap();
+    let status = response.status.unwrap();
+    let params = response.headers.get::<Location>()
+        .and_then(|location| location.splitn(2, '#').nth(1).map(|fragment| fragment.to_string()))
+        .map(|fragment| form_urlencoded::parse(fragment.as_bytes()).into_owned().collect())
+        .unwrap_or_default();
+    (status, params)
+}
+
+fn session_owner(db: &SQLite, token: &str) -> Id {
+    db.perform(Search(Session::search_parameter(Id(token.to_string())))).unwrap().owner
+}
+
+#[test]
+fn provider_accounts_only_sign_in_as_existing_presenters_once_linked() {
+    let now = Utc::now();
+    let claims = json!({
+        "iss": format!("http://127.0.0.1:{}", PORT),
+        "sub": "teacher-42",
+        "aud": CLIENT_ID,
+        "exp": now.timestamp() + 300,
+        "iat": now.timestamp(),
+        "nonce": NONCE,
+        "email": "teacher@school.example",
+        "email_verified": true,
+    });
+    let mut issuer = mock_issuer::start(PORT, oidc::pkce_challenge(CODE_VERIFIER), claims);
+    let db = setup_db();
+    let handler = CallbackHandler::new(db.clone(), provider());
+    let teacher = db.perform(Save(Presenter::new("teacher@school.example".to_string(), "hunter2".to_string()))).unwrap();
+    let teacher_id = teacher.email_address.clone();
+    let teacher_session = db.perform(Save(Session::new(teacher))).unwrap();
+
+    // The state must come back to the browser that started signing in.
+    let (status, _) = callback(&db, &handler, "state-1", None);
+    assert_eq!(status, status::BadRequest);
+    let (status, _) = callback(&db, &handler, "state-2", Some("asq_oidc_state=state-1"));
+    assert_eq!(status, status::BadRequest);
+
+    let (status, first) = callback(&db, &handler, "state-3", Some("asq_oidc_state=state-3"));
+    assert_eq!(status, status::Found);
+    let provider_presenter = session_owner(&db, &first["sessionToken"]);
+    assert!(oidc::is_provider_presenter(&provider_presenter));
+
+    let link = LinkHandler::new(db.clone());
+    let (status, _) = post(&link, json!({
+        "sessionToken": first["sessionToken"],
+        "providerSessionToken": first["sessionToken"],
+    }));
+    assert_eq!(status, status::BadRequest);
+    let (status, _) = post(&link, json!({
+        "sessionToken": teacher_session.token.0,
+        "providerSessionToken": first["sessionToken"],
+    }));
+    assert_eq!(status, status::Ok);
+
+    let (status, second) = callback(&db, &handler, "state-4", Some("asq_oidc_state=state-4"));
+    assert_eq!(status, status::Found);
+    assert_eq!(session_owner(&db, &second["sessionToken"]), teacher_id);
+
+    issuer.close().unwrap();
+}
@@ -0,0 +1,62 @@
+use iron::status;
+
+use server::api::organizations::presentations::MovePresentationHandler;
+use server::capabilities::{Capability, Save, Search, Update};
+use server::models::{Id, Budget, FilterAction, FilterSetting, Organization, OrganizationMember, OrganizationRole,
+    Presentation, Presenter, RateLimits, Session};
+
+use super::super::{post, setup_db};
+
+
+#[test]
+fn presentations_moved_into_an_organization_take_on_its_defaults() {
+    let db = setup_db();
+    let session_for = |email: &str| {
+        let presenter = db.perform(Save(Presenter::new(email.to_string(), "password".to_string()))).unwrap();
+        db.perform(Save(Session::new(presenter))).unwrap().token.0
+    };
+    let lecturer_session = session_for("lecturer@example.com");
+    let outsider_session = session_for("outsider@example.com");
+    let lecturer = Id("lecturer@example.com".to_string());
+    let outsider = Id("outsider@example.com".to_string());
+
+    let mut organization = db.perform(Save(Organization::new("Physics".to_string()))).unwrap();
+    organization.defaults.rate_limits = RateLimits { ask: Budget::new(2, 1), nod: Budget::new(10, 10) };
+    organization.defaults.filters = vec![FilterSetting::Links { max_links: 0, action: FilterAction::Reject }];
+    organization.defaults.retention_days = Some(30);
+    let organization = db.perform(Update(organization)).unwrap();
+    db.perform(Save(OrganizationMember::new(organization.id.clone(), lecturer.clone(), OrganizationRole::Member)))
+        .unwrap();
+
+    let custom_filters = vec![FilterSetting::RepeatedCharacters { max_repeats: 4, action: FilterAction::Review }];
+    let mut presentation = Presentation::new(lecturer.clone(), "Optics".to_string());
+    presentation.filters = custom_filters.clone();
+    let presentation = db.perform(Save(presentation)).unwrap();
+    let other = db.perform(Save(Presentation::new(outsider.clone(), "Budget".to_string()))).unwrap();
+
+    let handler = MovePresentationHandler::new(db.clone());
+    let (status, _) = post(&handler, json!({
+        "sessionToken": outsider_session,
+        "presentation": other.id.0,
+        "organization": organization.id.0,
+    }));
+    assert_eq!(status, status::BadRequest);
+
+    let (status, _) = post(&handler, json!({
+        "sessionToken": lecturer_session,
+        "presentation": presentation.id.0,
+        "organization": organization.id.0,
+    }));
+    assert_eq!(status, status::Ok);
+    let moved = db.perform(Search(Presentation::search_parameter(presentation.id.clone()))).unwrap();
+    assert_eq!(moved.organization, Some(organization.id.clone()));
+    assert_eq!(moved.filters, custom_filters);
+    assert_eq!(moved.rate_limits, organization.defaults.rate_limits);
+    assert_eq!(moved.retention_days, Some(30));
+
+    let (status, _) = post(&handler, json!({ "sessionToken": lecturer_session, "presentation": presentation.id.0 }));
+    assert_eq!(status, status::Ok);
+    let moved_out = db.perform(Search(Presentation::search_parameter(presentation.id))).unwrap();
+    assert_eq!(moved_out.organization, None);
+    assert_eq!(moved_out.retention_days, Some(30));
+}
@@ -0,0 +1,30 @@
+use iron::status;
+
+use server::api::presentations::access::RequestAccessHandler;
+use server::capabilities::{Capability, Save};
+use server::models::{Id, Presentation};
+use server::ratelimit::MemoryBuckets;
+
+use super::super::{post, setup_db};
+
+
+#[test]
+fn passcodes_cannot_be_guessed_without_limit() {
+    let db = setup_db();
+    let mut presentation = Presentation::new(Id("presenter@example.com".to_string()), "Week 5".to_string());
+    presentation.set_passcode(Some("open sesame"));
+    let presentation = db.perform(Save(presentation)).unwrap();
+    let handler = RequestAccessHandler::new(db.clone(), MemoryBuckets::new());
+
+    let (status, body) = post(&handler, json!({ "presentation": presentation.id.0, "passcode": "open sesame" }));
+    assert_eq!(status, status::Ok);
+    assert!(body["accessToken"].is_string());
+    for guess in 0..9 {
+        let (status, _) = post(&handler, json!({ "presentation": presentation.id.0, "passcode": guess.to_string() }));
+        assert_eq!(status, status::Forbidden);
+    }
+
+    let (status, body) = post(&handler, json!({ "presentation": presentation.id.0, "passcode": "open sesame" }));
+    assert_eq!(status, status::TooManyRequests);
+    assert!(body["accessToken"].is_null());
+}
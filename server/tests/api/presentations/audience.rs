@@ -0,0 +1,94 @@
+use iron::status;
+
+use server::api::presentations::audience::JoinAudienceHandler;
+use server::api::questions::flag::FlagHandler;
+use server::capabilities::{Capability, Save, Search};
+use server::models::{Id, Audience, Presentation, Question, QuestionStatus};
+use server::ratelimit::MemoryBuckets;
+
+use super::super::{post, setup_db};
+
+
+#[test]
+fn audience_members_who_join_can_flag_questions() {
+    let db = setup_db();
+    let creator = Id("presenter@example.com".to_string());
+    let presentation = db.perform(Save(Presentation::new(creator.clone(), "Week 4".to_string()))).unwrap();
+    let question = Question::new(presentation.id.clone(), "Is this on the exam?".to_string());
+    let question = db.perform(Save(question)).unwrap();
+    let mut private = Presentation::new(creator, "Week 5".to_string());
+    private.set_passcode(Some("open sesame"));
+    let private = db.perform(Save(private)).unwrap();
+
+    let join = JoinAudienceHandler::new(db.clone(), MemoryBuckets::new());
+    let (status, body) = post(&join, json!({ "presentation": presentation.id.0 }));
+    assert_eq!(status, status::Ok);
+    let audience = body["audience"].as_str().unwrap().to_string();
+    let submission_token = body["submissionToken"].as_str().unwrap().to_string();
+    let (status, _) = post(&join, json!({ "presentation": private.id.0 }));
+    assert_eq!(status, status::Forbidden);
+
+    let flag = FlagHandler::new(db.clone(), MemoryBuckets::new());
+    let (status, _) = post(&flag, json!({
+        "question": question.id.0,
+        "audience": audience,
+        "submissionToken": "not the token",
+        "reason": "Off topic",
+    }));
+    assert_eq!(status, status::BadRequest);
+    let (status, body) = post(&flag, json!({
+        "question": question.id.0,
+        "audience": audience,
+        "submissionToken": submission_token,
+        "reason": "Off topic",
+    }));
+    assert_eq!(status, status::Ok);
+    assert_eq!(body["flag"]["audience"], json!(audience));
+}
+
+#[test]
+fn audience_members_behind_one_address_flag_as_one() {
+    let db = setup_db();
+    let creator = Id("presenter@example.com".to_string());
+    let mut presentation = Presentation::new(creator, "Week 6".to_string());
+    presentation.flag_threshold = 2;
+    let presentation = db.perform(Save(presentation)).unwrap();
+    let question = Question::new(presentation.id.clone(), "Is this on the exam?".to_string());
+    let question = db.perform(Save(question)).unwrap();
+    let flag = FlagHandler::new(db.clone(), MemoryBuckets::new());
+    let flag_as = |audience: Audience| post(&flag, json!({
+        "question": question.id.0,
+        "audience": audience.id.0,
+        "submissionToken": audience.submission_token,
+        "reason": "Off topic",
+    })).0;
+
+    // Every request in these tests comes from the same address.
+    for _ in 0..3 {
+        let anonymous = db.perform(Save(Audience::new(Id::generate()))).unwrap();
+        assert_eq!(flag_as(anonymous), status::Ok);
+    }
+    let found = db.perform(Search(Question::search_parameter(question.id.clone()))).unwrap();
+    assert_eq!(found.status, QuestionStatus::Approved);
+
+    for learner in &["lti:learner-1", "lti:learner-2"] {
+        let launched = db.perform(Save(Audience::new(Id(learner.to_string())))).unwrap();
+        assert_eq!(flag_as(launched), status::Ok);
+    }
+    let found = db.perform(Search(Question::search_parameter(question.id))).unwrap();
+    assert_eq!(found.status, QuestionStatus::Pending);
+}
+
+#[test]
+fn joining_the_audience_is_limited_per_address() {
+    let db = setup_db();
+    let presentation = Presentation::new(Id("presenter@example.com".to_string()), "Week 7".to_string());
+    let presentation = db.perform(Save(presentation)).unwrap();
+    let join = JoinAudienceHandler::new(db.clone(), MemoryBuckets::new());
+
+    let statuses = (0..60)
+        .map(|_| post(&join, json!({ "presentation": presentation.id.0 })).0)
+        .collect::<Vec<_>>();
+    assert!(statuses.iter().take(50).all(|status| *status == status::Ok));
+    assert_eq!(statuses.last(), Some(&status::TooManyRequests));
+}
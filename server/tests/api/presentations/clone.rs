@@ -0,0 +1,32 @@
+use iron::status;
+
+use server::api::presentations::clone::ClonePresentationHandler;
+use server::capabilities::{Capability, Save};
+use server::models::{Id, Presentation, Presenter, Session};
+
+use super::super::{post, setup_db};
+
+
+#[test]
+fn copies_are_given_titles_only_within_the_usual_limits() {
+    let db = setup_db();
+    let presenter = db.perform(Save(Presenter::new("lecturer@example.com".to_string(), "password".to_string())))
+        .unwrap();
+    let session = db.perform(Save(Session::new(presenter))).unwrap().token.0;
+    let creator = Id("lecturer@example.com".to_string());
+    let presentation = db.perform(Save(Presentation::new(creator, "Compilers".to_string()))).unwrap();
+
+    let handler = ClonePresentationHandler::new(db.clone());
+    let clone_titled = |title: String| post(&handler, json!({
+        "sessionToken": session,
+        "presentation": presentation.id.0,
+        "title": title,
+    }));
+    let (status, _) = clone_titled("  ".to_string());
+    assert_eq!(status, status::BadRequest);
+    let (status, _) = clone_titled("x".repeat(201));
+    assert_eq!(status, status::BadRequest);
+    let (status, body) = clone_titled("  Compilers, again  ".to_string());
+    assert_eq!(status, status::Ok);
+    assert_eq!(body["presentation"]["title"], json!("Compilers, again"));
+}
@@ -0,0 +1,38 @@
+use iron::status;
+
+use server::api::presentations::list::ListHandler;
+use server::capabilities::{Capability, Save};
+use server::models::{Id, Presentation, Presenter, Session};
+
+use super::super::{get, setup_db};
+
+
+#[test]
+fn only_presenters_see_how_their_presentations_are_run() {
+    let db = setup_db();
+    let presenter = db.perform(Save(Presenter::new("presenter@example.com".to_string(), "password".to_string()))).unwrap();
+    let session = db.perform(Save(Session::new(presenter))).unwrap();
+    let mut presentation = Presentation::new(Id("presenter@example.com".to_string()), "Compilers".to_string());
+    presentation.description = "Parsing, mostly.".to_string();
+    presentation.retention_days = Some(30);
+    presentation.set_passcode(Some("open sesame"));
+    db.perform(Save(presentation)).unwrap();
+    let handler = ListHandler::new(db.clone());
+
+    let (status, body) = get(&handler, "http://127.0.0.1:9001/presentations?presenter=presenter@example.com");
+    assert_eq!(status, status::Ok);
+    let listed = &body["presentations"][0];
+    assert_eq!(listed["title"], "Compilers");
+    assert_eq!(listed["description"], "Parsing, mostly.");
+    assert_eq!(listed["isPrivate"], true);
+    for hidden in &["creator", "filters", "rateLimits", "retentionDays", "organization", "joinCode", "role"] {
+        assert!(listed.get(hidden).is_none(), "{} should not be listed", hidden);
+    }
+
+    let url = format!("http://127.0.0.1:9001/presentations?presenter=presenter@example.com&sessionToken={}",
+                      session.token.0);
+    let (status, body) = get(&handler, &url);
+    assert_eq!(status, status::Ok);
+    assert_eq!(body["presentations"][0]["retentionDays"], 30);
+    assert_eq!(body["presentations"][0]["role"], "owner");
+}
@@ -0,0 +1,5 @@
+mod access;
+mod audience;
+mod clone;
+mod list;
+mod transfer;
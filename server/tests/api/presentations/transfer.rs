@@ -0,0 +1,52 @@
+use std::fs;
+
+use iron::status;
+
+use server::api::presentations::transfer::{AcceptTransferHandler, OfferTransferHandler};
+use server::auth::role_of;
+use server::capabilities::{Capability, Save, Search};
+use server::mailer::outbox::OutboxMailer;
+use server::models::{Id, Membership, Presentation, Presenter, Role, Session};
+
+use super::super::{post, setup_db};
+
+
+#[test]
+fn only_creators_may_hand_presentations_over() {
+    let outbox_dir = "only_creators_may_hand_presentations_over";
+    let db = setup_db();
+    let session_for = |email: &str| {
+        let presenter = db.perform(Save(Presenter::new(email.to_string(), "password".to_string()))).unwrap();
+        db.perform(Save(Session::new(presenter))).unwrap().token.0
+    };
+    let creator_session = session_for("creator@example.com");
+    let co_owner_session = session_for("co-owner@example.com");
+    let successor_session = session_for("successor@example.com");
+    let creator = Id("creator@example.com".to_string());
+    let presentation = db.perform(Save(Presentation::new(creator.clone(), "Compilers".to_string()))).unwrap();
+    let co_owner = Membership::new(presentation.id.clone(), Id("co-owner@example.com".to_string()), Role::Owner);
+    db.perform(Save(co_owner)).unwrap();
+
+    let offer = OfferTransferHandler::new(db.clone(), OutboxMailer::new(outbox_dir));
+    let (status, _) = post(&offer, json!({
+        "sessionToken": co_owner_session,
+        "presentation": presentation.id.0,
+        "to": "co-owner@example.com",
+    }));
+    assert_eq!(status, status::BadRequest);
+    let (status, _) = post(&offer, json!({
+        "sessionToken": creator_session,
+        "presentation": presentation.id.0,
+        "to": "successor@example.com",
+    }));
+    assert_eq!(status, status::Ok);
+
+    let accept = AcceptTransferHandler::new(db.clone());
+    let (status, _) = post(&accept, json!({ "sessionToken": successor_session, "presentation": presentation.id.0 }));
+    assert_eq!(status, status::Ok);
+    let presentation = db.perform(Search(Presentation::search_parameter(presentation.id))).unwrap();
+    assert_eq!(presentation.creator, Id("successor@example.com".to_string()));
+    assert_eq!(role_of(&db, &creator, &presentation), Some(Role::Presenter));
+
+    fs::remove_dir_all(outbox_dir).unwrap();
+}
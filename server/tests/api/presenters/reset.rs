@@ -0,0 +1,44 @@
+use std::fs;
+
+use iron::status;
+
+use server::api::presenters::reset::{RequestResetHandler, ResetPasswordHandler};
+use server::auth::authenticate;
+use server::capabilities::{Capability, Save, Search};
+use server::mailer::outbox::OutboxMailer;
+use server::models::{Id, Presenter, Scope, Session};
+
+use super::{post, setup_db};
+
+
+#[test]
+fn resetting_a_password_replaces_it_and_ends_every_session() {
+    let outbox_dir = "resetting_a_password_replaces_it_and_ends_every_session";
+    let db = setup_db();
+    let email = Id("presenter@example.com".to_string());
+    let presenter = db.perform(Save(Presenter::new(email.0.clone(), "forgotten".to_string()))).unwrap();
+    let session = db.perform(Save(Session::new(presenter))).unwrap();
+
+    let request_reset = RequestResetHandler::new(db.clone(), OutboxMailer::new(outbox_dir));
+    let (status, _) = post(&request_reset, json!({ "emailAddress": email.0 }));
+    assert_eq!(status, status::Ok);
+    let sent = OutboxMailer::new(outbox_dir).messages().unwrap();
+    assert_eq!(sent.len(), 1);
+    let token = sent[0].body.split("\n\n").nth(1).unwrap().to_string();
+
+    let reset_password = ResetPasswordHandler::new(db.clone());
+    let (status, _) = post(&reset_password, json!({ "token": token, "password": "remembered" }));
+    assert_eq!(status, status::Ok);
+
+    let presenter = db.perform(Search(Presenter::search_parameter(email))).unwrap();
+    assert!(presenter.password_matches("remembered"));
+    assert!(!presenter.password_matches("forgotten"));
+    assert!(presenter.email_verified);
+    assert!(authenticate(&db, session.token, Scope::QuestionsRead).is_err());
+
+    // Reset tokens are single-use.
+    let (status, _) = post(&reset_password, json!({ "token": token, "password": "again" }));
+    assert_eq!(status, status::BadRequest);
+
+    fs::remove_dir_all(outbox_dir).unwrap();
+}
@@ -1,10 +1,10 @@
-use iron::prelude::*;
-use iron::{Headers, status};
-use iron_test::{request, response};
-use serde_json as json;
-use sqlite::Connection;
+use iron::status;
 
-use server::api;
+use server::api::questions::ask::AskHandler;
+use server::capabilities::{Capability, Save};
+use server::models::{Id, Presentation, Question};
+
+use super::super::{post, setup_db};
 
 
 #[test]
@@ -27,3 +27,33 @@ fn cannot_ask_questions_for_presentations_that_dont_exist() {
     teardown_db(db_name, db);
     */
 }
+
+#[test]
+fn similar_questions_are_suggested_without_stopping_the_question() {
+    let db = setup_db();
+    let presentation = Presentation::new(Id("presenter@example.com".to_string()), "Week 4".to_string());
+    let presentation = db.perform(Save(presentation)).unwrap();
+    let asked = Question::new(presentation.id.clone(), "When is the final exam?".to_string());
+    let asked = db.perform(Save(asked)).unwrap();
+    let handler = AskHandler::new(db.clone());
+
+    let (status, body) = post(&handler, json!({
+        "presentation": presentation.id.0,
+        "question": "When is the final exam?",
+    }));
+    assert_eq!(status, status::Ok);
+    assert!(body["question"]["id"].is_string());
+    assert_eq!(body["suggestions"], json!([]));
+
+    let (status, body) = post(&handler, json!({
+        "presentation": presentation.id.0,
+        "question": "When is the final exam??",
+        "suggestSimilar": true,
+    }));
+    assert_eq!(status, status::Ok);
+    assert_eq!(body["question"]["text"], json!("When is the final exam??"));
+    let suggested: Vec<&str> = body["suggestions"].as_array().unwrap().iter()
+        .map(|question| question["id"].as_str().unwrap())
+        .collect();
+    assert!(suggested.contains(&asked.id.0.as_str()));
+}
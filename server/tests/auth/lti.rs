@@ -0,0 +1,87 @@
+use chrono::prelude::*;
+use serde_json::{self, Value};
+
+use server::auth::jwt::JwkSet;
+use server::auth::lti::{self, LtiPlatform};
+
+use super::mock_issuer;
+
+
+const PLATFORM_ISSUER: &'static str = "https://lms.school.example";
+const CLIENT_ID: &'static str = "asq-tool";
+const DEPLOYMENT_ID: &'static str = "deployment-1";
+const NONCE: &'static str = "launch-nonce";
+
+
+fn platforms() -> Vec<LtiPlatform> {
+    let keys: JwkSet = serde_json::from_str(include_str!("../fixtures/test_rsa_key.jwks.json")).unwrap();
+    vec![LtiPlatform {
+        issuer: PLATFORM_ISSUER.to_string(),
+        client_id: CLIENT_ID.to_string(),
+        deployment_ids: vec![DEPLOYMENT_ID.to_string()],
+        auth_login_url: format!("{}/auth", PLATFORM_ISSUER),
+        keys: keys,
+    }]
+}
+
+fn launch_claims(role: &str, now: DateTime<Utc>) -> Value {
+    json!({
+        "iss": PLATFORM_ISSUER,
+        "sub": "user-7",
+        "aud": CLIENT_ID,
+        "exp": now.timestamp() + 300,
+        "iat": now.timestamp(),
+        "nonce": NONCE,
+        "email": "teacher@school.example",
+        "https://purl.imsglobal.org/spec/lti/claim/message_type": "LtiResourceLinkRequest",
+        "https://purl.imsglobal.org/spec/lti/claim/version": "1.3.0",
+        "https://purl.imsglobal.org/spec/lti/claim/deployment_id": DEPLOYMENT_ID,
+        "https://purl.imsglobal.org/spec/lti/claim/roles": [role],
+        "https://purl.imsglobal.org/spec/lti/claim/resource_link": { "id": "link-1", "title": "Week 3" },
+    })
+}
+
+#[test]
+fn instructors_and_learners_are_told_apart() {
+    let now = Utc.timestamp(1500000000, 0);
+    let instructor = mock_issuer::sign(&launch_claims("http://purl.imsglobal.org/vocab/lis/v2/membership#Instructor", now));
+    let learner = mock_issuer::sign(&launch_claims("http://purl.imsglobal.org/vocab/lis/v2/membership#Learner", now));
+
+    let instructor = lti::validate_launch(&platforms(), &instructor, NONCE, now).unwrap();
+    assert!(instructor.is_instructor());
+    // The email claim is up to the platform, so it never decides which presenter signs in.
+    assert_eq!(instructor.presenter_id().0, "lti:https://lms.school.example:user-7");
+    assert!(lti::is_platform_presenter(&instructor.presenter_id()));
+    assert_eq!(instructor.resource_link.title, Some("Week 3".to_string()));
+
+    let learner = lti::validate_launch(&platforms(), &learner, NONCE, now).unwrap();
+    assert!(!learner.is_instructor());
+    assert_eq!(learner.audience_id(), instructor.audience_id());
+    assert!(!learner.audience_id().0.contains("user-7"));
+}
+
+#[test]
+fn launches_must_match_the_registered_platform() {
+    let now = Utc.timestamp(1500000000, 0);
+    let role = "http://purl.imsglobal.org/vocab/lis/v2/membership#Learner";
+
+    let valid = mock_issuer::sign(&launch_claims(role, now));
+    assert!(lti::validate_launch(&platforms(), &valid, "another-nonce", now).is_err());
+    assert!(lti::validate_launch(&platforms(), &valid, NONCE, now + ::chrono::Duration::minutes(10)).is_err());
+
+    let mut claims = launch_claims(role, now);
+    claims["https://purl.imsglobal.org/spec/lti/claim/deployment_id"] = json!("deployment-2");
+    assert!(lti::validate_launch(&platforms(), &mock_issuer::sign(&claims), NONCE, now).is_err());
+
+    let mut claims = launch_claims(role, now);
+    claims["aud"] = json!("some-other-tool");
+    assert!(lti::validate_launch(&platforms(), &mock_issuer::sign(&claims), NONCE, now).is_err());
+
+    let mut claims = launch_claims(role, now);
+    claims["https://purl.imsglobal.org/spec/lti/claim/message_type"] = json!("LtiDeepLinkingRequest");
+    assert!(lti::validate_launch(&platforms(), &mock_issuer::sign(&claims), NONCE, now).is_err());
+
+    let mut tampered = valid.clone();
+    tampered.pop();
+    assert!(lti::validate_launch(&platforms(), &tampered, NONCE, now).is_err());
+}
@@ -0,0 +1,75 @@
+//! A minimal OpenID Connect provider that runs in-process, so that the relying party can be tested
+//! without network access. Tokens are signed with the fixed key in `tests/fixtures`.
+
+use std::io::Read;
+use std::sync::Arc;
+
+use base64;
+use iron::prelude::*;
+use iron::{Listening, status};
+use ring::rand::SystemRandom;
+use ring::signature::{self, RSAKeyPair, RSASigningState};
+use router::Router;
+use serde_json::Value;
+use untrusted::Input;
+use url::form_urlencoded;
+
+use server::auth::oidc::pkce_challenge;
+
+
+const SIGNING_KEY: &'static [u8] = include_bytes!("../fixtures/test_rsa_key.pk8");
+const JWKS: &'static str = include_str!("../fixtures/test_rsa_key.jwks.json");
+
+pub const CLIENT_ID: &'static str = "asq-test-client";
+pub const CLIENT_SECRET: &'static str = "asq-test-secret";
+pub const AUTHORIZATION_CODE: &'static str = "test-authorization-code";
+
+
+/// Sign a set of claims as an RS256 JSON Web Token with the test key.
+pub fn sign(claims: &Value) -> String {
+    let header = json!({ "alg": "RS256", "typ": "JWT", "kid": "test-key" });
+    let encode = |value: &Value| base64::encode_config(value.to_string().as_bytes(), base64::URL_SAFE_NO_PAD);
+    let signed_data = format!("{}.{}", encode(&header), encode(claims));
+    let key_pair = RSAKeyPair::from_pkcs8(Input::from(SIGNING_KEY)).unwrap();
+    let mut signing_state = RSASigningState::new(Arc::new(key_pair)).unwrap();
+    let mut signature = vec![0; signing_state.key_pair().public_modulus_len()];
+    signing_state
+        .sign(&signature::RSA_PKCS1_SHA256, &SystemRandom::new(), signed_data.as_bytes(), &mut signature)
+        .unwrap();
+    format!("{}.{}", signed_data, base64::encode_config(&signature, base64::URL_SAFE_NO_PAD))
+}
+
+/// Run a mock issuer on `port`. The token endpoint accepts `AUTHORIZATION_CODE` as long as the
+/// code verifier matches `code_challenge`, and responds with an ID token carrying `claims`.
+pub fn start(port: u16, code_challenge: String, claims: Value) -> Listening {
+    let issuer = format!("http://127.0.0.1:{}", port);
+    let discovery = json!({
+        "issuer": issuer,
+        "authorization_endpoint": format!("{}/authorize", issuer),
+        "token_endpoint": format!("{}/token", issuer),
+        "jwks_uri": format!("{}/jwks", issuer),
+    });
+
+    let mut router = Router::new();
+    router.get("/.well-known/openid-configuration", move |_: &mut Request| {
+        Ok(Response::with((status::Ok, discovery.to_string())))
+    }, "discovery");
+    router.get("/jwks", |_: &mut Request| {
+        Ok(Response::with((status::Ok, JWKS)))
+    }, "jwks");
+    router.post("/token", move |request: &mut Request| {
+        let mut body = String::new();
+        request.body.read_to_string(&mut body).unwrap();
+        let params = form_urlencoded::parse(body.as_bytes()).into_owned().collect::<Vec<(String, String)>>();
+        let param = |name: &str| params.iter().find(|&&(ref key, _)| key == name).map(|&(_, ref value)| value.clone());
+        let verifier_matches = param("code_verifier").map(|v| pkce_challenge(&v) == code_challenge).unwrap_or(false);
+        if param("code") == Some(AUTHORIZATION_CODE.to_string()) && verifier_matches {
+            let tokens = json!({ "token_type": "Bearer", "id_token": sign(&claims) });
+            Ok(Response::with((status::Ok, tokens.to_string())))
+        } else {
+            Ok(Response::with((status::BadRequest, "{\"error\": \"invalid_grant\"}")))
+        }
+    }, "token");
+
+    Iron::new(router).http(("127.0.0.1", port)).unwrap()
+}
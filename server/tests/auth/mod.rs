@@ -0,0 +1,4 @@
+mod lti;
+pub mod mock_issuer;
+mod oidc;
+mod totp;
@@ -0,0 +1,64 @@
+use chrono::prelude::*;
+
+use server::auth::oidc::{self, OidcConfig, OidcProvider};
+
+use super::mock_issuer::{self, AUTHORIZATION_CODE, CLIENT_ID, CLIENT_SECRET};
+
+
+fn config(port: u16) -> OidcConfig {
+    OidcConfig {
+        issuer: format!("http://127.0.0.1:{}", port),
+        client_id: CLIENT_ID.to_string(),
+        client_secret: CLIENT_SECRET.to_string(),
+        redirect_uri: "http://127.0.0.1:9001/api/oidc/callback".to_string(),
+        post_login_redirect: "/".to_string(),
+    }
+}
+
+#[test]
+fn authorization_code_flow_yields_validated_claims() {
+    let port = 47301;
+    let verifier = oidc::random_value();
+    let nonce = oidc::random_value();
+    let now = Utc.timestamp(1500000000, 0);
+    let claims = json!({
+        "iss": format!("http://127.0.0.1:{}", port),
+        "sub": "teacher-42",
+        "aud": CLIENT_ID,
+        "exp": now.timestamp() + 300,
+        "iat": now.timestamp(),
+        "nonce": nonce,
+        "email": "teacher@school.example",
+        "email_verified": true,
+    });
+    let mut issuer = mock_issuer::start(port, oidc::pkce_challenge(&verifier), claims);
+
+    let provider = OidcProvider::discover(config(port)).unwrap();
+
+    let url = provider.authorization_url("some-state", &nonce, &verifier).unwrap();
+    assert!(url.starts_with(&format!("http://127.0.0.1:{}/authorize?", port)));
+    assert!(url.contains(&format!("code_challenge={}", oidc::pkce_challenge(&verifier))));
+    assert!(url.contains("code_challenge_method=S256"));
+
+    assert!(provider.exchange_code(AUTHORIZATION_CODE, "the-wrong-verifier").is_err());
+    let id_token = provider.exchange_code(AUTHORIZATION_CODE, &verifier).unwrap();
+
+    let validated = provider.validate_id_token(&id_token, &nonce, now).unwrap();
+    assert_eq!(validated.sub, "teacher-42");
+    assert_eq!(validated.email, Some("teacher@school.example".to_string()));
+
+    assert!(provider.validate_id_token(&id_token, "another-nonce", now).is_err());
+    assert!(provider.validate_id_token(&id_token, &nonce, now + ::chrono::Duration::minutes(10)).is_err());
+
+    let mut tampered = id_token.clone();
+    tampered.pop();
+    assert!(provider.validate_id_token(&tampered, &nonce, now).is_err());
+
+    issuer.close().unwrap();
+}
+
+#[test]
+fn pkce_challenge_matches_rfc_7636_example() {
+    let verifier = "dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk";
+    assert_eq!(oidc::pkce_challenge(verifier), "E9Melhoa2OwvFrEMTJguCHaoeK1zSdLQJ2eN8JVPwQ4");
+}
@@ -0,0 +1,65 @@
+use chrono::prelude::*;
+
+use server::auth::totp;
+use server::models::{Id, Presenter};
+
+
+// The SHA-1 test secret from RFC 6238, "12345678901234567890", encoded in base32.
+const RFC_SECRET: &'static str = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ";
+
+
+#[test]
+fn codes_match_rfc_6238_test_vectors() {
+    let cases = [(59, 287082), (1111111109, 81804), (1234567890, 5924), (2000000000, 279037)];
+    for &(time, expected) in cases.iter() {
+        let at = Utc.timestamp(time, 0);
+        assert_eq!(totp::code_at(RFC_SECRET, at), Some(expected));
+    }
+}
+
+#[test]
+fn verification_tolerates_one_step_of_drift() {
+    let now = Utc.timestamp(1111111109, 0);
+    assert!(totp::verify(RFC_SECRET, "081804", now));
+    assert!(totp::verify(RFC_SECRET, "081804", Utc.timestamp(1111111109 + 30, 0)));
+    assert!(!totp::verify(RFC_SECRET, "081804", Utc.timestamp(1111111109 + 90, 0)));
+    assert!(!totp::verify(RFC_SECRET, "81804", now));
+    assert!(!totp::verify(RFC_SECRET, "not a code", now));
+}
+
+#[test]
+fn recovery_codes_can_only_be_used_once() {
+    let mut presenter = Presenter::search_parameter(Id("presenter@example.com".to_string()));
+    let codes = presenter.begin_totp_enrollment();
+    let now = Utc.timestamp(1111111109, 0);
+
+    assert!(presenter.verify_second_factor(&codes[0], now));
+    assert!(!presenter.verify_second_factor(&codes[0], now));
+    assert!(presenter.verify_second_factor(&codes[1].to_uppercase(), now));
+    assert_eq!(presenter.recovery_codes.len(), codes.len() - 2);
+}
+
+#[test]
+fn provisioning_uri_names_the_issuer_and_account() {
+    let uri = totp::provisioning_uri("presenter@example.com", RFC_SECRET);
+    assert!(uri.starts_with("otpauth://totp/AsQ:presenter@example.com?"));
+    assert!(uri.contains(&format!("secret={}", RFC_SECRET)));
+    assert!(uri.contains("issuer=AsQ"));
+}
+
+#[test]
+fn totp_codes_cannot_be_replayed() {
+    let mut presenter = Presenter::search_parameter(Id("presenter@example.com".to_string()));
+    presenter.begin_totp_enrollment();
+    presenter.totp_secret = Some(RFC_SECRET.to_string());
+    let now = Utc.timestamp(1111111109, 0);
+    let code_at = |time: i64| format!("{:06}", totp::code_at(RFC_SECRET, Utc.timestamp(time, 0)).unwrap());
+
+    assert!(presenter.confirm_totp_enrollment("081804", now));
+    assert!(presenter.totp_enabled);
+    assert!(!presenter.verify_second_factor("081804", now));
+    // The previous step's code is within the allowed drift, but came before the one just used.
+    assert!(!presenter.verify_second_factor(&code_at(1111111109 - 30), now));
+    assert!(presenter.verify_second_factor(&code_at(1111111109 + 30), now));
+    assert!(!presenter.verify_second_factor(&code_at(1111111109 + 30), now));
+}
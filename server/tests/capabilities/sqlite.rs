@@ -1,6 +1,24 @@
-use server::capabilities::{Capability, Save, Search};
-use server::capabilities::sqlite::SQLite;
-use server::models::{Id, Question};
+use std::net::IpAddr;
+use std::sync::{Arc, Mutex};
+
+use chrono::prelude::*;
+use chrono::Duration;
+use sqlite::Connection;
+
+use server::auth::{authenticate, authorize, check_access, role_of};
+use server::capabilities::{Aggregate, Capability, Delete, FindAll, Save, Search, Update};
+use server::capabilities::initializers::init_sqlite_tables;
+use server::capabilities::sqlite::{AnswersForQuestion, ApiTokenUse, DashboardOrder, FlagsForPresentation,
+                                   FlagsForQuestion, Import, MembersOfPresentation, MergeQuestions,
+                                   NodDistribution, PresentationsForOrganization, PresentationsForPresenter,
+                                   PresenterDashboard, QuestionNod, QuestionTotals, QuestionsForPresentation,
+                                   QuestionsOverTime, SQLite, TextSearch, TimeToAnswer, TopQuestions,
+                                   TransferOwnership};
+use server::models::{Id, AccessGrant, AccountToken, Action, Answer, ApiToken, Budget, Flag, Invitation, JoinCode,
+                     Membership, NodCount, OidcLoginAttempt, Organization, OrganizationMember, OrganizationRole,
+                     OwnershipTransfer, Presentation, PresentationDetails, Presenter, Question, QuestionCounts,
+                     QuestionStatus, RateLimits, Role, Scope, Session, TokenPurpose};
+use server::ratelimit::{BucketStore, PersistentBuckets};
 
 use super::{setup_db, teardown_db};
 
@@ -31,3 +49,734 @@ fn can_create_and_retrieve_questions() {
 
     teardown_db(db_name, db);
 }
+
+#[test]
+fn account_tokens_can_only_be_redeemed_once() {
+    let db_name = "account_tokens_can_only_be_redeemed_once.db";
+    let db = setup_db(db_name);
+
+    capability!(TestCap for SQLite,
+                composing { Save<AccountToken>,   AccountToken, String },
+                          { Search<AccountToken>, AccountToken, String },
+                          { Delete<AccountToken>, (),           String });
+
+    fn run_test<DB: TestCap>(db: &DB) {
+        let owner = Id("presenter@example.com".to_string());
+        let token = AccountToken::new(owner, TokenPurpose::PasswordReset);
+        let token = db.perform(Save(token)).unwrap();
+        let token_id = token.token.clone();
+
+        let found = db.perform(Search(AccountToken::search_parameter(token_id.clone()))).unwrap();
+        assert!(found.is_redeemable_for(TokenPurpose::PasswordReset, Utc::now()));
+        assert!(!found.is_redeemable_for(TokenPurpose::EmailVerification, Utc::now()));
+        assert!(!found.is_redeemable_for(TokenPurpose::PasswordReset, found.expires_at));
+
+        assert!(db.perform(Delete(found)).is_ok());
+        assert!(db.perform(Search(AccountToken::search_parameter(token_id.clone()))).is_err());
+        assert!(db.perform(Delete(AccountToken::search_parameter(token_id))).is_err());
+    }
+
+    run_test(&db);
+
+    teardown_db(db_name, db);
+}
+
+#[test]
+fn sessions_are_stored_by_digest_only() {
+    let db_name = "sessions_are_stored_by_digest_only.db";
+    let db = setup_db(db_name);
+
+    capability!(TestCap for SQLite,
+                composing { Save<Session>,   Session, String },
+                          { Search<Session>, Session, String });
+
+    fn run_test<DB: TestCap>(db: &DB, db_name: &str) {
+        let presenter = Presenter::search_parameter(Id("presenter@example.com".to_string()));
+        let session = db.perform(Save(Session::new(presenter))).unwrap();
+        let token = session.token.clone();
+
+        let found = db.perform(Search(Session::search_parameter(token.clone()))).unwrap();
+        assert_eq!(found.owner.0, "presenter@example.com");
+
+        let wrong_token = Id(format!("{}x", token.0));
+        assert!(db.perform(Search(Session::search_parameter(wrong_token))).is_err());
+
+        let conn = Connection::open(db_name).unwrap();
+        let leaked: i64 = conn.query_row(
+            "select count(*) from sessions where token_digest = ?1",
+            &[&token.0],
+            |row| row.get(0)).unwrap();
+        assert_eq!(leaked, 0);
+    }
+
+    run_test(&db, db_name);
+
+    teardown_db(db_name, db);
+}
+
+#[test]
+fn api_tokens_authenticate_only_within_their_scopes() {
+    let db_name = "api_tokens_authenticate_only_within_their_scopes.db";
+    let db = setup_db(db_name);
+
+    let owner = Id("presenter@example.com".to_string());
+    let token = ApiToken::new(owner, "export script".to_string(), vec![Scope::QuestionsRead]);
+    let token = db.perform(Save(token)).unwrap();
+    let credential = token.token.clone();
+
+    let presenter = authenticate(&db, credential.clone(), Scope::QuestionsRead).unwrap();
+    assert_eq!(presenter.0, "presenter@example.com");
+    assert!(authenticate(&db, credential.clone(), Scope::QuestionsWrite).is_err());
+
+    let mut found = db.perform(Search(ApiToken::search_parameter(credential.clone()))).unwrap();
+    assert!(found.last_used.is_some());
+
+    let token_id = found.id.clone();
+    found.revoked = true;
+    db.perform(Update(found)).unwrap();
+    assert!(authenticate(&db, credential.clone(), Scope::QuestionsRead).is_err());
+
+    // Recording a use never brings a revoked token back.
+    let token_use = ApiTokenUse { token_id: token_id, used_at: Utc::now() };
+    assert_eq!(db.perform(Update(token_use)).unwrap(), 0);
+    assert!(db.perform(Search(ApiToken::search_parameter(credential))).unwrap().revoked);
+
+    teardown_db(db_name, db);
+}
+
+#[test]
+fn join_codes_resolve_only_while_presentations_are_open() {
+    let db_name = "join_codes_resolve_only_while_presentations_are_open.db";
+    let db = setup_db(db_name);
+
+    let presentation = Presentation::new(Id("presenter@example.com".to_string()), "Week 1".to_string());
+    let mut presentation = db.perform(Save(presentation)).unwrap();
+    let code = presentation.join_code.clone().unwrap();
+    assert!(code.is_well_formed());
+    assert!(!code.0.contains('0') && !code.0.contains('O') && !code.0.contains('1') && !code.0.contains('L'));
+
+    let typed = JoinCode::normalize(&format!(" {}-{} ", &code.0[..3], code.0[3..].to_lowercase()));
+    let found = db.perform(Search(typed)).unwrap();
+    assert_eq!(found.id, presentation.id);
+
+    presentation.close();
+    let presentation = db.perform(Update(presentation)).unwrap();
+    assert!(presentation.join_code.is_none());
+    assert!(db.perform(Search(code)).is_err());
+
+    teardown_db(db_name, db);
+}
+
+#[test]
+fn private_presentations_require_a_grant_for_that_presentation() {
+    let db_name = "private_presentations_require_a_grant_for_that_presentation.db";
+    let db = setup_db(db_name);
+
+    let mut presentation = Presentation::new(Id("presenter@example.com".to_string()), "Week 2".to_string());
+    presentation.set_passcode(Some("open sesame"));
+    let presentation = db.perform(Save(presentation)).unwrap();
+    let other = db.perform(Save(Presentation::new(Id("presenter@example.com".to_string()), "Week 3".to_string()))).unwrap();
+
+    let presentation = db.perform(Search(Presentation::search_parameter(presentation.id))).unwrap();
+    assert!(presentation.is_private);
+    assert!(presentation.passcode_matches("open sesame"));
+    assert!(!presentation.passcode_matches("open says me"));
+    assert!(check_access(&db, &presentation, None).is_err());
+    assert!(check_access(&db, &other, None).is_ok());
+
+    let grant = db.perform(Save(AccessGrant::new(presentation.id.clone()))).unwrap();
+    let stray = db.perform(Save(AccessGrant::new(other.id.clone()))).unwrap();
+    assert!(check_access(&db, &presentation, Some(grant.token)).is_ok());
+    assert!(check_access(&db, &presentation, Some(stray.token)).is_err());
+
+    teardown_db(db_name, db);
+}
+
+#[test]
+fn persistent_buckets_survive_being_reopened() {
+    let db_name = "persistent_buckets_survive_being_reopened.db";
+    let db = setup_db(db_name);
+
+    let budget = Budget::new(1, 1);
+    let now = Utc::now();
+    let presentation = Presentation::new(Id("presenter@example.com".to_string()), "Week 4".to_string());
+    let presentation = db.perform(Save(presentation)).unwrap();
+    assert_eq!(presentation.rate_limits, RateLimits::default());
+
+    assert_eq!(PersistentBuckets::new(db.clone()).take("nod:week4:ip:10.0.0.1", budget, now).unwrap(), Ok(()));
+    assert!(PersistentBuckets::new(db.clone()).take("nod:week4:ip:10.0.0.1", budget, now).unwrap().is_err());
+
+    teardown_db(db_name, db);
+}
+
+#[test]
+fn question_reviews_are_stored() {
+    let db_name = "question_reviews_are_stored.db";
+    let db = setup_db(db_name);
+
+    let mut question = Question::new(Id("moderated".to_string()), "Is this on topic?".to_string());
+    question.status = QuestionStatus::Pending;
+    let question = db.perform(Save(question)).unwrap();
+    let mut found = db.perform(Search(Question::search_parameter(question.id.clone()))).unwrap();
+    assert_eq!(found.status, QuestionStatus::Pending);
+    assert!(!found.is_visible());
+
+    found.review(QuestionStatus::Rejected);
+    db.perform(Update(found)).unwrap();
+    let found = db.perform(Search(Question::search_parameter(question.id))).unwrap();
+    assert_eq!(found.status, QuestionStatus::Rejected);
+    assert!(found.reviewed_at.is_some());
+
+    teardown_db(db_name, db);
+}
+
+#[test]
+fn audience_members_flag_a_question_only_once() {
+    let db_name = "audience_members_flag_a_question_only_once.db";
+    let db = setup_db(db_name);
+
+    let presentation = db.perform(Save(Presentation::new(Id("presenter@example.com".to_string()), "Week 5".to_string()))).unwrap();
+    let question = db.perform(Save(Question::new(presentation.id.clone(), "Spam?".to_string()))).unwrap();
+    let first = Id("audience-1".to_string());
+    let second = Id("audience-2".to_string());
+
+    let address = "192.0.2.1".parse::<IpAddr>().unwrap();
+
+    assert!(db.perform(Save(Flag::new(question.id.clone(), first.clone(), address, "Spam".to_string()))).is_ok());
+    assert!(db.perform(Save(Flag::new(question.id.clone(), first, address, "Still spam".to_string()))).is_err());
+    assert!(db.perform(Save(Flag::new(question.id.clone(), second, address, "Rude".to_string()))).is_ok());
+
+    let flags = db.perform(FindAll(FlagsForQuestion { question_id: question.id.clone() })).unwrap();
+    assert_eq!(flags.len(), 2);
+    assert!(flags.iter().all(|flag| flag.source == "address:192.0.2.1"));
+    assert!(!presentation.flags_warrant_review(flags.len()));
+    let flags = db.perform(FindAll(FlagsForPresentation { presentation_id: presentation.id })).unwrap();
+    assert_eq!(flags.iter().map(|flag| flag.reason.as_str()).collect::<Vec<_>>(), vec!["Spam", "Rude"]);
+
+    teardown_db(db_name, db);
+}
+
+#[test]
+fn nods_are_only_counted_for_visible_questions() {
+    let db_name = "nods_are_only_counted_for_visible_questions.db";
+    let db = setup_db(db_name);
+
+    let presentation = Id("nodding".to_string());
+    let question = db.perform(Save(Question::new(presentation.clone(), "Is there a break?".to_string()))).unwrap();
+    let mut held = Question::new(presentation.clone(), "Is there a break yet?".to_string());
+    held.hold_for_review("Too similar to another question.".to_string());
+    let held = db.perform(Save(held)).unwrap();
+
+    for _ in 0..2 {
+        assert_eq!(db.perform(Update(QuestionNod { question_id: question.id.clone() })).unwrap(), 1);
+    }
+    assert_eq!(db.perform(Update(QuestionNod { question_id: held.id.clone() })).unwrap(), 0);
+
+    let question = db.perform(Search(Question::search_parameter(question.id))).unwrap();
+    assert_eq!(question.nods, 2);
+    let held = db.perform(Search(Question::search_parameter(held.id))).unwrap();
+    assert_eq!(held.nods, 0);
+
+    teardown_db(db_name, db);
+}
+
+#[test]
+fn merging_questions_sums_their_nods() {
+    let db_name = "merging_questions_sums_their_nods.db";
+    let db = setup_db(db_name);
+
+    let presentation = Id("merging".to_string());
+    let mut target = Question::new(presentation.clone(), "Will the slides be shared?".to_string());
+    target.nods = 3;
+    let mut duplicate = Question::new(presentation.clone(), "Are the slides online?".to_string());
+    duplicate.nods = 2;
+    let other = Question::new(Id("elsewhere".to_string()), "Slides?".to_string());
+    let target = db.perform(Save(target)).unwrap();
+    let duplicate = db.perform(Save(duplicate)).unwrap();
+    let other = db.perform(Save(other)).unwrap();
+
+    assert!(db.perform(Update(MergeQuestions { target: target.id.clone(), originals: vec![other.id] })).is_err());
+    let merged = db.perform(Update(MergeQuestions {
+        target: target.id.clone(),
+        originals: vec![duplicate.id.clone()],
+    })).unwrap();
+    assert_eq!(merged.nods, 5);
+    let duplicate = db.perform(Search(Question::search_parameter(duplicate.id))).unwrap();
+    assert_eq!(duplicate.merged_into.map(|id| id.0), Some(target.id.0));
+    assert!(!duplicate.is_visible());
+
+    teardown_db(db_name, db);
+}
+
+#[test]
+fn questions_and_answers_are_found_by_full_text_search() {
+    let db_name = "questions_and_answers_are_found_by_full_text_search.db";
+    let db = setup_db(db_name);
+
+    let presentation = Id("searchable".to_string());
+    let question = db.perform(Save(Question::new(presentation.clone(), "How do lifetimes work?".to_string()))).unwrap();
+    let mut hidden = Question::new(presentation.clone(), "Are lifetimes on the exam?".to_string());
+    hidden.status = QuestionStatus::Pending;
+    db.perform(Save(hidden)).unwrap();
+    let answer = "The borrow checker infers them.".to_string();
+    let answer = Answer::new(Id("presenter".to_string()), question.id.clone(), answer);
+    db.perform(Save(answer)).unwrap();
+    let search = |text: &str, include_hidden: bool| db.perform(FindAll(TextSearch {
+        presentation_id: presentation.clone(),
+        text: text.to_string(),
+        include_hidden: include_hidden,
+        limit: 10,
+    })).unwrap();
+
+    assert_eq!(search("lifetimes", false).len(), 1);
+    assert_eq!(search("lifetimes", true).len(), 2);
+    let found = search("\"borrow checker\"", false);
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].kind, "answer");
+    assert_eq!(found[0].question.0, question.id.0);
+    assert!(found[0].snippet.contains("<mark>borrow</mark>"));
+    assert!(search("\"checker borrow\"", false).is_empty());
+
+    let mut question = question;
+    question.text = "How does ownership work?".to_string();
+    db.perform(Update(question)).unwrap();
+    assert!(search("lifetimes", false).is_empty());
+
+    teardown_db(db_name, db);
+}
+
+#[test]
+fn imports_create_everything_or_nothing() {
+    let db_name = "imports_create_everything_or_nothing.db";
+    let db = setup_db(db_name);
+
+    let presentation = Presentation::new(Id("presenter".to_string()), "Imported".to_string());
+    let question = Question::new(presentation.id.clone(), "Seeded before the talk?".to_string());
+    let answer = Answer::new(presentation.creator.clone(), question.id.clone(), "Yes.".to_string());
+    let question_id = question.id.clone();
+    let import = db.perform(Save(Import {
+        presentation: presentation,
+        new_presentation: true,
+        questions: vec![(question, vec![answer])],
+    })).unwrap();
+    let presentation_id = import.presentation.id.clone();
+    assert!(db.perform(Search(Presentation::search_parameter(presentation_id.clone()))).is_ok());
+    let answers = db.perform(FindAll(AnswersForQuestion { question_id: question_id.clone() })).unwrap();
+    assert_eq!(answers.len(), 1);
+
+    // The second question reuses the first one's ID, so the new question before it is not kept either.
+    let fresh = Question::new(presentation_id.clone(), "Kept?".to_string());
+    let mut clash = Question::new(presentation_id.clone(), "Clashes".to_string());
+    clash.id = question_id;
+    let result = db.perform(Save(Import {
+        presentation: import.presentation,
+        new_presentation: false,
+        questions: vec![(fresh, vec![]), (clash, vec![])],
+    }));
+    assert!(result.is_err());
+    let questions = db.perform(FindAll(QuestionsForPresentation { presentation_id: presentation_id })).unwrap();
+    assert_eq!(questions.len(), 1);
+
+    teardown_db(db_name, db);
+}
+
+#[test]
+fn presentation_statistics_are_aggregated() {
+    let db_name = "presentation_statistics_are_aggregated.db";
+    let db = setup_db(db_name);
+
+    let presentation = Id("aggregated".to_string());
+    let start = Utc.ymd(2018, 5, 1).and_hms(10, 0, 0);
+    for (minute, nods, answer_after) in vec![(0, 3, Some(60)), (1, 0, Some(180)), (7, 3, None), (8, 1, Some(120))] {
+        let mut question = Question::new(presentation.clone(), format!("Question at minute {}?", minute));
+        question.ask_date = start + Duration::minutes(minute);
+        question.nods = nods;
+        let question = db.perform(Save(question)).unwrap();
+        if let Some(seconds) = answer_after {
+            let mut answer = Answer::new(Id("presenter".to_string()), question.id.clone(), "Answered.".to_string());
+            answer.written_date = question.ask_date + Duration::seconds(seconds);
+            db.perform(Save(answer)).unwrap();
+        }
+    }
+    // Rejected questions are never shown to the audience, so they are left out of every statistic.
+    let mut rejected = Question::new(presentation.clone(), "Spam?".to_string());
+    rejected.ask_date = start + Duration::minutes(2);
+    rejected.nods = 5;
+    rejected.status = QuestionStatus::Rejected;
+    let rejected = db.perform(Save(rejected)).unwrap();
+    let mut answer = Answer::new(Id("presenter".to_string()), rejected.id.clone(), "No.".to_string());
+    answer.written_date = rejected.ask_date + Duration::seconds(10);
+    db.perform(Save(answer)).unwrap();
+    let id = || presentation.clone();
+
+    let counts = db.perform(Aggregate(QuestionTotals { presentation_id: id() })).unwrap();
+    assert_eq!(counts, QuestionCounts { questions: 4, answered: 3 });
+    assert_eq!(counts.answered_ratio(), 0.75);
+    let distribution = db.perform(Aggregate(NodDistribution { presentation_id: id() })).unwrap();
+    assert_eq!(distribution, vec![
+        NodCount { nods: 0, questions: 1 }, NodCount { nods: 1, questions: 1 }, NodCount { nods: 3, questions: 2 },
+    ]);
+    let buckets = db.perform(Aggregate(QuestionsOverTime { presentation_id: id(), bucket_minutes: 5 })).unwrap();
+    assert_eq!(buckets.iter().map(|bucket| (bucket.start, bucket.questions)).collect::<Vec<_>>(),
+               vec![(start, 2), (start + Duration::minutes(5), 2)]);
+    let median = db.perform(Aggregate(TimeToAnswer { presentation_id: id() })).unwrap().unwrap();
+    assert!((median - 120.0).abs() < 0.01);
+    let top = db.perform(FindAll(TopQuestions { presentation_id: id(), limit: 2 })).unwrap();
+    assert_eq!(top.iter().map(|question| question.nods).collect::<Vec<_>>(), vec![3, 3]);
+    assert!(db.perform(Aggregate(TimeToAnswer { presentation_id: Id("empty".to_string()) })).unwrap().is_none());
+
+    teardown_db(db_name, db);
+}
+
+#[test]
+fn dashboards_summarize_each_presentation() {
+    let db_name = "dashboards_summarize_each_presentation.db";
+    let db = setup_db(db_name);
+
+    let presenter = Id("busy@example.com".to_string());
+    let start = Utc.ymd(2018, 9, 3).and_hms(9, 0, 0);
+    let mut quiet = Presentation::new(presenter.clone(), "Lecture 1".to_string());
+    quiet.creation_date = start;
+    quiet.is_open_to_questions = false;
+    quiet.join_code = None;
+    let quiet = db.perform(Save(quiet)).unwrap();
+    let mut lively = Presentation::new(presenter.clone(), "Lecture 2".to_string());
+    lively.creation_date = start + Duration::days(7);
+    let lively = db.perform(Save(lively)).unwrap();
+    let mut question = Question::new(quiet.id.clone(), "Asked long after?".to_string());
+    question.ask_date = start + Duration::days(10);
+    db.perform(Save(question)).unwrap();
+    let mut answered = Question::new(quiet.id.clone(), "Answered?".to_string());
+    answered.ask_date = start + Duration::hours(1);
+    let answered = db.perform(Save(answered)).unwrap();
+    let mut answer = Answer::new(presenter.clone(), answered.id.clone(), "Yes.".to_string());
+    answer.written_date = start + Duration::hours(2);
+    db.perform(Save(answer)).unwrap();
+    let dashboard = |after: Option<DateTime<Utc>>, open: Option<bool>, order: DashboardOrder| db
+        .perform(Aggregate(PresenterDashboard {
+            presenter_id: presenter.clone(),
+            created_after: after,
+            created_before: None,
+            open: open,
+            tag: None,
+            order: order,
+        }))
+        .unwrap();
+
+    let found = dashboard(None, None, DashboardOrder::LastActivity);
+    assert_eq!(found.len(), 2);
+    assert_eq!(found[0].presentation.id, quiet.id);
+    assert_eq!((found[0].questions, found[0].unanswered), (2, 1));
+    assert_eq!(found[0].last_activity, start + Duration::days(10));
+    assert_eq!((found[1].questions, found[1].last_activity), (0, lively.creation_date));
+    let found = dashboard(None, None, DashboardOrder::Created);
+    assert_eq!(found[0].presentation.id, lively.id);
+    assert_eq!(dashboard(Some(start + Duration::days(1)), None, DashboardOrder::Created).len(), 1);
+    assert_eq!(dashboard(None, Some(false), DashboardOrder::Created)[0].presentation.id, quiet.id);
+    let all = db.perform(FindAll(PresentationsForPresenter { presenter_id: presenter.clone(), tag: None })).unwrap();
+    assert_eq!(all.len(), 2);
+    assert!(all.iter().all(|found| found.role == Role::Owner));
+
+    // Presentations the presenter was invited to are summarized along with their role.
+    let guest = Presentation::new(Id("host@example.com".to_string()), "Guest lecture".to_string());
+    let guest = db.perform(Save(guest)).unwrap();
+    db.perform(Save(Membership::new(guest.id.clone(), presenter.clone(), Role::Moderator))).unwrap();
+    db.perform(Save(Presentation::new(Id("host@example.com".to_string()), "Elsewhere".to_string()))).unwrap();
+    let found = dashboard(None, None, DashboardOrder::Created);
+    assert_eq!(found.len(), 3);
+    assert_eq!((&found[0].presentation.id, found[0].role), (&guest.id, Role::Moderator));
+    assert_eq!(found[1].role, Role::Owner);
+    let all = db.perform(FindAll(PresentationsForPresenter { presenter_id: presenter.clone(), tag: None })).unwrap();
+    assert_eq!(all.iter().map(|found| found.role).collect::<Vec<_>>(), vec![Role::Moderator, Role::Owner, Role::Owner]);
+
+    teardown_db(db_name, db);
+}
+
+#[test]
+fn presentation_details_are_stored_and_tags_filter_lists() {
+    let db_name = "presentation_details_are_stored_and_tags_filter_lists.db";
+    let db = setup_db(db_name);
+
+    let start = Utc.ymd(2018, 10, 1).and_hms(14, 0, 0);
+    let details = PresentationDetails {
+        title: "  Lifetimes  ".to_string(),
+        description: "Why the borrow checker complains.".to_string(),
+        scheduled_start: Some(start),
+        scheduled_end: Some(start + Duration::minutes(50)),
+        language: Some("en-GB".to_string()),
+        tags: vec!["Rust".to_string(), "week-5".to_string(), "rust".to_string()],
+        external_link: Some("https://example.com/slides".to_string()),
+    };
+    let invalid = |change: &Fn(&mut PresentationDetails)| {
+        let mut details = details.clone();
+        change(&mut details);
+        details.validate().is_err()
+    };
+    assert!(invalid(&|details| details.scheduled_end = Some(start)));
+    assert!(invalid(&|details| details.language = Some("english".to_string())));
+    assert!(invalid(&|details| details.tags = vec!["\"quoted\"".to_string()]));
+    assert!(invalid(&|details| details.external_link = Some("javascript:alert(1)".to_string())));
+
+    let presenter = Id("tagger@example.com".to_string());
+    let mut presentation = Presentation::new(presenter.clone(), String::new());
+    presentation.set_details(details.validate().unwrap());
+    let presentation = db.perform(Save(presentation)).unwrap();
+    db.perform(Save(Presentation::new(presenter.clone(), "Untagged".to_string()))).unwrap();
+    let tagged = |tag: &str| db.perform(FindAll(PresentationsForPresenter {
+        presenter_id: presenter.clone(),
+        tag: Some(tag.to_string()),
+    })).unwrap();
+
+    let found = tagged("RUST");
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].presentation.title, "Lifetimes");
+    assert_eq!(found[0].presentation.tags, vec!["rust".to_string(), "week-5".to_string()]);
+    assert_eq!(found[0].presentation.scheduled_end, presentation.scheduled_end);
+    assert_eq!(found[0].presentation.external_link, presentation.external_link);
+    assert!(tagged("week").is_empty());
+
+    teardown_db(db_name, db);
+}
+
+#[test]
+fn members_may_only_do_what_their_role_permits() {
+    let db_name = "members_may_only_do_what_their_role_permits.db";
+    let db = setup_db(db_name);
+
+    let owner = Id("owner@example.com".to_string());
+    let moderator = Id("moderator@example.com".to_string());
+    let presentation = db.perform(Save(Presentation::new(owner.clone(), "Traits".to_string()))).unwrap();
+    let session_for = |presenter: &Id| {
+        let session = Session::new(Presenter::search_parameter(presenter.clone()));
+        db.perform(Save(session)).unwrap().token
+    };
+    let allowed = |presenter: &Id, action: Action| {
+        authorize(&db, session_for(presenter), Scope::QuestionsWrite, &presentation, action).is_ok()
+    };
+    assert!(allowed(&owner, Action::ManageMembers));
+    assert!(!allowed(&moderator, Action::ViewQuestions));
+
+    let invitation = Invitation::new(presentation.id.clone(), Id("Moderator@Example.com".to_string()),
+                                     Role::Moderator, owner.clone());
+    let invitation = db.perform(Save(invitation)).unwrap();
+    let found = db.perform(Search(Invitation::search_parameter(invitation.token.clone()))).unwrap();
+    assert!(found.may_be_accepted_by(&moderator, Utc::now()));
+    assert!(!found.may_be_accepted_by(&owner, Utc::now()));
+    assert!(!found.may_be_accepted_by(&moderator, found.expires_at));
+    db.perform(Save(Membership::new(presentation.id.clone(), moderator.clone(), found.role))).unwrap();
+    db.perform(Delete(found)).unwrap();
+    assert!(db.perform(Search(Invitation::search_parameter(invitation.token))).is_err());
+
+    assert!(allowed(&moderator, Action::ModerateQuestions));
+    assert!(!allowed(&moderator, Action::AnswerQuestions));
+    assert!(!allowed(&moderator, Action::ChangeSettings));
+
+    db.perform(Save(Membership::new(presentation.id.clone(), moderator.clone(), Role::Presenter))).unwrap();
+    assert!(allowed(&moderator, Action::AnswerQuestions));
+    let members = db.perform(FindAll(MembersOfPresentation { presentation_id: presentation.id.clone() })).unwrap();
+    assert_eq!(members.len(), 1);
+    assert_eq!(members[0].role, Role::Presenter);
+
+    db.perform(Delete(Membership::search_parameter(presentation.id.clone(), moderator.clone()))).unwrap();
+    assert!(!allowed(&moderator, Action::ViewQuestions));
+
+    teardown_db(db_name, db);
+}
+
+#[test]
+fn organization_admins_own_the_organizations_presentations() {
+    let db_name = "organization_admins_own_the_organizations_presentations.db";
+    let db = setup_db(db_name);
+
+    let admin = Id("head@example.com".to_string());
+    let lecturer = Id("lecturer@example.com".to_string());
+    let mut organization = db.perform(Save(Organization::new("Physics".to_string()))).unwrap();
+    organization.defaults.rate_limits = RateLimits { ask: Budget::new(2, 1), nod: Budget::new(10, 10) };
+    organization.defaults.retention_days = Some(30);
+    db.perform(Update(organization.clone())).unwrap();
+    let organization = db.perform(Search(Organization::search_parameter(organization.id.clone()))).unwrap();
+    assert_eq!(organization.defaults.retention_days, Some(30));
+    db.perform(Save(OrganizationMember::new(organization.id.clone(), admin.clone(), OrganizationRole::Admin))).unwrap();
+    db.perform(Save(OrganizationMember::new(organization.id.clone(), lecturer.clone(), OrganizationRole::Member)))
+        .unwrap();
+
+    let mut presentation = Presentation::new(lecturer.clone(), "Optics".to_string());
+    presentation.join_organization(organization.id.clone(), organization.defaults.clone());
+    let presentation = db.perform(Save(presentation)).unwrap();
+    let private = db.perform(Save(Presentation::new(admin.clone(), "Budget".to_string()))).unwrap();
+
+    let found = db.perform(FindAll(PresentationsForOrganization { organization_id: organization.id.clone() }))
+        .unwrap();
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].rate_limits, organization.defaults.rate_limits);
+    assert_eq!(found[0].retention_days, Some(30));
+    assert_eq!(role_of(&db, &admin, &found[0]), Some(Role::Owner));
+    assert_eq!(role_of(&db, &lecturer, &found[0]), Some(Role::Owner));
+    assert_eq!(role_of(&db, &lecturer, &private), None);
+
+    let mut customized = Presentation::new(lecturer.clone(), "Lasers".to_string());
+    customized.set_retention(Some(7)).unwrap();
+    customized.join_organization(organization.id.clone(), organization.defaults.clone());
+    assert_eq!(customized.retention_days, Some(7));
+    assert_eq!(customized.rate_limits, organization.defaults.rate_limits);
+
+    db.perform(Delete(OrganizationMember::search_parameter(organization.id.clone(), admin.clone()))).unwrap();
+    assert_eq!(role_of(&db, &admin, &presentation), None);
+
+    teardown_db(db_name, db);
+}
+
+#[test]
+fn presentations_change_hands_only_when_the_offer_is_accepted() {
+    let db_name = "presentations_change_hands_only_when_the_offer_is_accepted.db";
+    let db = setup_db(db_name);
+
+    let leaving = Id("leaving@example.com".to_string());
+    let arriving = Id("arriving@example.com".to_string());
+    let mut presentation = Presentation::new(leaving.clone(), "Compilers".to_string());
+    presentation.set_passcode(Some("lexer"));
+    presentation.requires_approval = true;
+    let presentation = db.perform(Save(presentation)).unwrap();
+    db.perform(Save(Membership::new(presentation.id.clone(), arriving.clone(), Role::Moderator))).unwrap();
+
+    let copy = presentation.copy_for(arriving.clone());
+    assert!(copy.id != presentation.id && copy.join_code != presentation.join_code);
+    assert!(copy.requires_approval && copy.passcode_matches("lexer") && !copy.passcode_matches("parser"));
+    assert_eq!(copy.creator, arriving);
+
+    let offered_to = Id("Arriving@Example.com".to_string());
+    db.perform(Save(OwnershipTransfer::new(presentation.id.clone(), leaving.clone(), offered_to))).unwrap();
+    let offer = db.perform(Search(OwnershipTransfer::search_parameter(presentation.id.clone()))).unwrap();
+    assert!(offer.may_be_accepted_by(&arriving, Utc::now()));
+    assert!(!offer.may_be_accepted_by(&leaving, Utc::now()));
+    let find = || db.perform(Search(Presentation::search_parameter(presentation.id.clone()))).unwrap();
+    assert_eq!(find().creator, leaving);
+
+    let accept = |from: &Id| db.perform(Update(TransferOwnership {
+        presentation_id: presentation.id.clone(),
+        from: from.clone(),
+        to: arriving.clone(),
+    }));
+    assert!(accept(&Id("someone@example.com".to_string())).is_err());
+    accept(&leaving).unwrap();
+    assert_eq!(find().creator, arriving);
+    assert!(db.perform(Search(OwnershipTransfer::search_parameter(presentation.id.clone()))).is_err());
+    assert!(db.perform(Search(Membership::search_parameter(presentation.id.clone(), arriving.clone()))).is_err());
+    let stays = db.perform(Search(Membership::search_parameter(presentation.id.clone(), leaving.clone()))).unwrap();
+    assert_eq!(stays.role, Role::Presenter);
+    assert!(accept(&leaving).is_err());
+
+    teardown_db(db_name, db);
+}
+
+#[test]
+fn presenters_keep_their_second_factor_settings() {
+    let db_name = "presenters_keep_their_second_factor_settings.db";
+    let db = setup_db(db_name);
+
+    let email = Id("presenter@example.com".to_string());
+    let presenter = db.perform(Save(Presenter::new(email.0.clone(), "hunter2".to_string()))).unwrap();
+    assert!(db.perform(Save(Presenter::new(email.0.clone(), "taken".to_string()))).is_err());
+
+    let mut presenter = db.perform(Search(Presenter::search_parameter(presenter.email_address))).unwrap();
+    assert!(presenter.password_matches("hunter2"));
+    assert!(!presenter.totp_enabled);
+    let codes = presenter.begin_totp_enrollment();
+    presenter.totp_enabled = true;
+    presenter.totp_last_step = Some(37037036);
+    db.perform(Update(presenter)).unwrap();
+
+    let presenter = db.perform(Search(Presenter::search_parameter(email.clone()))).unwrap();
+    assert!(presenter.totp_enabled);
+    assert!(presenter.totp_secret.is_some());
+    assert_eq!(presenter.totp_last_step, Some(37037036));
+    assert_eq!(presenter.recovery_codes.len(), codes.len());
+
+    let stranger = Presenter::search_parameter(Id("stranger@example.com".to_string()));
+    assert!(db.perform(Update(stranger)).is_err());
+
+    teardown_db(db_name, db);
+}
+
+#[test]
+fn expired_login_attempts_are_forgotten() {
+    let db_name = "expired_login_attempts_are_forgotten.db";
+    let db = setup_db(db_name);
+
+    let mut abandoned = OidcLoginAttempt::new(Id("abandoned".to_string()), "nonce".to_string(), "verifier".to_string());
+    abandoned.expires_at = Utc::now() - Duration::minutes(1);
+    db.perform(Save(abandoned)).unwrap();
+    assert!(db.perform(Search(OidcLoginAttempt::search_parameter(Id("abandoned".to_string())))).is_ok());
+
+    db.perform(Save(OidcLoginAttempt::new(Id("fresh".to_string()), "nonce".to_string(), "verifier".to_string()))).unwrap();
+    assert!(db.perform(Search(OidcLoginAttempt::search_parameter(Id("abandoned".to_string())))).is_err());
+    assert!(db.perform(Search(OidcLoginAttempt::search_parameter(Id("fresh".to_string())))).is_ok());
+
+    teardown_db(db_name, db);
+}
+
+#[test]
+fn databases_from_earlier_versions_are_upgraded() {
+    let connection = Connection::open_in_memory().unwrap();
+    connection.execute_batch(
+        "create table presentations (
+            id                      text primary key,
+            creator                 text not null,
+            title                   text not null,
+            is_open_to_questions    integer not null,
+            creation_date           text not null
+        );
+        create table questions (
+            id              text primary key,
+            presentation    text not null,
+            text            text not null,
+            nods            integer not null,
+            answered        integer not null,
+            ask_date        text not null
+        );
+        create table account_tokens (
+            token           text primary key,
+            owner           text not null,
+            purpose         text not null,
+            expires_at      text not null
+        );
+        create table access_grants (
+            token           text primary key,
+            presentation    text not null,
+            expires_at      text not null
+        );
+        create table invitations (
+            token           text primary key,
+            presentation    text not null,
+            email           text not null,
+            role            text not null,
+            invited_by      text not null,
+            expires_at      text not null
+        );
+        insert into presentations values
+            ('week-4', 'presenter@example.com', 'Week 4', 1, '2026-10-01T09:00:00+00:00');
+        insert into questions values
+            ('question-1', 'week-4', 'Is this on the exam?', 2, 0, '2026-10-01T09:05:00+00:00');").unwrap();
+    let db = SQLite::new(Arc::new(Mutex::new(connection)));
+    init_sqlite_tables(&db).unwrap();
+    init_sqlite_tables(&db).unwrap();
+
+    let presentation = db.perform(Search(Presentation::search_parameter(Id("week-4".to_string())))).unwrap();
+    assert_eq!(presentation.title, "Week 4");
+    assert_eq!(presentation.rate_limits, RateLimits::default());
+    assert!(presentation.filters.is_empty());
+    assert!(presentation.tags.is_empty());
+    assert_eq!(presentation.flag_threshold, 3);
+    let question = db.perform(Search(Question::search_parameter(Id("question-1".to_string())))).unwrap();
+    assert_eq!(question.nods, 2);
+    assert!(question.is_visible());
+
+    let token = AccountToken::new(Id("presenter@example.com".to_string()), TokenPurpose::PasswordReset);
+    assert!(db.perform(Save(token)).is_ok());
+    let grant = db.perform(Save(AccessGrant::new(presentation.id.clone()))).unwrap();
+    assert!(db.perform(Search(AccessGrant::search_parameter(grant.token))).is_ok());
+    let invitation = Invitation::new(presentation.id, Id("moderator@example.com".to_string()), Role::Moderator,
+                                     presentation.creator);
+    let invitation = db.perform(Save(invitation)).unwrap();
+    assert!(db.perform(Search(Invitation::search_parameter(invitation.token))).is_ok());
+}
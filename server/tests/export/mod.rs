@@ -0,0 +1,52 @@
+use json;
+
+use server::export::{self, ExportedQuestion, Format};
+use server::models::{Answer, Id, Presentation, Question};
+
+
+fn export(format: Format) -> String {
+    let presentation = Presentation::new(Id("presenter@example.com".to_string()), "Ownership, explained".to_string());
+    let mut question = Question::new(presentation.id.clone(), "What does \"move\" mean, exactly?".to_string());
+    question.nods = 2;
+    question.answered = true;
+    let answer = "=Ownership changes hands.".to_string();
+    let answer = Answer::new(presentation.creator.clone(), question.id.clone(), answer);
+    let entries = vec![
+        ExportedQuestion { question: question, answers: vec![answer] },
+        ExportedQuestion { question: Question::new(presentation.id.clone(), "Next?".to_string()), answers: vec![] },
+    ];
+
+    let mut out = vec![];
+    export::write_header(format, &presentation, &mut out).unwrap();
+    for (index, entry) in entries.iter().enumerate() {
+        export::write_question(format, index == 0, entry, &mut out).unwrap();
+    }
+    export::write_footer(format, &mut out).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn csv_exports_quote_awkward_fields() {
+    let csv = export(Format::from_str("csv").unwrap());
+    let rows: Vec<&str> = csv.lines().collect();
+    assert_eq!(rows[0], "id,text,nods,answered,timeAsked,answers,status,reviewReason,mergedInto");
+    assert!(rows[1].contains(",\"What does \"\"move\"\" mean, exactly?\",2,true,"));
+    assert!(rows[1].ends_with(",'=Ownership changes hands.,approved,,"));
+}
+
+#[test]
+fn json_exports_are_valid_documents() {
+    let document: json::Value = json::from_str(&export(Format::Json)).unwrap();
+    assert_eq!(document["presentation"]["title"], "Ownership, explained");
+    assert_eq!(document["questions"].as_array().unwrap().len(), 2);
+    assert_eq!(document["questions"][0]["answers"][0]["text"], "=Ownership changes hands.");
+}
+
+#[test]
+fn markdown_exports_quote_answers() {
+    let markdown = export(Format::Markdown);
+    assert!(markdown.starts_with("# Ownership, explained\n"));
+    assert!(markdown.contains("## What does \"move\" mean, exactly?\n"));
+    assert!(markdown.contains("2 nods · answered"));
+    assert!(markdown.contains("> =Ownership changes hands.\n"));
+}
@@ -0,0 +1,45 @@
+use server::filters::{self, LinkFilter, QuestionFilter, RepeatedCharacterFilter, Verdict, WordListFilter};
+use server::models::{FilterAction, FilterSetting};
+
+
+#[test]
+fn word_lists_see_through_substituted_characters() {
+    let filter = WordListFilter::new(&["Heck".to_string()], FilterAction::Reject);
+    assert!(filter.check("What the h3ck is a monad?").ne(&Verdict::Accept));
+    assert_eq!(filter.check("Can you check the slides?"), Verdict::Accept);
+}
+
+#[test]
+fn links_are_counted_in_every_form() {
+    let filter = LinkFilter::new(1, FilterAction::Review);
+    assert_eq!(filter.check("Is https://example.com/docs up to date?"), Verdict::Accept);
+    match filter.check("Buy at www.example.com or cheap-pills.ru, or mail sales@example.net") {
+        Verdict::Review(_) => (),
+        verdict => panic!("expected review, got {:?}", verdict),
+    }
+    assert_eq!(filter.check("Is 3.14 close enough, e.g. for pi?"), Verdict::Accept);
+}
+
+#[test]
+fn repeated_characters_are_limited() {
+    let filter = RepeatedCharacterFilter::new(3, FilterAction::Reject);
+    assert_eq!(filter.check("Sooo what happens next?"), Verdict::Accept);
+    assert!(filter.check("SoOoO what happens next?").ne(&Verdict::Accept));
+}
+
+#[test]
+fn rejections_take_precedence_over_reviews() {
+    let settings = vec![
+        FilterSetting::Links { max_links: 0, action: FilterAction::Review },
+        FilterSetting::RepeatedCharacters { max_repeats: 2, action: FilterAction::Reject },
+    ];
+    match filters::check_all(&settings, "see example.com") {
+        Verdict::Review(_) => (),
+        verdict => panic!("expected review, got {:?}", verdict),
+    }
+    match filters::check_all(&settings, "see example.com!!!") {
+        Verdict::Reject(_) => (),
+        verdict => panic!("expected rejection, got {:?}", verdict),
+    }
+    assert_eq!(filters::check_all(&[], "anything at all"), Verdict::Accept);
+}
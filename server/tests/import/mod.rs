@@ -0,0 +1,105 @@
+use server::export::{self, ExportedQuestion, Format};
+use server::import::{self, RowError};
+use server::models::{Answer, Id, Presentation, Question, QuestionStatus};
+
+
+#[test]
+fn csv_exports_can_be_imported() {
+    let presentation = Presentation::new(Id("presenter@example.com".to_string()), "Ownership".to_string());
+    let mut question = Question::new(presentation.id.clone(), "What does \"move\" mean,\nexactly?".to_string());
+    question.nods = 4;
+    let answer = Answer::new(presentation.creator.clone(), question.id.clone(), "-1 copies.".to_string());
+    let entry = ExportedQuestion { question: question, answers: vec![answer] };
+    let mut out = vec![];
+    export::write_header(Format::Csv, &presentation, &mut out).unwrap();
+    export::write_question(Format::Csv, true, &entry, &mut out).unwrap();
+
+    let document = import::parse(Format::Csv, &String::from_utf8(out).unwrap()).unwrap();
+    assert_eq!(document.questions.len(), 1);
+    let imported = &document.questions[0];
+    assert_eq!(imported.text, entry.question.text);
+    assert_eq!(imported.nods, 4);
+    assert_eq!(imported.asked_at, Some(entry.question.ask_date));
+    assert_eq!(imported.answers, vec!["-1 copies.".to_string()]);
+}
+
+#[test]
+fn every_invalid_row_is_reported() {
+    let csv = "text,nods,answered\nFine?,1,false\n,2,true\nNods?,lots,true\nShort,1\n";
+    assert_eq!(import::parse(Format::Csv, csv), Err(vec![
+        RowError { row: 2, message: "The question has no text.".to_string() },
+        RowError { row: 3, message: "Nods must be a whole number.".to_string() },
+        RowError { row: 4, message: "Expected 3 fields but found 2.".to_string() },
+    ]));
+
+    let json = r#"{"questions": [{"question": {"text": "Fine?"}}, {"question": {"nods": 3}}]}"#;
+    let errors = import::parse(Format::Json, json).unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].row, 2);
+}
+
+#[test]
+fn questions_sharing_an_id_are_reported() {
+    let csv = "id,text\nq1,First?\nq2,Second?\nq1,Third?\nq1,Fourth?\n";
+    assert_eq!(import::parse(Format::Csv, csv), Err(vec![
+        RowError { row: 3, message: "The ID q1 is already used in row 1.".to_string() },
+        RowError { row: 4, message: "The ID q1 is already used in row 1.".to_string() },
+    ]));
+
+    let json = r#"{"questions": [{"question": {"id": "q1", "text": "First?"}},
+                                 {"question": {"id": "q1", "text": "Second?"}}]}"#;
+    assert_eq!(import::parse(Format::Json, json), Err(vec![
+        RowError { row: 2, message: "The ID q1 is already used in row 1.".to_string() },
+    ]));
+}
+
+#[test]
+fn json_exports_keep_their_title_and_answers() {
+    let json = r#"{"presentation": {"title": "Traits"},
+                   "questions": [{"question": {"text": "Why dyn?", "nods": 2, "answered": true},
+                                  "answers": [{"text": "For trait objects."}, {"text": "See chapter 17."}]}]}"#;
+    let document = import::parse(Format::Json, json).unwrap();
+    assert_eq!(document.title, Some("Traits".to_string()));
+    assert_eq!(document.questions[0].answers.len(), 2);
+
+    let presentation = Presentation::new(Id("presenter@example.com".to_string()), "Traits".to_string());
+    let records = import::records(document, &presentation);
+    assert_eq!(records[0].0.presentation, presentation.id);
+    assert_eq!(records[0].1[0].author, presentation.creator);
+}
+
+#[test]
+fn hidden_questions_stay_hidden_when_imported() {
+    let presentation = Presentation::new(Id("presenter@example.com".to_string()), "Lifetimes".to_string());
+    let original = Question::new(presentation.id.clone(), "What is 'static?".to_string());
+    let mut rejected = Question::new(presentation.id.clone(), "Buy cheap watches".to_string());
+    rejected.review(QuestionStatus::Rejected);
+    rejected.review_reason = Some("Contains a link".to_string());
+    let mut duplicate = Question::new(presentation.id.clone(), "What does 'static mean?".to_string());
+    duplicate.merged_into = Some(original.id.clone());
+    let entries: Vec<ExportedQuestion> = vec![original, rejected, duplicate].into_iter()
+        .map(|question| ExportedQuestion { question: question, answers: vec![] })
+        .collect();
+
+    for format in vec![Format::Csv, Format::Json] {
+        let mut out = vec![];
+        export::write_header(format, &presentation, &mut out).unwrap();
+        for (index, entry) in entries.iter().enumerate() {
+            export::write_question(format, index == 0, entry, &mut out).unwrap();
+        }
+        export::write_footer(format, &mut out).unwrap();
+
+        let document = import::parse(format, &String::from_utf8(out).unwrap()).unwrap();
+        let target = Presentation::new(Id("presenter@example.com".to_string()), "Lifetimes".to_string());
+        let records = import::records(document, &target);
+        let questions: Vec<&Question> = records.iter().map(|&(ref question, _)| question).collect();
+        assert_eq!(questions.len(), 3);
+        assert!(questions[0].is_visible());
+        assert_eq!(questions[1].status, QuestionStatus::Rejected);
+        assert_eq!(questions[1].review_reason, Some("Contains a link".to_string()));
+        assert!(!questions[1].is_visible());
+        assert_eq!(questions[2].merged_into, Some(questions[0].id.clone()));
+        assert!(questions[0].id != entries[0].question.id);
+        assert!(!questions[2].is_visible());
+    }
+}
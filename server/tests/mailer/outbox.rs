@@ -0,0 +1,23 @@
+use std::fs;
+
+use server::mailer::{Mailer, Message};
+use server::mailer::outbox::OutboxMailer;
+
+
+#[test]
+fn outbox_keeps_every_message_sent() {
+    let outbox_dir = "outbox_keeps_every_message_sent";
+    let mailer = OutboxMailer::new(outbox_dir);
+
+    let first = Message::new("a@example.com".to_string(), "First".to_string(), "one".to_string());
+    let second = Message::new("b@example.com".to_string(), "Second".to_string(), "two".to_string());
+    mailer.send(first).unwrap();
+    mailer.send(second).unwrap();
+
+    let sent = mailer.messages().unwrap();
+    assert_eq!(sent.len(), 2);
+    assert_eq!(sent[0].to, "a@example.com");
+    assert_eq!(sent[1].subject, "Second");
+
+    fs::remove_dir_all(outbox_dir).unwrap();
+}
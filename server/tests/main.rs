@@ -1,10 +1,28 @@
+extern crate base64;
 extern crate chrono;
 extern crate iron;
+extern crate png;
+extern crate qrcode;
+extern crate ring;
+extern crate router;
 extern crate rusqlite as sqlite;
+#[macro_use] extern crate serde_json;
 extern crate serde_json as json;
+extern crate untrusted;
+extern crate url;
 #[macro_use] extern crate server_lib as server;
 
 #[cfg(test)] extern crate iron_test;
 
 mod api;
+mod auth;
 mod capabilities;
+mod export;
+mod filters;
+mod import;
+mod mailer;
+mod qr;
+mod ratelimit;
+mod report;
+mod scheduler;
+mod similarity;
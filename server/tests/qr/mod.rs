@@ -0,0 +1,71 @@
+use png;
+use qrcode::{Color, QrCode};
+
+use server::qr;
+
+
+const JOIN_URL: &'static str = "http://127.0.0.1:9001/#join/ABC234";
+
+/// The margin and module size `qr` renders with.
+const QUIET_ZONE: usize = 4;
+const PNG_MODULE_SIZE: usize = 8;
+
+
+/// The modules, row by row, that a code for `data` must contain, and its width.
+fn expected_modules(data: &str) -> (usize, Vec<bool>) {
+    let code = QrCode::new(data.as_bytes()).unwrap();
+    let modules = code.to_colors().into_iter().map(|color| color == Color::Dark).collect();
+    (code.width(), modules)
+}
+
+#[test]
+fn renders_svg_documents() {
+    let svg = qr::svg(JOIN_URL).unwrap();
+    assert!(svg.starts_with("<?xml"));
+    assert!(svg.contains("<svg"));
+    assert!(svg.trim_right().ends_with("</svg>"));
+    assert_eq!(svg, qr::svg(JOIN_URL).unwrap());
+}
+
+#[test]
+fn svg_documents_draw_the_code_for_their_data() {
+    let svg = qr::svg(JOIN_URL).unwrap();
+    let (width, expected) = expected_modules(JOIN_URL);
+    let path = svg.split("<path d=\"").nth(1).unwrap().split('"').next().unwrap();
+    let mut drawn = vec![false; width * width];
+    for command in path.split('M').skip(1) {
+        let mut coordinates = command.split(|c| c == ',' || c == 'h')
+            .take(2)
+            .map(|n| n.parse::<usize>().unwrap() - QUIET_ZONE);
+        let (x, y) = (coordinates.next().unwrap(), coordinates.next().unwrap());
+        drawn[y * width + x] = true;
+    }
+    assert_eq!(drawn, expected);
+    assert!(drawn != expected_modules("http://127.0.0.1:9001/#join/ABC235").1);
+}
+
+#[test]
+fn renders_png_images() {
+    let png = qr::png(JOIN_URL).unwrap();
+    assert_eq!(&png[..8], &[0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n']);
+}
+
+#[test]
+fn png_images_draw_the_code_for_their_data() {
+    let image = qr::png(JOIN_URL).unwrap();
+    let (info, mut reader) = png::Decoder::new(&image[..]).read_info().unwrap();
+    let mut pixels = vec![0; info.buffer_size()];
+    reader.next_frame(&mut pixels).unwrap();
+
+    let (width, expected) = expected_modules(JOIN_URL);
+    assert_eq!(info.width as usize, (width + 2 * QUIET_ZONE) * PNG_MODULE_SIZE);
+    let size = info.width as usize;
+    let drawn: Vec<bool> = (0..width * width)
+        .map(|index| {
+            let x = (index % width + QUIET_ZONE) * PNG_MODULE_SIZE + PNG_MODULE_SIZE / 2;
+            let y = (index / width + QUIET_ZONE) * PNG_MODULE_SIZE + PNG_MODULE_SIZE / 2;
+            pixels[y * size + x] == 0
+        })
+        .collect();
+    assert_eq!(drawn, expected);
+}
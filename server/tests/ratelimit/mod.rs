@@ -0,0 +1,52 @@
+use chrono::Duration;
+use chrono::prelude::*;
+
+use server::models::{Budget, TokenBucket};
+use server::ratelimit::{BucketStore, MemoryBuckets};
+
+
+#[test]
+fn buckets_allow_bursts_then_refill_over_time() {
+    let budget = Budget::new(2, 6);
+    let start = Utc::now();
+    let mut bucket = TokenBucket::full("ask:presentation:ip:127.0.0.1".to_string(), budget, start);
+
+    assert!(bucket.take(budget, start).is_ok());
+    assert!(bucket.take(budget, start).is_ok());
+    assert_eq!(bucket.take(budget, start), Err(10));
+    assert_eq!(bucket.take(budget, start + Duration::seconds(4)), Err(6));
+    assert!(bucket.take(budget, start + Duration::seconds(10)).is_ok());
+}
+
+#[test]
+fn memory_buckets_are_kept_apart_by_key() {
+    let buckets = MemoryBuckets::new();
+    let budget = Budget::new(1, 1);
+    let now = Utc::now();
+
+    assert_eq!(buckets.take("ask:one:ip:10.0.0.1", budget, now).unwrap(), Ok(()));
+    assert_eq!(buckets.take("ask:one:ip:10.0.0.1", budget, now).unwrap(), Err(60));
+    assert_eq!(buckets.take("ask:one:ip:10.0.0.2", budget, now).unwrap(), Ok(()));
+    assert_eq!(buckets.take("nod:one:ip:10.0.0.1", budget, now).unwrap(), Ok(()));
+}
+
+#[test]
+fn memory_buckets_discard_idle_buckets_once_there_are_too_many() {
+    let buckets = MemoryBuckets::new();
+    let budget = Budget::new(1, 1);
+    let start = Utc::now();
+    for client in 0..10000 {
+        buckets.take(&format!("ask:one:ip:{}", client), budget, start).unwrap().unwrap();
+    }
+    assert_eq!(buckets.bucket_count(), 10000);
+
+    let later = start + Duration::minutes(61);
+    assert_eq!(buckets.take("ask:one:ip:late", budget, later).unwrap(), Ok(()));
+    assert_eq!(buckets.bucket_count(), 1);
+}
+
+#[test]
+fn shared_budgets_scale_with_their_clients() {
+    assert_eq!(Budget::new(5, 2).shared_by(25), Budget::new(125, 50));
+    assert_eq!(Budget::new(u32::max_value(), 1).shared_by(2).capacity, u32::max_value());
+}
@@ -0,0 +1,21 @@
+use server::export::ExportedQuestion;
+use server::models::{Answer, Id, Presentation, Question};
+use server::report::{self, Summary};
+
+
+#[test]
+fn reports_are_pdf_documents_with_a_summary() {
+    let presentation = Presentation::new(Id("presenter@example.com".to_string()), "Week 6: Traits".to_string());
+    let mut entries = vec![];
+    for nods in 0..40 {
+        let mut question = Question::new(presentation.id.clone(), format!("Question number {} about trait objects?", nods));
+        question.nods = nods;
+        question.answered = nods % 2 == 0;
+        let answer = Answer::new(presentation.creator.clone(), question.id.clone(), "Ünïcödé answers ".repeat(20));
+        entries.push(ExportedQuestion { question: question, answers: vec![answer] });
+    }
+
+    assert_eq!(Summary::of(&entries), Summary { questions: 40, answered: 20, nods: 780 });
+    let pdf = report::render(&presentation, &entries).unwrap();
+    assert!(pdf.starts_with(b"%PDF-"));
+}
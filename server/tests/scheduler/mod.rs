@@ -0,0 +1,127 @@
+use std::sync::{Arc, Mutex};
+
+use chrono::Duration;
+use chrono::prelude::*;
+use iron::status;
+use sqlite::Connection;
+
+use server::api::questions::ask::AskHandler;
+use server::capabilities::{Capability, FindAll, Save, Search};
+use server::capabilities::initializers::init_sqlite_tables;
+use server::capabilities::sqlite::{QuestionsForPresentation, SQLite};
+use server::events::{EventKind, EventLog};
+use server::models::{Id, Presentation, Question};
+use server::scheduler::{Clock, Scheduler};
+
+use api::{post, setup_db};
+
+
+struct ManualClock(Mutex<DateTime<Utc>>);
+
+impl ManualClock {
+    fn advance(&self, by: Duration) {
+        let mut now = self.0.lock().unwrap();
+        *now = *now + by;
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> DateTime<Utc> {
+        *self.0.lock().unwrap()
+    }
+}
+
+
+#[test]
+fn presentations_open_and_close_on_schedule() {
+    let db = SQLite::new(Arc::new(Mutex::new(Connection::open_in_memory().unwrap())));
+    init_sqlite_tables(&db).unwrap();
+    let start = Utc.ymd(2018, 11, 5).and_hms(13, 0, 0);
+    let clock = Arc::new(ManualClock(Mutex::new(start)));
+    let events = Arc::new(EventLog::new());
+
+    let mut presentation = Presentation::new(Id("scheduler@example.com".to_string()), "Office hours".to_string());
+    presentation.close();
+    presentation.set_schedule(Some(start + Duration::minutes(10)), Some(start + Duration::minutes(70))).unwrap();
+    let presentation = db.perform(Save(presentation)).unwrap();
+    let id = presentation.id.clone();
+    let find = || db.perform(Search(Presentation::search_parameter(id.clone()))).unwrap();
+
+    let scheduler = Scheduler::new(db.clone(), clock.clone(), events.clone());
+    assert!(scheduler.tick().unwrap().is_empty());
+    clock.advance(Duration::minutes(10));
+    assert_eq!(scheduler.tick().unwrap(), vec![id.clone()]);
+    let opened = find();
+    assert!(opened.is_open_to_questions && opened.join_code.is_some());
+    assert_eq!(opened.opens_at, None);
+
+    // A scheduler started after the closing time, as if the server had been down, still closes it.
+    let restarted = Scheduler::new(db.clone(), clock.clone(), events.clone());
+    clock.advance(Duration::hours(2));
+    assert_eq!(restarted.tick().unwrap(), vec![id.clone()]);
+    let closed = find();
+    assert!(!closed.is_open_to_questions);
+    assert_eq!(closed.closes_at, None);
+    assert!(restarted.tick().unwrap().is_empty());
+
+    let (emitted, latest) = events.since(&id, 0).unwrap();
+    assert_eq!(emitted.iter().map(|event| event.kind).collect::<Vec<_>>(), vec![EventKind::Opened, EventKind::Closed]);
+    assert_eq!(latest, 2);
+    assert_eq!(events.since(&id, 1).unwrap().0.len(), 1);
+}
+
+#[test]
+fn questions_can_only_be_asked_while_presentations_are_open() {
+    let db = setup_db();
+    let start = Utc.ymd(2018, 11, 5).and_hms(13, 0, 0);
+    let clock = Arc::new(ManualClock(Mutex::new(start)));
+    let scheduler = Scheduler::new(db.clone(), clock.clone(), Arc::new(EventLog::new()));
+
+    let mut presentation = Presentation::new(Id("scheduler@example.com".to_string()), "Office hours".to_string());
+    presentation.close();
+    presentation.set_schedule(Some(start + Duration::minutes(10)), Some(start + Duration::minutes(70))).unwrap();
+    let presentation = db.perform(Save(presentation)).unwrap();
+    let handler = AskHandler::new(db.clone());
+    let ask = || post(&handler, json!({ "presentation": presentation.id.0, "question": "Is this on the exam?" }));
+
+    let (status, body) = ask();
+    assert_eq!(status, status::Forbidden);
+    assert_eq!(body["error"], json!("This presentation is closed to questions."));
+    clock.advance(Duration::minutes(10));
+    scheduler.tick().unwrap();
+    assert_eq!(ask().0, status::Ok);
+    clock.advance(Duration::hours(1));
+    scheduler.tick().unwrap();
+    assert_eq!(ask().0, status::Forbidden);
+}
+
+#[test]
+fn questions_are_deleted_once_they_are_no_longer_kept() {
+    let db = SQLite::new(Arc::new(Mutex::new(Connection::open_in_memory().unwrap())));
+    init_sqlite_tables(&db).unwrap();
+    let start = Utc.ymd(2018, 11, 5).and_hms(13, 0, 0);
+    let clock = Arc::new(ManualClock(Mutex::new(start)));
+    let scheduler = Scheduler::new(db.clone(), clock.clone(), Arc::new(EventLog::new()));
+
+    let presenter = Id("retention@example.com".to_string());
+    let mut kept = Presentation::new(presenter.clone(), "Kept for a week".to_string());
+    kept.set_retention(Some(7)).unwrap();
+    assert!(kept.set_retention(Some(0)).is_err());
+    let kept = db.perform(Save(kept)).unwrap();
+    let forever = db.perform(Save(Presentation::new(presenter, "Kept forever".to_string()))).unwrap();
+    for (days_ago, presentation) in vec![(10, &kept), (3, &kept), (10, &forever)] {
+        let mut question = Question::new(presentation.id.clone(), format!("Asked {} days ago", days_ago));
+        question.ask_date = start - Duration::days(days_ago);
+        db.perform(Save(question)).unwrap();
+    }
+    let questions = |presentation: &Presentation| db.perform(FindAll(QuestionsForPresentation {
+        presentation_id: presentation.id.clone(),
+    })).unwrap();
+
+    assert_eq!(scheduler.expire_questions().unwrap(), 1);
+    assert_eq!(questions(&kept).len(), 1);
+    assert_eq!(questions(&forever).len(), 1);
+    clock.advance(Duration::days(5));
+    assert_eq!(scheduler.expire_questions().unwrap(), 1);
+    assert!(questions(&kept).is_empty());
+}
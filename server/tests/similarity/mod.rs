@@ -0,0 +1,25 @@
+use server::models::{Id, Question};
+use server::similarity;
+
+
+#[test]
+fn rephrased_questions_are_similar() {
+    let threshold = similarity::DUPLICATE_THRESHOLD;
+    assert!(similarity::similarity("Will the slides be shared?", "Are you sharing the slides") >= threshold);
+    assert!(similarity::similarity("Will the slides be shared?", "When is the exam?") < threshold);
+    assert_eq!(similarity::similarity("what is it?", "who is it?"), 0.0);
+}
+
+#[test]
+fn duplicates_are_found_most_similar_first() {
+    let presentation = Id("lecture".to_string());
+    let questions = vec![
+        Question::new(presentation.clone(), "When is the exam?".to_string()),
+        Question::new(presentation.clone(), "Where can I download the lecture slides?".to_string()),
+        Question::new(presentation.clone(), "Will the slides be shared?".to_string()),
+    ];
+
+    let duplicates = similarity::find_duplicates("Are the slides shared anywhere?", &questions, 3);
+    assert_eq!(duplicates.len(), 1);
+    assert_eq!(duplicates[0].text, "Will the slides be shared?");
+}